@@ -0,0 +1,125 @@
+/*
+ * C interface to rust-chess, generated with cbindgen:
+ *
+ *   cbindgen --config cbindgen.toml --output include/rust_chess.h
+ *
+ * Build the shared library with `cargo build --release --features ffi`.
+ *
+ * Every function returning a ChessErrorCode leaves its out parameters untouched
+ * on failure, except chess_game_legal_moves which always writes out_count.
+ * Strings passed in must be nul terminated utf-8 and are only borrowed for the
+ * duration of the call. GameState handles are owned by the caller and must be
+ * released with chess_game_free exactly once. A handle must not be used from
+ * two threads at the same time.
+ */
+
+#ifndef RUST_CHESS_H
+#define RUST_CHESS_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+#define CHESS_UCI_MOVE_LEN 6
+
+#define CHESS_MAX_MOVES 256
+
+#define CHESS_DEFAULT_SEARCH_DEPTH 4
+
+typedef enum ChessErrorCode {
+  ChessErrorCode_Ok = 0,
+  ChessErrorCode_NullPointer = 1,
+  ChessErrorCode_InvalidUtf8 = 2,
+  ChessErrorCode_BufferTooSmall = 3,
+  ChessErrorCode_InvalidMoveNotation = 4,
+  ChessErrorCode_InvalidFenString = 100,
+  ChessErrorCode_InvalidActiveColor = 101,
+  ChessErrorCode_InvalidCastleRights = 102,
+  ChessErrorCode_InvalidEnPassant = 103,
+  ChessErrorCode_InvalidBoardString = 104,
+  ChessErrorCode_InvalidMoveCounter = 105,
+  ChessErrorCode_InvalidPosition = 106,
+  ChessErrorCode_GameOver = 200,
+  ChessErrorCode_NoPieceAtOrigin = 201,
+  ChessErrorCode_NotCurrentTurn = 202,
+  ChessErrorCode_UnexpectedCapture = 203,
+  ChessErrorCode_CastleWhileInCheck = 204,
+  ChessErrorCode_MoveIntoCheck = 205,
+  ChessErrorCode_StillInCheck = 206,
+  ChessErrorCode_IllegalMove = 207,
+} ChessErrorCode;
+
+typedef enum ChessColor {
+  ChessColor_White = 0,
+  ChessColor_Black = 1,
+} ChessColor;
+
+typedef enum ChessGameStatus {
+  ChessGameStatus_New = 0,
+  ChessGameStatus_InProgress = 1,
+  ChessGameStatus_Check = 2,
+  ChessGameStatus_Checkmate = 3,
+  ChessGameStatus_Stalemate = 4,
+  ChessGameStatus_Draw = 5,
+  ChessGameStatus_TimeForfeit = 6,
+} ChessGameStatus;
+
+typedef struct GameState GameState;
+
+typedef struct ChessUciMove {
+  char uci[CHESS_UCI_MOVE_LEN];
+} ChessUciMove;
+
+typedef struct ChessSearchLimits {
+  uint8_t depth;
+  uint64_t nodes;
+  uint64_t move_time_ms;
+} ChessSearchLimits;
+
+typedef struct ChessSearchResult {
+  struct ChessUciMove best_move;
+  int32_t score;
+  uint8_t depth;
+  uint64_t nodes;
+} ChessSearchResult;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+const char *chess_error_message(int code);
+
+struct GameState *chess_game_new(void);
+
+void chess_game_free(struct GameState *game);
+
+enum ChessErrorCode chess_game_from_fen(const char *fen, struct GameState **out_game);
+
+enum ChessErrorCode chess_game_load_fen(struct GameState *game, const char *fen);
+
+enum ChessErrorCode chess_game_fen(const struct GameState *game, char *buffer, size_t buffer_len);
+
+enum ChessErrorCode chess_game_legal_moves(const struct GameState *game,
+                                           struct ChessUciMove *out_moves,
+                                           size_t capacity,
+                                           size_t *out_count);
+
+enum ChessErrorCode chess_game_make_move(struct GameState *game, const char *uci);
+
+enum ChessErrorCode chess_game_status(const struct GameState *game,
+                                      enum ChessGameStatus *out_status);
+
+enum ChessErrorCode chess_game_active_color(const struct GameState *game,
+                                            enum ChessColor *out_color);
+
+enum ChessErrorCode chess_game_search(const struct GameState *game,
+                                      struct ChessSearchLimits limits,
+                                      struct ChessSearchResult *out_result);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* RUST_CHESS_H */
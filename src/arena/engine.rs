@@ -0,0 +1,181 @@
+use crate::chess_move::chess_move::ChessMove;
+use crate::search::alpha_beta::{
+    is_mate_score, is_tablebase_score, INFINITY, MATE_SCORE, TABLEBASE_WIN_SCORE,
+};
+use crate::search::search_handle::SearchHandle;
+use crate::search::search_limits::SearchLimits;
+use crate::search::search_options::SearchOptions;
+use crate::state::evaluate_game_state::find_book_move;
+use crate::state::game_state::GameState;
+use thiserror::Error;
+
+pub const DEFAULT_ENGINE_DEPTH: u8 = 3;
+// what a mate counts as when adjudicating by score
+pub const MATE_CENTIPAWNS: i32 = 100_000;
+// evaluate_game_state scores a pawn as 2
+const CENTIPAWNS_PER_SCORE_UNIT: i32 = 50;
+
+#[derive(Error, Debug)]
+pub enum EngineError {
+    #[error("Failed to start engine {0}: {1}")]
+    Spawn(String, std::io::Error),
+    #[error("Engine io failed: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Engine {0} exited unexpectedly")]
+    Exited(String),
+    #[error("Engine {0} did not respond in time waiting for {1}")]
+    Timeout(String, &'static str),
+    #[error("Engine {0} sent an invalid move: {1}")]
+    InvalidMove(String, String),
+    #[error("Engine {0} found no move")]
+    NoMove(String),
+}
+
+// from the point of view of the side to move
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum EngineScore {
+    Centipawns(i32),
+    // negative when the side to move gets mated
+    Mate(i32),
+}
+
+impl EngineScore {
+    pub const fn as_centipawns(&self) -> i32 {
+        match *self {
+            Self::Centipawns(centipawns) => centipawns,
+            Self::Mate(moves) if moves < 0 => -MATE_CENTIPAWNS,
+            Self::Mate(_) => MATE_CENTIPAWNS,
+        }
+    }
+    pub const fn from_search_score(score: i32) -> Self {
+        let mate_plies = if is_mate_score(score) {
+            Some(MATE_SCORE - score.abs())
+        } else if is_tablebase_score(score) {
+            Some(TABLEBASE_WIN_SCORE - score.abs())
+        } else {
+            None
+        };
+        match mate_plies {
+            // plies from the root rounded up to full moves
+            Some(plies) => Self::Mate((plies + 1) / 2 * score.signum()),
+            // a loss on time carries no distance, so only the sign is meaningful
+            None if score == INFINITY || score == -INFINITY => Self::Mate(score.signum()),
+            None => Self::Centipawns(score.saturating_mul(CENTIPAWNS_PER_SCORE_UNIT)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EngineMove {
+    pub chess_move: ChessMove,
+    pub score: Option<EngineScore>,
+}
+
+// the game so far, uci engines want the start position and the moves rather than the result
+pub struct EnginePosition<'a> {
+    pub start: &'a GameState,
+    pub moves: &'a [ChessMove],
+    pub game_state: &'a GameState,
+}
+
+pub trait Engine {
+    fn name(&self) -> &str;
+    fn new_game(&mut self) -> Result<(), EngineError>;
+    fn best_move(&mut self, position: &EnginePosition) -> Result<EngineMove, EngineError>;
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BuiltinEngine {
+    pub name: String,
+    pub limits: SearchLimits,
+    pub search_options: SearchOptions,
+}
+
+impl BuiltinEngine {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            limits: SearchLimits {
+                depth: Some(DEFAULT_ENGINE_DEPTH),
+                ..SearchLimits::new()
+            },
+            search_options: SearchOptions::selective(),
+        }
+    }
+}
+
+impl Engine for BuiltinEngine {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn new_game(&mut self) -> Result<(), EngineError> {
+        Ok(())
+    }
+    fn best_move(&mut self, position: &EnginePosition) -> Result<EngineMove, EngineError> {
+        if let Some(chess_move) = self
+            .search_options
+            .book
+            .and_then(|book| find_book_move(position.game_state, book, self.search_options))
+        {
+            return Ok(EngineMove {
+                chess_move,
+                score: None,
+            });
+        }
+        let result =
+            SearchHandle::start(position.game_state, self.limits, self.search_options).join();
+        let chess_move = result
+            .best_move
+            .ok_or_else(|| EngineError::NoMove(self.name.clone()))?;
+        // a search that never finished a depth has no score worth reporting
+        let score = (result.depth > 0).then(|| EngineScore::from_search_score(result.score));
+        Ok(EngineMove { chess_move, score })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::position::*;
+    use crate::notation::fen::deserialize;
+    use crate::search::alpha_beta::{mated_score, tablebase_score};
+    use crate::tablebase::tablebase::TablebaseValue;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(2, EngineScore::Centipawns(100))]
+    #[case(-7, EngineScore::Centipawns(-350))]
+    #[case(tablebase_score(TablebaseValue::Win(5), 2), EngineScore::Mate(4))]
+    #[case(-tablebase_score(TablebaseValue::Loss(4), 1), EngineScore::Mate(3))]
+    #[case(tablebase_score(TablebaseValue::Loss(6), 2), EngineScore::Mate(-4))]
+    #[case(INFINITY, EngineScore::Mate(1))]
+    #[case(-INFINITY, EngineScore::Mate(-1))]
+    #[case(MATE_SCORE - 1, EngineScore::Mate(1))]
+    #[case(MATE_SCORE - 5, EngineScore::Mate(3))]
+    #[case(mated_score(4), EngineScore::Mate(-2))]
+    fn test_from_search_score(#[case] score: i32, #[case] expected: EngineScore) {
+        assert_eq!(expected, EngineScore::from_search_score(score));
+    }
+
+    #[test]
+    fn test_builtin_engine_best_move() {
+        let game_state =
+            deserialize("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1").expect("bad fen string!");
+        let mut engine = BuiltinEngine::new("builtin");
+        let engine_move = engine
+            .best_move(&EnginePosition {
+                start: &game_state,
+                moves: &[],
+                game_state: &game_state,
+            })
+            .expect("expected a move");
+        assert_eq!(
+            (A1, A8),
+            (engine_move.chess_move.from, engine_move.chess_move.to)
+        );
+        assert_eq!(
+            Some(MATE_CENTIPAWNS),
+            engine_move.score.map(|score| score.as_centipawns())
+        );
+    }
+}
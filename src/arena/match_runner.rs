@@ -0,0 +1,496 @@
+use crate::arena::engine::{Engine, EngineError, EnginePosition, EngineScore};
+use crate::arena::match_stats::{MatchScore, SprtOptions, SprtStatus};
+use crate::chess_move::chess_move_handler::default_chess_move_handler;
+use crate::color::Color;
+use crate::notation::pgn::{PgnGame, PgnResult};
+use crate::notation::san::{legal_moves_with_status, serialize_san};
+use crate::state::game_state::GameState;
+use crate::tablebase::tablebase::{Tablebase, TablebaseValue};
+use std::sync::Arc;
+
+pub const DEFAULT_MATCH_GAMES: u32 = 2;
+const DEFAULT_MAX_PLIES: u32 = 400;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AdjudicationOptions {
+    // a side loses once both engines have scored it at least this far behind for resign_moves moves each
+    pub resign_score: Option<i32>,
+    pub resign_moves: u32,
+    // a draw once both engines have scored within this of 0 for draw_moves moves each
+    pub draw_score: Option<i32>,
+    pub draw_moves: u32,
+    // no draw adjudication before this move number
+    pub draw_move_number: u16,
+    pub max_plies: Option<u32>,
+}
+
+impl AdjudicationOptions {
+    pub const fn new() -> Self {
+        Self {
+            resign_score: None,
+            resign_moves: 3,
+            draw_score: None,
+            draw_moves: 8,
+            draw_move_number: 40,
+            max_plies: Some(DEFAULT_MAX_PLIES),
+        }
+    }
+}
+
+impl Default for AdjudicationOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MatchOptions {
+    pub games: u32,
+    pub event: String,
+    pub adjudication: AdjudicationOptions,
+    pub tablebase: Option<Arc<Tablebase>>,
+    // stops the match as soon as the test concludes
+    pub sprt: Option<SprtOptions>,
+}
+
+impl MatchOptions {
+    pub fn new() -> Self {
+        Self {
+            games: DEFAULT_MATCH_GAMES,
+            event: "Engine match".to_string(),
+            adjudication: AdjudicationOptions::new(),
+            tablebase: None,
+            sprt: None,
+        }
+    }
+}
+
+impl Default for MatchOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Termination {
+    Normal,
+    ResignAdjudication,
+    DrawAdjudication,
+    TablebaseAdjudication,
+    MaxPlies,
+    IllegalMove,
+}
+
+impl Termination {
+    // the standard values of the pgn termination tag
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::ResignAdjudication
+            | Self::DrawAdjudication
+            | Self::TablebaseAdjudication
+            | Self::MaxPlies => "adjudication",
+            Self::IllegalMove => "rules infraction",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct GameRecord {
+    pub round: u32,
+    pub white: String,
+    pub black: String,
+    pub start: GameState,
+    // san
+    pub moves: Vec<String>,
+    pub result: PgnResult,
+    pub termination: Termination,
+}
+
+impl GameRecord {
+    pub fn to_pgn_game(&self, event: &str) -> PgnGame {
+        let mut game = PgnGame::new();
+        game.set_tag("Event", event);
+        game.set_tag("Round", &self.round.to_string());
+        game.set_tag("White", &self.white);
+        game.set_tag("Black", &self.black);
+        game.set_tag("Result", self.result.as_str());
+        game.set_movetext(&self.start, self.moves.clone());
+        game.set_tag("Termination", self.termination.as_str());
+        game.result = self.result;
+        game
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MatchReport {
+    // from the point of view of the first engine
+    pub score: MatchScore,
+    pub sprt_status: Option<SprtStatus>,
+    pub llr: Option<f64>,
+}
+
+const fn win_for(color: Color) -> PgnResult {
+    match color {
+        Color::White => PgnResult::WhiteWins,
+        Color::Black => PgnResult::BlackWins,
+    }
+}
+
+const fn tablebase_result(value: TablebaseValue, active_color: Color) -> PgnResult {
+    match value {
+        TablebaseValue::Win(_) => win_for(active_color),
+        TablebaseValue::Loss(_) => win_for(active_color.as_inverse()),
+        TablebaseValue::Draw => PgnResult::Draw,
+    }
+}
+
+// white's point of view of every move played, None when the engine gave no score
+struct Adjudicator<'a> {
+    options: &'a AdjudicationOptions,
+    white_scores: Vec<Option<i32>>,
+}
+
+impl<'a> Adjudicator<'a> {
+    const fn new(options: &'a AdjudicationOptions) -> Self {
+        Self {
+            options,
+            white_scores: Vec::new(),
+        }
+    }
+    fn record(&mut self, mover: Color, score: Option<EngineScore>) {
+        let white_score = score.map(|score| match mover {
+            Color::White => score.as_centipawns(),
+            Color::Black => score.as_centipawns().saturating_neg(),
+        });
+        self.white_scores.push(white_score);
+    }
+    // the last moves_each moves of both engines, if they all have a score
+    fn recent_scores(&self, moves_each: u32) -> Option<Vec<i32>> {
+        let plies = moves_each as usize * 2;
+        if plies == 0 || self.white_scores.len() < plies {
+            return None;
+        }
+        self.white_scores[self.white_scores.len() - plies..]
+            .iter()
+            .copied()
+            .collect()
+    }
+    fn verdict(&self, game_state: &GameState) -> Option<(PgnResult, Termination)> {
+        if let Some(resign_score) = self.options.resign_score {
+            let scores = self.recent_scores(self.options.resign_moves);
+            if let Some(scores) = scores {
+                if scores.iter().all(|score| *score >= resign_score) {
+                    return Some((PgnResult::WhiteWins, Termination::ResignAdjudication));
+                }
+                if scores.iter().all(|score| *score <= -resign_score) {
+                    return Some((PgnResult::BlackWins, Termination::ResignAdjudication));
+                }
+            }
+        }
+        if let Some(draw_score) = self.options.draw_score {
+            if game_state.move_counter.full_move >= self.options.draw_move_number {
+                let scores = self.recent_scores(self.options.draw_moves);
+                if scores.is_some_and(|scores| scores.iter().all(|score| score.abs() <= draw_score))
+                {
+                    return Some((PgnResult::Draw, Termination::DrawAdjudication));
+                }
+            }
+        }
+        None
+    }
+}
+
+pub fn play_game(
+    white: &mut dyn Engine,
+    black: &mut dyn Engine,
+    start: &GameState,
+    round: u32,
+    options: &MatchOptions,
+) -> Result<GameRecord, EngineError> {
+    white.new_game()?;
+    black.new_game()?;
+    let mut game_state = start.clone();
+    let mut moves = vec![];
+    let mut sans = vec![];
+    let mut adjudicator = Adjudicator::new(&options.adjudication);
+    let (result, termination) = loop {
+        if game_state.game_status.is_game_over() {
+            break (
+                PgnResult::from_game_status(game_state.game_status),
+                Termination::Normal,
+            );
+        }
+        let tablebase_value = options
+            .tablebase
+            .as_ref()
+            .and_then(|tablebase| tablebase.probe(&game_state));
+        if let Some(value) = tablebase_value {
+            break (
+                tablebase_result(value, game_state.active_color),
+                Termination::TablebaseAdjudication,
+            );
+        }
+        if let Some(verdict) = adjudicator.verdict(&game_state) {
+            break verdict;
+        }
+        if options
+            .adjudication
+            .max_plies
+            .is_some_and(|max_plies| moves.len() >= max_plies as usize)
+        {
+            break (PgnResult::Draw, Termination::MaxPlies);
+        }
+        let mover = game_state.active_color;
+        let engine: &mut dyn Engine = match mover {
+            Color::White => &mut *white,
+            Color::Black => &mut *black,
+        };
+        let position = EnginePosition {
+            start,
+            moves: &moves,
+            game_state: &game_state,
+        };
+        let engine_move = match engine.best_move(&position) {
+            Ok(engine_move) => engine_move,
+            Err(EngineError::InvalidMove(_, _)) => {
+                break (win_for(mover.as_inverse()), Termination::IllegalMove)
+            }
+            Err(err) => return Err(err),
+        };
+        // the move handler trusts the move it's given, so engines don't get to pick their own rules
+        let chess_move = engine_move.chess_move;
+        let is_legal = legal_moves_with_status(&game_state)
+            .iter()
+            .any(|(legal, _)| *legal == chess_move);
+        if !is_legal {
+            break (win_for(mover.as_inverse()), Termination::IllegalMove);
+        }
+        sans.push(serialize_san(&game_state, &chess_move));
+        if default_chess_move_handler(&mut game_state, &chess_move, None).is_err() {
+            sans.pop();
+            break (win_for(mover.as_inverse()), Termination::IllegalMove);
+        }
+        moves.push(chess_move);
+        adjudicator.record(mover, engine_move.score);
+    };
+    Ok(GameRecord {
+        round,
+        white: white.name().to_string(),
+        black: black.name().to_string(),
+        start: start.clone(),
+        moves: sans,
+        result,
+        termination,
+    })
+}
+
+// every opening is played twice with colours swapped, the starting position is used without openings
+pub fn run_match(
+    engine_a: &mut dyn Engine,
+    engine_b: &mut dyn Engine,
+    openings: &[GameState],
+    options: Option<MatchOptions>,
+    mut on_game: impl FnMut(&GameRecord, &MatchScore),
+) -> Result<MatchReport, EngineError> {
+    let options = options.unwrap_or_default();
+    let starting_position = [GameState::new()];
+    let openings = if openings.is_empty() {
+        &starting_position[..]
+    } else {
+        openings
+    };
+    let mut score = MatchScore::new();
+    let mut sprt_status = None;
+    for game in 0..options.games {
+        let opening = &openings[(game / 2) as usize % openings.len()];
+        let a_is_white = game % 2 == 0;
+        let record = if a_is_white {
+            play_game(engine_a, engine_b, opening, game + 1, &options)?
+        } else {
+            play_game(engine_b, engine_a, opening, game + 1, &options)?
+        };
+        match (record.result, a_is_white) {
+            (PgnResult::WhiteWins, true) | (PgnResult::BlackWins, false) => score.wins += 1,
+            (PgnResult::WhiteWins, false) | (PgnResult::BlackWins, true) => score.losses += 1,
+            (PgnResult::Draw | PgnResult::Unknown, _) => score.draws += 1,
+        }
+        on_game(&record, &score);
+        if let Some(sprt) = options.sprt {
+            let status = sprt.status(&score);
+            sprt_status = Some(status);
+            if status != SprtStatus::Continue {
+                break;
+            }
+        }
+    }
+    Ok(MatchReport {
+        score,
+        sprt_status,
+        llr: options.sprt.map(|sprt| sprt.llr(&score)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arena::engine::{BuiltinEngine, EngineMove};
+    use crate::notation::fen::deserialize;
+    use rstest::rstest;
+
+    const MATE_IN_ONE: &str = "6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1";
+
+    // plays the first legal move and reports a fixed score, which can change from game to game
+    struct ScriptedEngine {
+        name: String,
+        scores: Vec<Option<i32>>,
+        games: usize,
+    }
+
+    impl ScriptedEngine {
+        fn new(name: &str, scores: Vec<Option<i32>>) -> Self {
+            Self {
+                name: name.to_string(),
+                scores,
+                games: 0,
+            }
+        }
+    }
+
+    impl Engine for ScriptedEngine {
+        fn name(&self) -> &str {
+            &self.name
+        }
+        fn new_game(&mut self) -> Result<(), EngineError> {
+            self.games += 1;
+            Ok(())
+        }
+        fn best_move(&mut self, position: &EnginePosition) -> Result<EngineMove, EngineError> {
+            let (chess_move, _) = legal_moves_with_status(position.game_state)
+                .into_iter()
+                .next()
+                .ok_or_else(|| EngineError::NoMove(self.name.clone()))?;
+            let score = self.scores[(self.games - 1) % self.scores.len()];
+            Ok(EngineMove {
+                chess_move,
+                score: score.map(EngineScore::Centipawns),
+            })
+        }
+    }
+
+    #[test]
+    fn test_play_game_checkmate() {
+        let start = deserialize(MATE_IN_ONE).expect("bad fen string!");
+        let mut white = BuiltinEngine::new("white");
+        let mut black = BuiltinEngine::new("black");
+        let record = play_game(&mut white, &mut black, &start, 1, &MatchOptions::new())
+            .expect("game failed");
+        assert_eq!(vec!["Ra8#"], record.moves);
+        assert_eq!(PgnResult::WhiteWins, record.result);
+        assert_eq!(Termination::Normal, record.termination);
+        let pgn = crate::notation::pgn::serialize(&record.to_pgn_game("test"));
+        assert!(pgn.contains(&format!("[FEN \"{MATE_IN_ONE}\"]")), "{pgn}");
+        assert!(pgn.contains("[Termination \"normal\"]"), "{pgn}");
+    }
+
+    #[rstest]
+    #[case(Some(700), Some(-700), PgnResult::WhiteWins, Termination::ResignAdjudication, 4)]
+    #[case(Some(-700), Some(700), PgnResult::BlackWins, Termination::ResignAdjudication, 4)]
+    #[case(Some(10), Some(-20), PgnResult::Draw, Termination::DrawAdjudication, 6)]
+    #[case(None, None, PgnResult::Draw, Termination::MaxPlies, 10)]
+    fn test_play_game_adjudication(
+        #[case] white_score: Option<i32>,
+        #[case] black_score: Option<i32>,
+        #[case] result: PgnResult,
+        #[case] termination: Termination,
+        #[case] plies: usize,
+    ) {
+        let mut options = MatchOptions::new();
+        options.adjudication = AdjudicationOptions {
+            resign_score: Some(600),
+            resign_moves: 2,
+            draw_score: Some(25),
+            draw_moves: 2,
+            draw_move_number: 4,
+            max_plies: Some(10),
+        };
+        let mut white = ScriptedEngine::new("white", vec![white_score]);
+        let mut black = ScriptedEngine::new("black", vec![black_score]);
+        let record =
+            play_game(&mut white, &mut black, &GameState::new(), 1, &options).expect("game failed");
+        assert_eq!((result, termination), (record.result, record.termination));
+        assert_eq!(plies, record.moves.len());
+    }
+
+    #[test]
+    fn test_play_game_tablebase_adjudication() {
+        let mut tablebase = Tablebase::new();
+        tablebase.generate_by_name("KQvK").expect("valid material");
+        let mut options = MatchOptions::new();
+        options.tablebase = Some(Arc::new(tablebase));
+        let start = deserialize("4k3/8/8/8/8/8/8/3QK3 b - - 0 1").expect("bad fen string!");
+        let mut white = ScriptedEngine::new("white", vec![None]);
+        let mut black = ScriptedEngine::new("black", vec![None]);
+        let record = play_game(&mut white, &mut black, &start, 1, &options).expect("game failed");
+        assert_eq!(PgnResult::WhiteWins, record.result);
+        assert_eq!(Termination::TablebaseAdjudication, record.termination);
+        assert!(record.moves.is_empty());
+    }
+
+    #[test]
+    fn test_run_match_swaps_colours() {
+        let opening = deserialize(MATE_IN_ONE).expect("bad fen string!");
+        let mut engine_a = BuiltinEngine::new("a");
+        let mut engine_b = BuiltinEngine::new("b");
+        let mut players = vec![];
+        let report = run_match(
+            &mut engine_a,
+            &mut engine_b,
+            &[opening],
+            None,
+            |record, _| players.push((record.white.clone(), record.black.clone())),
+        )
+        .expect("match failed");
+        // whoever has white mates in one
+        assert_eq!(
+            vec![
+                ("a".to_string(), "b".to_string()),
+                ("b".to_string(), "a".to_string())
+            ],
+            players
+        );
+        assert_eq!(
+            MatchScore {
+                wins: 1,
+                draws: 0,
+                losses: 1
+            },
+            report.score
+        );
+        assert_eq!(None, report.sprt_status);
+    }
+
+    #[test]
+    fn test_run_match_stops_on_sprt() {
+        let mut options = MatchOptions::new();
+        options.games = 1000;
+        options.sprt = Some(SprtOptions::new(0.0, 10.0));
+        options.adjudication.resign_score = Some(600);
+        options.adjudication.resign_moves = 1;
+        options.adjudication.draw_score = Some(0);
+        options.adjudication.draw_moves = 1;
+        options.adjudication.draw_move_number = 1;
+        // a wins two games out of three by adjudication whatever its colour, and draws the third
+        let mut engine_a = ScriptedEngine::new("a", vec![Some(700), Some(700), Some(0)]);
+        let mut engine_b = ScriptedEngine::new("b", vec![Some(-700), Some(-700), Some(0)]);
+        let mut games = 0;
+        let report = run_match(&mut engine_a, &mut engine_b, &[], Some(options), |_, _| {
+            games += 1
+        })
+        .expect("match failed");
+        assert_eq!(Some(SprtStatus::AcceptH1), report.sprt_status);
+        assert!(games < 1000);
+        assert_eq!(games, report.score.games());
+        assert!(report.llr.is_some_and(|llr| llr >= 2.94));
+    }
+}
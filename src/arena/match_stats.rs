@@ -0,0 +1,195 @@
+use std::fmt::{Display, Formatter};
+
+// two sided 95% confidence
+const CONFIDENCE_Z: f64 = 1.959964;
+
+// expected score for an elo difference
+fn logistic(elo: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-elo / 400.0))
+}
+
+fn elo_from_score(score: f64) -> f64 {
+    -400.0 * (1.0 / score - 1.0).log10()
+}
+
+// from the point of view of the first engine
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct MatchScore {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+impl MatchScore {
+    pub const fn new() -> Self {
+        Self {
+            wins: 0,
+            draws: 0,
+            losses: 0,
+        }
+    }
+    pub const fn games(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+    pub fn score(&self) -> Option<f64> {
+        let games = self.games();
+        (games > 0).then(|| (self.wins as f64 + self.draws as f64 / 2.0) / games as f64)
+    }
+    // variance of a single game's result around the mean score
+    fn variance(&self) -> Option<f64> {
+        let score = self.score()?;
+        let games = self.games() as f64;
+        Some(
+            (self.wins as f64 * (1.0 - score).powi(2)
+                + self.draws as f64 * (0.5 - score).powi(2)
+                + self.losses as f64 * score.powi(2))
+                / games,
+        )
+    }
+    // None while the score is 0% or 100% since the elo difference is unbounded
+    pub fn elo(&self) -> Option<f64> {
+        let score = self.score()?;
+        (score > 0.0 && score < 1.0).then(|| elo_from_score(score))
+    }
+    // half the width of the 95% confidence interval
+    pub fn elo_error_margin(&self) -> Option<f64> {
+        let score = self.score()?;
+        let standard_error = (self.variance()? / self.games() as f64).sqrt();
+        let low = score - CONFIDENCE_Z * standard_error;
+        let high = score + CONFIDENCE_Z * standard_error;
+        if low <= 0.0 || high >= 1.0 {
+            return None;
+        }
+        Some((elo_from_score(high) - elo_from_score(low)) / 2.0)
+    }
+}
+
+impl Display for MatchScore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "+{} ={} -{}", self.wins, self.draws, self.losses)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SprtStatus {
+    // the first engine isn't elo1 stronger
+    AcceptH0,
+    // the first engine is at least elo1 stronger
+    AcceptH1,
+    Continue,
+}
+
+impl Display for SprtStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::AcceptH0 => "H0 accepted (fail)",
+            Self::AcceptH1 => "H1 accepted (pass)",
+            Self::Continue => "continue",
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SprtOptions {
+    pub elo0: f64,
+    pub elo1: f64,
+    pub alpha: f64,
+    pub beta: f64,
+}
+
+impl SprtOptions {
+    pub const fn new(elo0: f64, elo1: f64) -> Self {
+        Self {
+            elo0,
+            elo1,
+            alpha: 0.05,
+            beta: 0.05,
+        }
+    }
+    pub fn lower_bound(&self) -> f64 {
+        (self.beta / (1.0 - self.alpha)).ln()
+    }
+    pub fn upper_bound(&self) -> f64 {
+        ((1.0 - self.beta) / self.alpha).ln()
+    }
+    // the normal approximation of the generalized sprt on logistic elo
+    pub fn llr(&self, match_score: &MatchScore) -> f64 {
+        let (Some(score), Some(variance)) = (match_score.score(), match_score.variance()) else {
+            return 0.0;
+        };
+        if variance <= 0.0 {
+            return 0.0;
+        }
+        let score0 = logistic(self.elo0);
+        let score1 = logistic(self.elo1);
+        match_score.games() as f64 * (score1 - score0) * (2.0 * score - score0 - score1)
+            / (2.0 * variance)
+    }
+    pub fn status(&self, match_score: &MatchScore) -> SprtStatus {
+        let llr = self.llr(match_score);
+        if llr >= self.upper_bound() {
+            SprtStatus::AcceptH1
+        } else if llr <= self.lower_bound() {
+            SprtStatus::AcceptH0
+        } else {
+            SprtStatus::Continue
+        }
+    }
+}
+
+impl Default for SprtOptions {
+    fn default() -> Self {
+        Self::new(0.0, 5.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn match_score(wins: u32, draws: u32, losses: u32) -> MatchScore {
+        MatchScore {
+            wins,
+            draws,
+            losses,
+        }
+    }
+
+    #[rstest]
+    #[case(match_score(60, 20, 20), Some(147.19))]
+    #[case(match_score(10, 10, 10), Some(0.0))]
+    #[case(match_score(20, 20, 60), Some(-147.19))]
+    #[case(match_score(5, 0, 0), None)]
+    #[case(match_score(0, 0, 0), None)]
+    fn test_elo(#[case] match_score: MatchScore, #[case] expected: Option<f64>) {
+        let elo = match_score.elo().map(|elo| (elo * 100.0).round() / 100.0);
+        assert_eq!(expected, elo);
+    }
+
+    #[test]
+    fn test_elo_error_margin() {
+        // 100 games, 40% draws, the margin shrinks with the square root of the games
+        let margin = match_score(30, 40, 30).elo_error_margin().unwrap();
+        assert!((margin - 53.16).abs() < 0.01, "margin {margin}");
+        let margin = match_score(300, 400, 300).elo_error_margin().unwrap();
+        assert!((margin - 16.69).abs() < 0.01, "margin {margin}");
+    }
+
+    #[rstest]
+    #[case(match_score(0, 0, 0), SprtStatus::Continue)]
+    #[case(match_score(60, 40, 40), SprtStatus::Continue)]
+    #[case(match_score(400, 200, 200), SprtStatus::AcceptH1)]
+    #[case(match_score(200, 200, 400), SprtStatus::AcceptH0)]
+    fn test_sprt(#[case] match_score: MatchScore, #[case] expected: SprtStatus) {
+        let sprt = SprtOptions::new(0.0, 10.0);
+        assert_eq!(expected, sprt.status(&match_score));
+    }
+
+    #[test]
+    fn test_sprt_bounds() {
+        let sprt = SprtOptions::default();
+        assert!((sprt.lower_bound() + 2.944).abs() < 0.001);
+        assert!((sprt.upper_bound() - 2.944).abs() < 0.001);
+    }
+}
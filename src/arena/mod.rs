@@ -0,0 +1,8 @@
+pub mod engine;
+pub mod match_runner;
+pub mod match_stats;
+pub mod openings;
+pub mod pairing;
+pub mod standings;
+pub mod tournament;
+pub mod uci_engine;
@@ -0,0 +1,78 @@
+use crate::notation::epd::{deserialize_epd_suite, EpdParsingError};
+use crate::notation::pgn::deserialize::PgnParsingError;
+use crate::notation::pgn::PgnReplayError;
+use crate::state::game_state::GameState;
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum OpeningsError {
+    #[error("Failed to read openings: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid epd opening: {0}")]
+    Epd(#[from] EpdParsingError),
+    #[error("Invalid pgn opening: {0}")]
+    Pgn(#[from] PgnParsingError),
+    #[error("Invalid pgn opening line: {0}")]
+    PgnReplay(#[from] PgnReplayError),
+    #[error("Unknown openings format, expected .epd or .pgn: {0}")]
+    UnknownFormat(String),
+}
+
+// every game in the pgn is played out to its last move
+pub fn openings_from_pgn(pgn: &str) -> Result<Vec<GameState>, OpeningsError> {
+    crate::notation::pgn::deserialize(pgn)?
+        .iter()
+        .map(|game| Ok(game.end_game_state()?))
+        .collect()
+}
+
+pub fn openings_from_epd(epd: &str) -> Result<Vec<GameState>, OpeningsError> {
+    Ok(deserialize_epd_suite(epd)?
+        .into_iter()
+        .map(|epd| epd.game_state)
+        .collect())
+}
+
+pub fn load_openings(path: impl AsRef<Path>) -> Result<Vec<GameState>, OpeningsError> {
+    let path = path.as_ref();
+    let contents = std::fs::read_to_string(path)?;
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("epd") => openings_from_epd(&contents),
+        Some(ext) if ext.eq_ignore_ascii_case("pgn") => openings_from_pgn(&contents),
+        _ => Err(OpeningsError::UnknownFormat(path.display().to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::fen::serialize;
+
+    #[test]
+    fn test_openings_from_pgn() {
+        let pgn = "1. e4 e5 2. Nf3 *\n\n[FEN \"4k3/8/8/8/8/8/4P3/4K3 w - - 0 1\"]\n\n1. e4 *\n";
+        let openings = openings_from_pgn(pgn).expect("valid openings");
+        assert_eq!(
+            vec![
+                "rnbqkbnr/pppp1ppp/8/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2",
+                "4k3/8/8/8/4P3/8/8/4K3 b - e3 0 1",
+            ],
+            openings
+                .iter()
+                .map(|game_state| serialize(game_state).get_str().to_string())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_openings_from_epd() {
+        let epd = "# two openings\nrnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - id \"1.e4\";\n\n4k3/8/8/8/8/8/4P3/4K3 w - -\n";
+        let openings = openings_from_epd(epd).expect("valid openings");
+        assert_eq!(2, openings.len());
+        assert!(matches!(
+            load_openings("openings.txt"),
+            Err(OpeningsError::Io(_))
+        ));
+    }
+}
@@ -0,0 +1,272 @@
+use crate::arena::standings::{Standing, TournamentGame};
+use crate::color::Color;
+use std::collections::HashSet;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Pairing {
+    pub white: usize,
+    pub black: usize,
+}
+
+impl Pairing {
+    pub const fn swapped(&self) -> Self {
+        Self {
+            white: self.black,
+            black: self.white,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SwissRound {
+    pub pairings: Vec<Pairing>,
+    pub bye: Option<usize>,
+}
+
+// berger tables, the last player stays put while the others rotate, with an odd number of
+// players whoever is paired with the missing one sits the round out
+pub fn round_robin_rounds(players: usize) -> Vec<Vec<Pairing>> {
+    if players < 2 {
+        return vec![];
+    }
+    let size = players + players % 2;
+    let mut ring: Vec<usize> = (0..size).collect();
+    let mut rounds = vec![];
+    for round in 0..size - 1 {
+        let mut pairings = vec![];
+        for board in 0..size / 2 {
+            let (a, b) = (ring[board], ring[size - 1 - board]);
+            if a >= players || b >= players {
+                continue;
+            }
+            // every other player passes through each seat once, so only the fixed player's
+            // colours need alternating to keep everyone within one game of even
+            let pairing = Pairing { white: a, black: b };
+            pairings.push(if board == 0 && round % 2 == 1 {
+                pairing.swapped()
+            } else {
+                pairing
+            });
+        }
+        rounds.push(pairings);
+        ring[..size - 1].rotate_right(1);
+    }
+    rounds
+}
+
+struct ColorHistory {
+    // whites minus blacks
+    balance: i32,
+    last: Option<Color>,
+}
+
+fn color_histories(players: usize, games: &[TournamentGame]) -> Vec<ColorHistory> {
+    let mut histories: Vec<ColorHistory> = (0..players)
+        .map(|_| ColorHistory {
+            balance: 0,
+            last: None,
+        })
+        .collect();
+    let mut games = games.to_vec();
+    games.sort_by_key(|game| game.round);
+    for game in games {
+        histories[game.white].balance += 1;
+        histories[game.white].last = Some(Color::White);
+        histories[game.black].balance -= 1;
+        histories[game.black].last = Some(Color::Black);
+    }
+    histories
+}
+
+// whoever has had fewer whites gets white, then whoever had black last, then the higher ranked
+fn allocate_colors(higher: usize, lower: usize, histories: &[ColorHistory]) -> Pairing {
+    let pairing = Pairing {
+        white: higher,
+        black: lower,
+    };
+    let (a, b) = (&histories[higher], &histories[lower]);
+    if a.balance != b.balance {
+        return if a.balance < b.balance {
+            pairing
+        } else {
+            pairing.swapped()
+        };
+    }
+    match (a.last, b.last) {
+        (Some(Color::White), Some(Color::Black) | None) => pairing.swapped(),
+        _ => pairing,
+    }
+}
+
+const fn game_key(a: usize, b: usize) -> (usize, usize) {
+    if a < b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+// the top half of a score group plays the bottom half, anyone left over floats down to the next group
+fn pair_players(
+    remaining: &[(usize, f64)],
+    played: &HashSet<(usize, usize)>,
+) -> Option<Vec<(usize, usize)>> {
+    let Some((&(player, points), rest)) = remaining.split_first() else {
+        return Some(vec![]);
+    };
+    let group = rest
+        .iter()
+        .take_while(|(_, other_points)| *other_points == points)
+        .count();
+    let natural = group.div_ceil(2).saturating_sub(1);
+    let candidates = (natural..group).chain(0..natural).chain(group..rest.len());
+    for ix in candidates {
+        let opponent = rest[ix].0;
+        if played.contains(&game_key(player, opponent)) {
+            continue;
+        }
+        let mut others = rest.to_vec();
+        others.remove(ix);
+        if let Some(mut pairs) = pair_players(&others, played) {
+            pairs.insert(0, (player, opponent));
+            return Some(pairs);
+        }
+    }
+    None
+}
+
+// standings are in rank order, the lowest ranked player without a bye sits out an odd round
+pub fn swiss_round(standings: &[Standing], games: &[TournamentGame]) -> SwissRound {
+    let mut ranking: Vec<(usize, f64)> = standings
+        .iter()
+        .map(|standing| (standing.player, standing.points))
+        .collect();
+    let mut bye = None;
+    if ranking.len() % 2 == 1 {
+        let min_byes = standings.iter().map(|standing| standing.byes).min();
+        let ix = standings
+            .iter()
+            .rposition(|standing| Some(standing.byes) == min_byes)
+            .expect("an odd number of players isn't empty");
+        bye = Some(ranking.remove(ix).0);
+    }
+    let played: HashSet<(usize, usize)> = games
+        .iter()
+        .map(|game| game_key(game.white, game.black))
+        .collect();
+    // once everyone has met there's no avoiding a rematch
+    let pairs = pair_players(&ranking, &played).unwrap_or_else(|| {
+        ranking
+            .chunks(2)
+            .map(|pair| (pair[0].0, pair[1].0))
+            .collect()
+    });
+    let histories = color_histories(standings.len(), games);
+    SwissRound {
+        pairings: pairs
+            .into_iter()
+            .map(|(higher, lower)| allocate_colors(higher, lower, &histories))
+            .collect(),
+        bye,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arena::standings::standings;
+    use crate::notation::pgn::PgnResult;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(2)]
+    #[case(3)]
+    #[case(4)]
+    #[case(7)]
+    #[case(8)]
+    fn test_round_robin_rounds(#[case] players: usize) {
+        let rounds = round_robin_rounds(players);
+        assert_eq!(players + players % 2 - 1, rounds.len());
+        let mut met = HashSet::new();
+        let mut balance = vec![0i32; players];
+        for pairings in &rounds {
+            let mut in_round = HashSet::new();
+            for pairing in pairings {
+                assert!(in_round.insert(pairing.white) && in_round.insert(pairing.black));
+                assert!(met.insert(game_key(pairing.white, pairing.black)));
+                balance[pairing.white] += 1;
+                balance[pairing.black] -= 1;
+            }
+        }
+        assert_eq!(players * (players - 1) / 2, met.len());
+        assert!(
+            balance.iter().all(|balance| balance.abs() <= 1),
+            "{balance:?}"
+        );
+    }
+
+    fn game(round: u32, white: usize, black: usize, result: PgnResult) -> TournamentGame {
+        TournamentGame {
+            round,
+            white,
+            black,
+            result,
+        }
+    }
+
+    #[test]
+    fn test_swiss_first_round() {
+        let standings = standings(5, &[], &[]);
+        let round = swiss_round(&standings, &[]);
+        assert_eq!(Some(4), round.bye);
+        assert_eq!(
+            vec![
+                Pairing { white: 0, black: 2 },
+                Pairing { white: 1, black: 3 }
+            ],
+            round.pairings
+        );
+    }
+
+    #[test]
+    fn test_swiss_avoids_rematches() {
+        let games = [
+            game(1, 0, 2, PgnResult::WhiteWins),
+            game(1, 3, 1, PgnResult::BlackWins),
+        ];
+        let round = swiss_round(&standings(4, &games, &[]), &games);
+        // the winners meet and so do the losers, whoever had black gets white
+        assert_eq!(None, round.bye);
+        assert_eq!(
+            vec![
+                Pairing { white: 1, black: 0 },
+                Pairing { white: 2, black: 3 }
+            ],
+            round.pairings
+        );
+        let games = [
+            games[0],
+            games[1],
+            game(2, 1, 0, PgnResult::Draw),
+            game(2, 2, 3, PgnResult::Draw),
+        ];
+        let round = swiss_round(&standings(4, &games, &[]), &games);
+        for pairing in &round.pairings {
+            assert!(!games
+                .iter()
+                .any(|game| game_key(game.white, game.black)
+                    == game_key(pairing.white, pairing.black)));
+        }
+    }
+
+    #[test]
+    fn test_swiss_bye_rotates() {
+        let games = [game(1, 0, 1, PgnResult::WhiteWins)];
+        let byes = [crate::arena::standings::Bye {
+            round: 1,
+            player: 2,
+        }];
+        let round = swiss_round(&standings(3, &games, &byes), &games);
+        assert_eq!(Some(1), round.bye);
+    }
+}
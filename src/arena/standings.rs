@@ -0,0 +1,285 @@
+use crate::notation::pgn::PgnResult;
+use std::cmp::Ordering;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TournamentGame {
+    pub round: u32,
+    pub white: usize,
+    pub black: usize,
+    pub result: PgnResult,
+}
+
+impl TournamentGame {
+    pub const fn opponent(&self, player: usize) -> Option<usize> {
+        if player == self.white {
+            Some(self.black)
+        } else if player == self.black {
+            Some(self.white)
+        } else {
+            None
+        }
+    }
+    // unfinished games count as draws, like they do in a match
+    pub fn points(&self, player: usize) -> Option<f64> {
+        let white_points = match self.result {
+            PgnResult::WhiteWins => 1.0,
+            PgnResult::BlackWins => 0.0,
+            PgnResult::Draw | PgnResult::Unknown => 0.5,
+        };
+        if player == self.white {
+            Some(white_points)
+        } else if player == self.black {
+            Some(1.0 - white_points)
+        } else {
+            None
+        }
+    }
+}
+
+// a round without an opponent, worth a win
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Bye {
+    pub round: u32,
+    pub player: usize,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Standing {
+    pub player: usize,
+    pub points: f64,
+    pub games: u32,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    pub byes: u32,
+    // the sum of every opponent's points, byes have no opponent and add nothing
+    pub buchholz: f64,
+    // the points of beaten opponents plus half the points of drawn ones
+    pub sonneborn_berger: f64,
+}
+
+impl Standing {
+    const fn new(player: usize) -> Self {
+        Self {
+            player,
+            points: 0.0,
+            games: 0,
+            wins: 0,
+            draws: 0,
+            losses: 0,
+            byes: 0,
+            buchholz: 0.0,
+            sonneborn_berger: 0.0,
+        }
+    }
+}
+
+fn compare_standings(a: &Standing, b: &Standing) -> Ordering {
+    b.points
+        .total_cmp(&a.points)
+        .then(b.buchholz.total_cmp(&a.buchholz))
+        .then(b.sonneborn_berger.total_cmp(&a.sonneborn_berger))
+        .then(a.player.cmp(&b.player))
+}
+
+// ranked by points, then buchholz, then sonneborn-berger, then the order players were entered in
+pub fn standings(players: usize, games: &[TournamentGame], byes: &[Bye]) -> Vec<Standing> {
+    let mut standings: Vec<Standing> = (0..players).map(Standing::new).collect();
+    for game in games {
+        for player in [game.white, game.black] {
+            let points = game.points(player).expect("player is in the game");
+            let standing = &mut standings[player];
+            standing.points += points;
+            standing.games += 1;
+            match points {
+                1.0 => standing.wins += 1,
+                0.0 => standing.losses += 1,
+                _ => standing.draws += 1,
+            }
+        }
+    }
+    for bye in byes {
+        standings[bye.player].points += 1.0;
+        standings[bye.player].byes += 1;
+    }
+    let points: Vec<f64> = standings.iter().map(|standing| standing.points).collect();
+    for game in games {
+        for player in [game.white, game.black] {
+            let opponent = game.opponent(player).expect("player is in the game");
+            let result = game.points(player).expect("player is in the game");
+            standings[player].buchholz += points[opponent];
+            standings[player].sonneborn_berger += points[opponent] * result;
+        }
+    }
+    standings.sort_by(compare_standings);
+    standings
+}
+
+fn result_cell(points: f64) -> &'static str {
+    match points {
+        1.0 => "1",
+        0.0 => "0",
+        _ => "=",
+    }
+}
+
+// players in standings order, with a column per opponent by rank
+pub fn crosstable(names: &[String], games: &[TournamentGame], byes: &[Bye]) -> String {
+    let standings = standings(names.len(), games, byes);
+    let mut ranks = vec![0; names.len()];
+    for (rank, standing) in standings.iter().enumerate() {
+        ranks[standing.player] = rank;
+    }
+    // every game between two players goes in one cell, so a double round robin shows both results
+    let mut cells = vec![vec![String::new(); names.len()]; names.len()];
+    for game in games {
+        for player in [game.white, game.black] {
+            let opponent = game.opponent(player).expect("player is in the game");
+            let points = game.points(player).expect("player is in the game");
+            cells[ranks[player]][ranks[opponent]].push_str(result_cell(points));
+        }
+    }
+    for (rank, row) in cells.iter_mut().enumerate() {
+        row[rank] = "x".to_string();
+    }
+    let cell_width = cells
+        .iter()
+        .flatten()
+        .map(|cell| cell.len())
+        .chain([names.len().to_string().len()])
+        .max()
+        .unwrap_or(1);
+    let name_width = names
+        .iter()
+        .map(|name| name.len())
+        .chain([4])
+        .max()
+        .unwrap_or(4);
+    let rank_width = names.len().to_string().len().max(1);
+    let mut table = format!("{:>rank_width$}  {:<name_width$}", "#", "Name");
+    for rank in 1..=names.len() {
+        table.push_str(&format!(" {rank:>cell_width$}"));
+    }
+    table.push_str(&format!(" {:>5} {:>6} {:>6}\n", "Pts", "Buch", "SB"));
+    for (rank, standing) in standings.iter().enumerate() {
+        table.push_str(&format!(
+            "{:>rank_width$}  {:<name_width$}",
+            rank + 1,
+            names[standing.player]
+        ));
+        for cell in &cells[rank] {
+            table.push_str(&format!(" {cell:>cell_width$}"));
+        }
+        table.push_str(&format!(
+            " {:>5.1} {:>6.1} {:>6.2}\n",
+            standing.points, standing.buchholz, standing.sonneborn_berger
+        ));
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(round: u32, white: usize, black: usize, result: PgnResult) -> TournamentGame {
+        TournamentGame {
+            round,
+            white,
+            black,
+            result,
+        }
+    }
+
+    // 0 beats 1 and 2, 1 draws 2 and beats 3, 2 beats 3, 3 beats 0
+    fn round_robin_games() -> Vec<TournamentGame> {
+        vec![
+            game(1, 0, 1, PgnResult::WhiteWins),
+            game(1, 2, 3, PgnResult::WhiteWins),
+            game(2, 2, 0, PgnResult::BlackWins),
+            game(2, 3, 1, PgnResult::BlackWins),
+            game(3, 0, 3, PgnResult::BlackWins),
+            game(3, 1, 2, PgnResult::Draw),
+        ]
+    }
+
+    #[test]
+    fn test_standings_tie_breaks() {
+        let standings = standings(4, &round_robin_games(), &[]);
+        assert_eq!(
+            vec![0, 1, 2, 3],
+            standings
+                .iter()
+                .map(|standing| standing.player)
+                .collect::<Vec<_>>()
+        );
+        let first = standings[0];
+        assert_eq!(
+            (2.0, 2, 0, 1),
+            (first.points, first.wins, first.draws, first.losses)
+        );
+        // 1 and 2 are level on every tie-break, so the entry order decides
+        let (second, third) = (standings[1], standings[2]);
+        assert_eq!((1.5, 1.5), (second.points, third.points));
+        assert_eq!((4.5, 4.5), (second.buchholz, third.buchholz));
+        assert_eq!(
+            (1.75, 1.75),
+            (second.sonneborn_berger, third.sonneborn_berger)
+        );
+        assert_eq!(1.0, standings[3].points);
+        assert_eq!(5.0, standings[3].buchholz);
+        assert_eq!(2.0, standings[3].sonneborn_berger);
+    }
+
+    #[test]
+    fn test_standings_buchholz_breaks_ties() {
+        let games = [
+            game(1, 0, 1, PgnResult::WhiteWins),
+            game(1, 3, 2, PgnResult::BlackWins),
+            game(2, 2, 0, PgnResult::Draw),
+            game(2, 1, 3, PgnResult::WhiteWins),
+        ];
+        let standings = standings(4, &games, &[]);
+        // 0 and 2 are level on points, 0 met the stronger opponents
+        assert_eq!((1.5, 2.5), (standings[0].points, standings[0].buchholz));
+        assert_eq!((1.5, 1.5), (standings[1].points, standings[1].buchholz));
+        assert_eq!((0, 2), (standings[0].player, standings[1].player));
+    }
+
+    #[test]
+    fn test_standings_bye() {
+        let games = [game(1, 0, 1, PgnResult::Draw)];
+        let byes = [Bye {
+            round: 1,
+            player: 2,
+        }];
+        let standings = standings(3, &games, &byes);
+        assert_eq!(2, standings[0].player);
+        assert_eq!(
+            (1.0, 0, 1, 0.0),
+            (
+                standings[0].points,
+                standings[0].games,
+                standings[0].byes,
+                standings[0].buchholz
+            )
+        );
+        assert_eq!(0.5, standings[1].buchholz);
+    }
+
+    #[test]
+    fn test_crosstable() {
+        let names: Vec<String> = ["alpha", "beta", "gamma", "delta"]
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+        let expected = "\
+#  Name  1 2 3 4   Pts   Buch     SB
+1  alpha x 1 1 0   2.0    4.0   3.00
+2  beta  0 x = 1   1.5    4.5   1.75
+3  gamma 0 = x 1   1.5    4.5   1.75
+4  delta 1 0 0 x   1.0    5.0   2.00
+";
+        assert_eq!(expected, crosstable(&names, &round_robin_games(), &[]));
+    }
+}
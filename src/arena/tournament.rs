@@ -0,0 +1,271 @@
+use crate::arena::engine::{Engine, EngineError};
+use crate::arena::match_runner::{play_game, AdjudicationOptions, GameRecord, MatchOptions};
+use crate::arena::pairing::{round_robin_rounds, swiss_round, Pairing};
+use crate::arena::standings::{crosstable, standings, Bye, Standing, TournamentGame};
+use crate::state::game_state::GameState;
+use crate::tablebase::tablebase::Tablebase;
+use std::sync::Arc;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TournamentFormat {
+    // every cycle has each pair meet once, colours swap from one cycle to the next
+    RoundRobin { cycles: u32 },
+    Swiss { rounds: u32 },
+}
+
+#[derive(Clone, Debug)]
+pub struct TournamentOptions {
+    pub format: TournamentFormat,
+    pub event: String,
+    pub adjudication: AdjudicationOptions,
+    pub tablebase: Option<Arc<Tablebase>>,
+}
+
+impl TournamentOptions {
+    pub fn new() -> Self {
+        Self {
+            format: TournamentFormat::RoundRobin { cycles: 1 },
+            event: "Engine tournament".to_string(),
+            adjudication: AdjudicationOptions::new(),
+            tablebase: None,
+        }
+    }
+}
+
+impl Default for TournamentOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TournamentReport {
+    pub event: String,
+    pub players: Vec<String>,
+    pub games: Vec<TournamentGame>,
+    pub byes: Vec<Bye>,
+    // in the same order as games
+    pub records: Vec<GameRecord>,
+}
+
+impl TournamentReport {
+    pub fn standings(&self) -> Vec<Standing> {
+        standings(self.players.len(), &self.games, &self.byes)
+    }
+    pub fn crosstable(&self) -> String {
+        crosstable(&self.players, &self.games, &self.byes)
+    }
+    pub fn to_pgn(&self) -> String {
+        self.records
+            .iter()
+            .map(|record| crate::notation::pgn::serialize(&record.to_pgn_game(&self.event)))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn engine_pair(
+    engines: &mut [Box<dyn Engine>],
+    pairing: Pairing,
+) -> (&mut dyn Engine, &mut dyn Engine) {
+    assert_ne!(pairing.white, pairing.black, "an engine can't play itself");
+    let (low, high) = engines.split_at_mut(pairing.white.max(pairing.black));
+    let (low, high) = (
+        low[pairing.white.min(pairing.black)].as_mut(),
+        high[0].as_mut(),
+    );
+    if pairing.white < pairing.black {
+        (low, high)
+    } else {
+        (high, low)
+    }
+}
+
+struct TournamentState<'a> {
+    engines: &'a mut [Box<dyn Engine>],
+    openings: &'a [GameState],
+    match_options: MatchOptions,
+    report: TournamentReport,
+}
+
+impl TournamentState<'_> {
+    // every game of a round starts from the same opening
+    fn play_round(
+        &mut self,
+        round: u32,
+        pairings: &[Pairing],
+        on_game: &mut impl FnMut(&GameRecord),
+    ) -> Result<(), EngineError> {
+        let opening = &self.openings[(round as usize - 1) % self.openings.len()];
+        for pairing in pairings {
+            let (white, black) = engine_pair(self.engines, *pairing);
+            let record = play_game(white, black, opening, round, &self.match_options)?;
+            on_game(&record);
+            self.report.games.push(TournamentGame {
+                round,
+                white: pairing.white,
+                black: pairing.black,
+                result: record.result,
+            });
+            self.report.records.push(record);
+        }
+        Ok(())
+    }
+}
+
+// without openings every game starts from the starting position
+pub fn run_tournament(
+    engines: &mut [Box<dyn Engine>],
+    openings: &[GameState],
+    options: Option<TournamentOptions>,
+    mut on_game: impl FnMut(&GameRecord),
+) -> Result<TournamentReport, EngineError> {
+    let options = options.unwrap_or_default();
+    let starting_position = [GameState::new()];
+    let openings = if openings.is_empty() {
+        &starting_position[..]
+    } else {
+        openings
+    };
+    let report = TournamentReport {
+        event: options.event.clone(),
+        players: engines
+            .iter()
+            .map(|engine| engine.name().to_string())
+            .collect(),
+        games: vec![],
+        byes: vec![],
+        records: vec![],
+    };
+    let mut state = TournamentState {
+        engines,
+        openings,
+        match_options: MatchOptions {
+            adjudication: options.adjudication,
+            tablebase: options.tablebase,
+            ..MatchOptions::new()
+        },
+        report,
+    };
+    let players = state.report.players.len();
+    match options.format {
+        TournamentFormat::RoundRobin { cycles } => {
+            let rounds = round_robin_rounds(players);
+            let mut round = 0;
+            for cycle in 0..cycles {
+                for pairings in &rounds {
+                    round += 1;
+                    let pairings: Vec<Pairing> = pairings
+                        .iter()
+                        .map(|pairing| {
+                            if cycle % 2 == 1 {
+                                pairing.swapped()
+                            } else {
+                                *pairing
+                            }
+                        })
+                        .collect();
+                    state.play_round(round, &pairings, &mut on_game)?;
+                }
+            }
+        }
+        TournamentFormat::Swiss { rounds } => {
+            for round in 1..=rounds {
+                let swiss = swiss_round(&state.report.standings(), &state.report.games);
+                if let Some(player) = swiss.bye {
+                    state.report.byes.push(Bye { round, player });
+                }
+                state.play_round(round, &swiss.pairings, &mut on_game)?;
+            }
+        }
+    }
+    Ok(state.report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arena::engine::BuiltinEngine;
+    use crate::notation::fen::deserialize;
+    use crate::notation::pgn::PgnResult;
+    use rstest::rstest;
+
+    // whoever has white mates in one
+    const MATE_IN_ONE: &str = "6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1";
+
+    fn engines(players: usize) -> Vec<Box<dyn Engine>> {
+        (0..players)
+            .map(|player| {
+                let mut engine = BuiltinEngine::new(&format!("engine{player}"));
+                engine.limits.depth = Some(2);
+                Box::new(engine) as Box<dyn Engine>
+            })
+            .collect()
+    }
+
+    #[rstest]
+    #[case(TournamentFormat::RoundRobin { cycles: 1 }, 4, 6, 0)]
+    #[case(TournamentFormat::RoundRobin { cycles: 2 }, 3, 6, 0)]
+    #[case(TournamentFormat::Swiss { rounds: 3 }, 5, 6, 3)]
+    fn test_run_tournament(
+        #[case] format: TournamentFormat,
+        #[case] players: usize,
+        #[case] games: usize,
+        #[case] byes: usize,
+    ) {
+        let mut engines = engines(players);
+        let opening = deserialize(MATE_IN_ONE).expect("bad fen string!");
+        let options = TournamentOptions {
+            format,
+            ..TournamentOptions::new()
+        };
+        let mut played = 0;
+        let report = run_tournament(&mut engines, &[opening], Some(options), |_| played += 1)
+            .expect("tournament failed");
+        assert_eq!(games, played);
+        assert_eq!((games, byes), (report.games.len(), report.byes.len()));
+        assert!(report
+            .games
+            .iter()
+            .all(|game| game.result == PgnResult::WhiteWins));
+        let points: f64 = report
+            .standings()
+            .iter()
+            .map(|standing| standing.points)
+            .sum();
+        assert_eq!((games + byes) as f64, points);
+        for (game, record) in report.games.iter().zip(&report.records) {
+            assert_eq!(report.players[game.white], record.white);
+            assert_eq!(report.players[game.black], record.black);
+        }
+    }
+
+    #[test]
+    fn test_tournament_exports() {
+        let mut engines = engines(2);
+        let opening = deserialize(MATE_IN_ONE).expect("bad fen string!");
+        let options = TournamentOptions {
+            format: TournamentFormat::RoundRobin { cycles: 2 },
+            ..TournamentOptions::new()
+        };
+        let report =
+            run_tournament(&mut engines, &[opening], Some(options), |_| {}).expect("failed");
+        assert_eq!(
+            "\
+#  Name     1  2   Pts   Buch     SB
+1  engine0  x 10   1.0    2.0   1.00
+2  engine1 01  x   1.0    2.0   1.00
+",
+            report.crosstable()
+        );
+        let pgn = report.to_pgn();
+        assert_eq!(2, pgn.matches("[Event \"Engine tournament\"]").count());
+        assert!(pgn.contains("[Round \"2\"]\n[White \"engine1\"]\n[Black \"engine0\"]"));
+        assert_eq!(
+            2,
+            crate::notation::pgn::deserialize(&pgn)
+                .expect("valid pgn")
+                .len()
+        );
+    }
+}
@@ -0,0 +1,294 @@
+use crate::arena::engine::{
+    Engine, EngineError, EngineMove, EnginePosition, EngineScore, DEFAULT_ENGINE_DEPTH,
+};
+use crate::notation::coordinate::{deserialize_coordinate, serialize_coordinate};
+use crate::notation::fen::{serialize, FEN_STARTING_POS};
+use crate::search::search_limits::SearchLimits;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+const DEFAULT_RESPONSE_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct UciEngineConfig {
+    pub name: String,
+    pub command: String,
+    pub args: Vec<String>,
+    // sent as setoption before every game
+    pub options: Vec<(String, String)>,
+    pub limits: SearchLimits,
+    // how long to wait for a reply on top of the move time
+    pub response_timeout: Duration,
+}
+
+impl UciEngineConfig {
+    pub fn new(name: &str, command: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            command: command.to_string(),
+            args: vec![],
+            options: vec![],
+            limits: SearchLimits::new(),
+            response_timeout: DEFAULT_RESPONSE_TIMEOUT,
+        }
+    }
+}
+
+pub struct UciEngine {
+    config: UciEngineConfig,
+    process: Child,
+    stdin: ChildStdin,
+    // lines are read on their own thread so a hung engine can't hang the match
+    lines: Receiver<String>,
+}
+
+fn parse_score(info: &str) -> Option<EngineScore> {
+    let mut tokens = info
+        .split_whitespace()
+        .skip_while(|token| *token != "score");
+    tokens.next()?;
+    let kind = tokens.next()?;
+    let value = tokens.next()?.parse().ok()?;
+    match kind {
+        "cp" => Some(EngineScore::Centipawns(value)),
+        "mate" => Some(EngineScore::Mate(value)),
+        _ => None,
+    }
+}
+
+fn go_command(limits: &SearchLimits) -> String {
+    let mut go = "go".to_string();
+    if let Some(depth) = limits.depth {
+        go.push_str(&format!(" depth {depth}"));
+    }
+    if let Some(nodes) = limits.nodes {
+        go.push_str(&format!(" nodes {nodes}"));
+    }
+    if let Some(move_time) = limits.move_time {
+        go.push_str(&format!(" movetime {}", move_time.as_millis()));
+    }
+    if go == "go" {
+        go.push_str(&format!(" depth {DEFAULT_ENGINE_DEPTH}"));
+    }
+    go
+}
+
+impl UciEngine {
+    pub fn spawn(config: UciEngineConfig) -> Result<Self, EngineError> {
+        let mut process = Command::new(&config.command)
+            .args(&config.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|err| EngineError::Spawn(config.command.clone(), err))?;
+        let stdin = process.stdin.take().expect("stdin is piped");
+        let stdout = process.stdout.take().expect("stdout is piped");
+        let (sender, lines) = channel();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else { break };
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        let mut engine = Self {
+            config,
+            process,
+            stdin,
+            lines,
+        };
+        engine.send("uci")?;
+        engine.wait_for("uciok", engine.config.response_timeout)?;
+        Ok(engine)
+    }
+    fn send(&mut self, command: &str) -> Result<(), EngineError> {
+        writeln!(self.stdin, "{command}")?;
+        self.stdin.flush()?;
+        Ok(())
+    }
+    fn read_line(
+        &self,
+        timeout: Duration,
+        waiting_for: &'static str,
+    ) -> Result<String, EngineError> {
+        self.lines.recv_timeout(timeout).map_err(|err| match err {
+            RecvTimeoutError::Timeout => {
+                EngineError::Timeout(self.config.name.clone(), waiting_for)
+            }
+            RecvTimeoutError::Disconnected => EngineError::Exited(self.config.name.clone()),
+        })
+    }
+    fn wait_for(&self, token: &'static str, timeout: Duration) -> Result<(), EngineError> {
+        while self.read_line(timeout, token)?.trim() != token {}
+        Ok(())
+    }
+    fn is_ready(&mut self) -> Result<(), EngineError> {
+        self.send("isready")?;
+        self.wait_for("readyok", self.config.response_timeout)
+    }
+    fn position_command(position: &EnginePosition) -> String {
+        let fen = serialize(position.start);
+        let mut command = if fen.get_str() == FEN_STARTING_POS {
+            "position startpos".to_string()
+        } else {
+            format!("position fen {}", fen.get_str())
+        };
+        if !position.moves.is_empty() {
+            command.push_str(" moves");
+            for chess_move in position.moves {
+                command.push(' ');
+                command.push_str(&serialize_coordinate(chess_move));
+            }
+        }
+        command
+    }
+}
+
+impl Engine for UciEngine {
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+    fn new_game(&mut self) -> Result<(), EngineError> {
+        for (name, value) in self.config.options.clone() {
+            self.send(&format!("setoption name {name} value {value}"))?;
+        }
+        self.send("ucinewgame")?;
+        self.is_ready()
+    }
+    fn best_move(&mut self, position: &EnginePosition) -> Result<EngineMove, EngineError> {
+        self.send(&Self::position_command(position))?;
+        self.send(&go_command(&self.config.limits))?;
+        let timeout =
+            self.config.response_timeout + self.config.limits.move_time.unwrap_or_default();
+        let mut score = None;
+        loop {
+            let line = self.read_line(timeout, "bestmove")?;
+            let mut tokens = line.split_whitespace();
+            match tokens.next() {
+                Some("info") => score = parse_score(&line).or(score),
+                Some("bestmove") => {
+                    let uci = tokens.next().unwrap_or_default();
+                    let chess_move =
+                        deserialize_coordinate(position.game_state, uci).map_err(|_| {
+                            EngineError::InvalidMove(self.config.name.clone(), uci.to_string())
+                        })?;
+                    return Ok(EngineMove { chess_move, score });
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl Drop for UciEngine {
+    fn drop(&mut self) {
+        // the engine may already be gone, in which case there's nothing left to clean up
+        let _ = self.send("quit");
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::game_state::GameState;
+    use rstest::rstest;
+
+    // answers every go with e2e4, which is all the first move of a game needs
+    #[cfg(unix)]
+    const FAKE_ENGINE: &str = r#"
+while read -r line; do
+  case "$line" in
+    uci) echo "id name fake"; echo "uciok" ;;
+    isready) echo "readyok" ;;
+    go*) echo "info depth 1 score cp 15 pv e2e4"; echo "info depth 2 score cp 21 pv e2e4"; echo "bestmove e2e4" ;;
+    quit) exit 0 ;;
+  esac
+done
+"#;
+
+    #[cfg(unix)]
+    fn fake_engine() -> UciEngine {
+        let mut config = UciEngineConfig::new("fake", "sh");
+        config.args = vec!["-c".to_string(), FAKE_ENGINE.to_string()];
+        UciEngine::spawn(config).expect("failed to start the fake engine")
+    }
+
+    #[rstest]
+    #[case("info depth 5 score cp -31 nodes 1000 pv e2e4", Some(EngineScore::Centipawns(-31)))]
+    #[case("info depth 9 score mate 3 pv a1a8", Some(EngineScore::Mate(3)))]
+    #[case("info depth 9 score lowerbound", None)]
+    #[case("info string hello", None)]
+    fn test_parse_score(#[case] info: &str, #[case] expected: Option<EngineScore>) {
+        assert_eq!(expected, parse_score(info));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_uci_engine_best_move() {
+        let mut engine = fake_engine();
+        engine.new_game().expect("new game failed");
+        let game_state = GameState::new();
+        let engine_move = engine
+            .best_move(&EnginePosition {
+                start: &game_state,
+                moves: &[],
+                game_state: &game_state,
+            })
+            .expect("expected a move");
+        assert_eq!("e2e4", serialize_coordinate(&engine_move.chess_move));
+        assert_eq!(Some(EngineScore::Centipawns(21)), engine_move.score);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_uci_engine_invalid_move() {
+        let mut engine = fake_engine();
+        let start = GameState::new();
+        let mut game_state = start.clone();
+        let e2e4 = deserialize_coordinate(&game_state, "e2e4").unwrap();
+        crate::chess_move::chess_move_handler::default_chess_move_handler(
+            &mut game_state,
+            &e2e4,
+            None,
+        )
+        .unwrap();
+        let moves = [e2e4];
+        let position = EnginePosition {
+            start: &start,
+            moves: &moves,
+            game_state: &game_state,
+        };
+        assert_eq!(
+            "position startpos moves e2e4",
+            UciEngine::position_command(&position)
+        );
+        assert!(matches!(
+            engine.best_move(&position),
+            Err(EngineError::InvalidMove(_, _))
+        ));
+    }
+
+    #[test]
+    fn test_go_command() {
+        let mut limits = SearchLimits::new();
+        assert_eq!("go depth 3", go_command(&limits));
+        limits.nodes = Some(5000);
+        limits.move_time = Some(Duration::from_millis(250));
+        assert_eq!("go nodes 5000 movetime 250", go_command(&limits));
+    }
+
+    #[test]
+    fn test_spawn_missing_engine() {
+        let config = UciEngineConfig::new("missing", "/nonexistent/engine");
+        assert!(matches!(
+            UciEngine::spawn(config),
+            Err(EngineError::Spawn(_, _))
+        ));
+    }
+}
@@ -0,0 +1,77 @@
+use rust_chess::book::polyglot_builder::{PolyglotBuilder, PolyglotBuilderOptions};
+use std::process::ExitCode;
+
+const USAGE: &str = "usage: build_book [--max-ply N] [--min-games N] <output.bin> <games.pgn>...";
+
+fn parse_args(
+    args: impl Iterator<Item = String>,
+) -> Result<(PolyglotBuilderOptions, String, Vec<String>), String> {
+    let mut options = PolyglotBuilderOptions::new();
+    let mut paths = vec![];
+    let mut args = args.peekable();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--max-ply" => {
+                let value = args.next().ok_or("--max-ply expects a value")?;
+                options.max_ply = value
+                    .parse()
+                    .map_err(|_| format!("invalid --max-ply: {value}"))?;
+            }
+            "--min-games" => {
+                let value = args.next().ok_or("--min-games expects a value")?;
+                options.min_games = value
+                    .parse()
+                    .map_err(|_| format!("invalid --min-games: {value}"))?;
+            }
+            _ => paths.push(arg),
+        }
+    }
+    if paths.len() < 2 {
+        return Err(USAGE.to_string());
+    }
+    let output = paths.remove(0);
+    Ok((options, output, paths))
+}
+
+fn main() -> ExitCode {
+    let (options, output, pgn_paths) = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("{message}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut builder = PolyglotBuilder::new(Some(options));
+    for pgn_path in pgn_paths {
+        let result = std::fs::read_to_string(&pgn_path)
+            .map_err(|err| err.to_string())
+            .and_then(|pgn| builder.add_pgn(&pgn).map_err(|err| err.to_string()));
+        match result {
+            Ok(import) => {
+                for (ix, err) in &import.rejected {
+                    eprintln!("{pgn_path}: skipped game {}: {err}", ix + 1);
+                }
+                println!(
+                    "{pgn_path}: {} games, {} skipped",
+                    import.added,
+                    import.rejected.len()
+                );
+            }
+            Err(err) => {
+                eprintln!("{pgn_path}: {err}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    let book = builder.build();
+    if let Err(err) = std::fs::write(&output, book.to_bytes()) {
+        eprintln!("{output}: {err}");
+        return ExitCode::FAILURE;
+    }
+    println!(
+        "wrote {} entries from {} games to {output}",
+        book.len(),
+        builder.games()
+    );
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,93 @@
+use rust_chess::notation::epd::deserialize_epd_suite;
+use rust_chess::notation::san::serialize_san;
+use rust_chess::search::epd_suite::{run_epd_suite_with, EpdSuiteOptions};
+use std::process::ExitCode;
+use std::time::Duration;
+
+const USAGE: &str = "usage: epd_suite [--depth N] [--time MS] [--threads N] <suite.epd>";
+
+fn parse_value<T: std::str::FromStr>(
+    args: &mut impl Iterator<Item = String>,
+    flag: &str,
+) -> Result<T, String> {
+    let value = args.next().ok_or(format!("{flag} expects a value"))?;
+    value
+        .parse()
+        .map_err(|_| format!("invalid {flag}: {value}"))
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<(EpdSuiteOptions, String), String> {
+    let mut options = EpdSuiteOptions::new();
+    let mut paths = vec![];
+    let mut args = args.peekable();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--depth" => options.limits.depth = Some(parse_value(&mut args, "--depth")?),
+            "--time" => {
+                // a time limit replaces the default depth limit
+                let millis = parse_value(&mut args, "--time")?;
+                options.limits.move_time = Some(Duration::from_millis(millis));
+                options.limits.depth = None;
+            }
+            "--threads" => options.search_options.threads = parse_value(&mut args, "--threads")?,
+            _ => paths.push(arg),
+        }
+    }
+    match paths.as_slice() {
+        [path] => Ok((options, path.clone())),
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+fn main() -> ExitCode {
+    let (options, path) = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("{message}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let epds = match std::fs::read_to_string(&path)
+        .map_err(|err| err.to_string())
+        .and_then(|suite| deserialize_epd_suite(&suite).map_err(|err| err.to_string()))
+    {
+        Ok(epds) => epds,
+        Err(err) => {
+            eprintln!("{path}: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut positions = epds.iter();
+    let report = run_epd_suite_with(&epds, Some(options), |result| {
+        let epd = positions.next().expect("one result per position");
+        let best_move = result
+            .best_move
+            .as_ref()
+            .map_or("-".to_string(), |chess_move| {
+                serialize_san(&epd.game_state, chess_move)
+            });
+        println!(
+            "{} {:<10} {best_move} {}/{}",
+            if result.solved { "+" } else { "-" },
+            result.id.as_deref().unwrap_or("?"),
+            result.points,
+            result.max_points,
+        );
+    });
+    match report {
+        Ok(report) => {
+            println!(
+                "solved {}/{}, score {}/{}",
+                report.solved().count(),
+                report.results.len(),
+                report.points(),
+                report.max_points(),
+            );
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{path}: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
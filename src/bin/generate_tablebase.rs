@@ -0,0 +1,38 @@
+use rust_chess::tablebase::tablebase::Tablebase;
+use std::process::ExitCode;
+
+const USAGE: &str = "usage: generate_tablebase <output dir> <material>... (e.g. KQvK KRvK KBNvK)";
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let Some(output_dir) = args.next() else {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    };
+    let names = args.collect::<Vec<_>>();
+    if names.is_empty() {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    }
+    let mut tablebase = Tablebase::new();
+    for name in names {
+        let start = std::time::Instant::now();
+        match tablebase.generate_by_name(&name) {
+            Ok(table) => println!(
+                "{name}: longest mate in {} moves, generated in {:?}",
+                table.longest_mate(),
+                start.elapsed()
+            ),
+            Err(err) => {
+                eprintln!("{name}: {err}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    if let Err(err) = tablebase.save_dir(&output_dir) {
+        eprintln!("{output_dir}: {err}");
+        return ExitCode::FAILURE;
+    }
+    println!("wrote {} tables to {output_dir}", tablebase.len());
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,269 @@
+use rust_chess::arena::engine::{BuiltinEngine, Engine};
+use rust_chess::arena::match_runner::{run_match, MatchOptions};
+use rust_chess::arena::match_stats::SprtOptions;
+use rust_chess::arena::openings::load_openings;
+use rust_chess::arena::uci_engine::{UciEngine, UciEngineConfig};
+use rust_chess::search::search_limits::SearchLimits;
+use rust_chess::tablebase::tablebase::Tablebase;
+use std::fs::File;
+use std::io::Write;
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::Duration;
+
+const USAGE: &str = "usage: match_runner --engine SPEC --engine SPEC [--games N] [--openings FILE.epd|FILE.pgn] \
+[--pgn OUT] [--max-plies N] [--resign-score CP] [--resign-moves N] [--draw-score CP] [--draw-moves N] \
+[--draw-after MOVE] [--tablebase DIR] [--sprt ELO0,ELO1[,ALPHA,BETA]]
+SPEC is comma separated key=value pairs: name, cmd (a uci engine, the builtin engine without one), \
+arg (repeatable), depth, nodes, time (ms), threads (builtin only) and option.NAME=VALUE (uci only)";
+
+enum EngineSpec {
+    Builtin(BuiltinEngine),
+    Uci(UciEngineConfig),
+}
+
+struct MatchArgs {
+    engines: Vec<EngineSpec>,
+    options: MatchOptions,
+    openings: Option<String>,
+    pgn: Option<String>,
+    tablebase: Option<String>,
+}
+
+fn parse_value<T: std::str::FromStr>(
+    args: &mut impl Iterator<Item = String>,
+    flag: &str,
+) -> Result<T, String> {
+    let value = args.next().ok_or(format!("{flag} expects a value"))?;
+    value
+        .parse()
+        .map_err(|_| format!("invalid {flag}: {value}"))
+}
+
+fn parse_field<T: std::str::FromStr>(key: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid engine {key}: {value}"))
+}
+
+fn parse_engine(spec: &str, index: usize) -> Result<EngineSpec, String> {
+    let mut name = format!("engine{index}");
+    let mut command = None;
+    let mut args = vec![];
+    let mut limits = SearchLimits::new();
+    let mut threads = None;
+    let mut options = vec![];
+    for field in spec.split(',').filter(|field| !field.is_empty()) {
+        let (key, value) = field
+            .split_once('=')
+            .ok_or(format!("engine fields are key=value: {field}"))?;
+        match key {
+            "name" => name = value.to_string(),
+            "cmd" => command = Some(value.to_string()),
+            "arg" => args.push(value.to_string()),
+            "depth" => limits.depth = Some(parse_field(key, value)?),
+            "nodes" => limits.nodes = Some(parse_field(key, value)?),
+            "time" => limits.move_time = Some(Duration::from_millis(parse_field(key, value)?)),
+            "threads" => threads = Some(parse_field(key, value)?),
+            _ => match key.strip_prefix("option.") {
+                Some(option) => options.push((option.to_string(), value.to_string())),
+                None => return Err(format!("unknown engine field: {key}")),
+            },
+        }
+    }
+    let has_limits = limits != SearchLimits::new();
+    Ok(match command {
+        Some(command) => {
+            let mut config = UciEngineConfig::new(&name, &command);
+            config.args = args;
+            config.options = options;
+            config.limits = limits;
+            EngineSpec::Uci(config)
+        }
+        None => {
+            let mut engine = BuiltinEngine::new(&name);
+            if has_limits {
+                engine.limits = limits;
+            }
+            if let Some(threads) = threads {
+                engine.search_options.threads = threads;
+            }
+            EngineSpec::Builtin(engine)
+        }
+    })
+}
+
+fn parse_sprt(value: &str) -> Result<SprtOptions, String> {
+    let invalid = || format!("invalid --sprt: {value}");
+    let values = value
+        .split(',')
+        .map(|value| value.trim().parse::<f64>().map_err(|_| invalid()))
+        .collect::<Result<Vec<_>, _>>()?;
+    match values.as_slice() {
+        [elo0, elo1] => Ok(SprtOptions::new(*elo0, *elo1)),
+        [elo0, elo1, alpha, beta] => Ok(SprtOptions {
+            alpha: *alpha,
+            beta: *beta,
+            ..SprtOptions::new(*elo0, *elo1)
+        }),
+        _ => Err(invalid()),
+    }
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<MatchArgs, String> {
+    let mut match_args = MatchArgs {
+        engines: vec![],
+        options: MatchOptions::new(),
+        openings: None,
+        pgn: None,
+        tablebase: None,
+    };
+    let options = &mut match_args.options;
+    let mut args = args.peekable();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--engine" => {
+                let spec: String = parse_value(&mut args, "--engine")?;
+                let index = match_args.engines.len() + 1;
+                match_args.engines.push(parse_engine(&spec, index)?);
+            }
+            "--games" => options.games = parse_value(&mut args, "--games")?,
+            "--openings" => match_args.openings = Some(parse_value(&mut args, "--openings")?),
+            "--pgn" => match_args.pgn = Some(parse_value(&mut args, "--pgn")?),
+            "--max-plies" => {
+                options.adjudication.max_plies = Some(parse_value(&mut args, "--max-plies")?)
+            }
+            "--resign-score" => {
+                options.adjudication.resign_score = Some(parse_value(&mut args, "--resign-score")?)
+            }
+            "--resign-moves" => {
+                options.adjudication.resign_moves = parse_value(&mut args, "--resign-moves")?
+            }
+            "--draw-score" => {
+                options.adjudication.draw_score = Some(parse_value(&mut args, "--draw-score")?)
+            }
+            "--draw-moves" => {
+                options.adjudication.draw_moves = parse_value(&mut args, "--draw-moves")?
+            }
+            "--draw-after" => {
+                options.adjudication.draw_move_number = parse_value(&mut args, "--draw-after")?
+            }
+            "--tablebase" => match_args.tablebase = Some(parse_value(&mut args, "--tablebase")?),
+            "--sprt" => {
+                let value: String = parse_value(&mut args, "--sprt")?;
+                options.sprt = Some(parse_sprt(&value)?);
+            }
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+    if match_args.engines.len() != 2 {
+        return Err(USAGE.to_string());
+    }
+    Ok(match_args)
+}
+
+fn start_engine(spec: EngineSpec) -> Result<Box<dyn Engine>, String> {
+    match spec {
+        EngineSpec::Builtin(engine) => Ok(Box::new(engine)),
+        EngineSpec::Uci(config) => UciEngine::spawn(config)
+            .map(|engine| Box::new(engine) as Box<dyn Engine>)
+            .map_err(|err| err.to_string()),
+    }
+}
+
+fn run(match_args: MatchArgs) -> Result<(), String> {
+    let MatchArgs {
+        engines,
+        mut options,
+        openings,
+        pgn,
+        tablebase,
+    } = match_args;
+    let openings = match openings {
+        Some(path) => load_openings(&path).map_err(|err| format!("{path}: {err}"))?,
+        None => vec![],
+    };
+    if let Some(dir) = tablebase {
+        let tablebase = Tablebase::load_dir(&dir).map_err(|err| format!("{dir}: {err}"))?;
+        options.tablebase = Some(Arc::new(tablebase));
+    }
+    let mut pgn_file = match pgn {
+        Some(path) => Some(File::create(&path).map_err(|err| format!("{path}: {err}"))?),
+        None => None,
+    };
+    let mut engines = engines
+        .into_iter()
+        .map(start_engine)
+        .collect::<Result<Vec<_>, _>>()?;
+    let [engine_a, engine_b] = engines.as_mut_slice() else {
+        unreachable!("two engines are checked when parsing")
+    };
+    let event = options.event.clone();
+    let sprt = options.sprt;
+    let mut write_error = None;
+    let report = run_match(
+        engine_a.as_mut(),
+        engine_b.as_mut(),
+        &openings,
+        Some(options),
+        |record, score| {
+            println!(
+                "game {:>4}: {} - {} {} ({}), score {score}",
+                record.round,
+                record.white,
+                record.black,
+                record.result.as_str(),
+                record.termination.as_str(),
+            );
+            if let Some(file) = pgn_file.as_mut() {
+                let pgn = rust_chess::notation::pgn::serialize(&record.to_pgn_game(&event));
+                if let Err(err) = writeln!(file, "{pgn}") {
+                    write_error.get_or_insert(err.to_string());
+                }
+            }
+        },
+    )
+    .map_err(|err| err.to_string())?;
+    if let Some(err) = write_error {
+        return Err(format!("failed to write pgn: {err}"));
+    }
+    let score = report.score;
+    println!(
+        "{} vs {}: {score} over {} games",
+        engine_a.name(),
+        engine_b.name(),
+        score.games()
+    );
+    match (score.elo(), score.elo_error_margin()) {
+        (Some(elo), Some(margin)) => println!("elo difference {elo:.1} +/- {margin:.1}"),
+        (Some(elo), None) => println!("elo difference {elo:.1}"),
+        _ => println!("elo difference unknown"),
+    }
+    if let (Some(sprt), Some(status), Some(llr)) = (sprt, report.sprt_status, report.llr) {
+        println!(
+            "sprt [{}, {}]: llr {llr:.2} ({:.2}, {:.2}), {status}",
+            sprt.elo0,
+            sprt.elo1,
+            sprt.lower_bound(),
+            sprt.upper_bound(),
+        );
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let match_args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("{message}");
+            return ExitCode::FAILURE;
+        }
+    };
+    match run(match_args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
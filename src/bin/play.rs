@@ -0,0 +1,306 @@
+use rust_chess::board::board_diagram::{render_board, BoardDiagramOptions, DiagramCharset};
+use rust_chess::book::polyglot_book::PolyglotBook;
+use rust_chess::chess_move::chess_move::ChessMove;
+use rust_chess::chess_move::chess_move_handler::default_chess_move_handler;
+use rust_chess::notation::coordinate::deserialize_coordinate;
+use rust_chess::notation::fen::{deserialize, serialize};
+use rust_chess::notation::pgn::{PgnGame, PgnResult};
+use rust_chess::notation::san::{deserialize_san, serialize_san};
+use rust_chess::search::search_options::SearchOptions;
+use rust_chess::state::evaluate_game_state::find_best_move_with_options;
+use rust_chess::state::game_state::GameState;
+use rust_chess::state::game_status::GameStatus;
+use std::io::{BufRead, Write};
+use std::process::ExitCode;
+
+const USAGE: &str =
+    "usage: play [--unicode] [--color] [--flip] [--fen FEN] [--bot DEPTH] [--book PATH]";
+const HELP: &str = "enter moves as san (Nf3) or coordinates (g1f3)
+commands: undo, fen, pgn, hint, bot <depth>, bot off, flip, help, quit";
+const DEFAULT_HINT_DEPTH: u8 = 3;
+
+struct Session {
+    start: GameState,
+    // the flag marks the plies the bot played
+    plies: Vec<(GameState, ChessMove, bool)>,
+    game_state: GameState,
+    bot_depth: Option<u8>,
+    search_options: SearchOptions,
+    diagram_options: BoardDiagramOptions,
+}
+
+impl Session {
+    fn play(&mut self, chess_move: ChessMove, by_bot: bool) -> Result<String, String> {
+        let san = serialize_san(&self.game_state, &chess_move);
+        let game_state_before = self.game_state.clone();
+        default_chess_move_handler(&mut self.game_state, &chess_move, None)
+            .map_err(|err| err.to_string())?;
+        self.plies.push((game_state_before, chess_move, by_bot));
+        Ok(san)
+    }
+    fn play_input(&mut self, input: &str) -> Result<String, String> {
+        let chess_move = deserialize_san(&self.game_state, input)
+            .map_err(|err| err.to_string())
+            .or_else(|san_err| {
+                // only mention the san error, coordinates are the fallback
+                deserialize_coordinate(&self.game_state, input).map_err(|_| san_err)
+            })?;
+        self.play(chess_move, false)
+    }
+    fn bot_move(&mut self, depth: u8) -> Result<String, String> {
+        let chess_move =
+            find_best_move_with_options(&self.game_state, depth, Some(self.search_options))?;
+        self.play(chess_move, true)
+    }
+    // takes back the user's last move along with any bot replies to it
+    fn undo(&mut self) -> usize {
+        let bot_plies = self
+            .plies
+            .iter()
+            .rev()
+            .take_while(|(_, _, by_bot)| *by_bot)
+            .count();
+        let plies = (bot_plies + 1).min(self.plies.len());
+        if let Some((game_state, _, _)) = self.plies.drain(self.plies.len() - plies..).next() {
+            self.game_state = game_state;
+        }
+        plies
+    }
+    fn pgn(&self) -> String {
+        let mut game = PgnGame::new();
+        game.set_movetext(
+            &self.start,
+            self.plies
+                .iter()
+                .map(|(game_state, chess_move, _)| serialize_san(game_state, chess_move))
+                .collect(),
+        );
+        game.result = PgnResult::from_game_status(self.game_state.game_status);
+        rust_chess::notation::pgn::serialize(&game)
+    }
+    fn status(&self) -> String {
+        let to_move = format!("{:?} to move", self.game_state.active_color);
+        match self.game_state.game_status {
+            GameStatus::New | GameStatus::InProgress => to_move,
+            GameStatus::Check(_) => format!("{to_move}, check"),
+            GameStatus::CheckMate(color) => {
+                format!("checkmate, {:?} wins", color.as_inverse())
+            }
+            GameStatus::Stalemate => "stalemate".to_string(),
+            GameStatus::Draw => "draw".to_string(),
+            GameStatus::TimeForfeit(color) => {
+                format!("{color:?} lost on time, {:?} wins", color.as_inverse())
+            }
+        }
+    }
+    fn board(&self) -> String {
+        format!(
+            "{}{}",
+            render_board(&self.game_state.board, Some(self.diagram_options)),
+            self.status()
+        )
+    }
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Session, String> {
+    let mut diagram_options = BoardDiagramOptions::new();
+    let mut game_state = GameState::new();
+    let mut bot_depth = None;
+    let mut search_options = SearchOptions::new();
+    let mut args = args.peekable();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--unicode" => diagram_options.charset = DiagramCharset::Unicode,
+            "--color" => diagram_options.ansi_colors = true,
+            "--flip" => diagram_options.flipped = true,
+            "--fen" => {
+                let fen = args.next().ok_or("--fen expects a value")?;
+                game_state = deserialize(&fen).map_err(|err| format!("invalid --fen: {err}"))?;
+            }
+            "--bot" => {
+                let value = args.next().ok_or("--bot expects a value")?;
+                bot_depth = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid --bot: {value}"))?,
+                );
+            }
+            "--book" => {
+                let path = args.next().ok_or("--book expects a value")?;
+                let book =
+                    PolyglotBook::open(&path).map_err(|err| format!("invalid --book: {err}"))?;
+                // the bot keeps using the book until the session ends
+                search_options.book = Some(Box::leak(Box::new(book)));
+            }
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+    Ok(Session {
+        start: game_state.clone(),
+        plies: vec![],
+        game_state,
+        bot_depth,
+        search_options,
+        diagram_options,
+    })
+}
+
+// the lines to print, or None once the session should end
+fn handle_line(session: &mut Session, line: &str) -> Option<Vec<String>> {
+    let words = line.split_whitespace().collect::<Vec<_>>();
+    let mut output = vec![];
+    match words.as_slice() {
+        [] => {}
+        ["quit" | "exit"] => return None,
+        ["help"] => output.push(HELP.to_string()),
+        ["fen"] => output.push(serialize(&session.game_state).to_string()),
+        ["pgn"] => output.push(session.pgn()),
+        ["flip"] => {
+            session.diagram_options.flipped = !session.diagram_options.flipped;
+            output.push(session.board());
+        }
+        ["undo"] => {
+            if session.undo() == 0 {
+                output.push("nothing to undo".to_string());
+            }
+            output.push(session.board());
+        }
+        ["hint"] => {
+            let depth = session.bot_depth.unwrap_or(DEFAULT_HINT_DEPTH);
+            match find_best_move_with_options(
+                &session.game_state,
+                depth,
+                Some(session.search_options),
+            ) {
+                Ok(chess_move) => output.push(format!(
+                    "hint: {}",
+                    serialize_san(&session.game_state, &chess_move)
+                )),
+                Err(err) => output.push(err.to_string()),
+            }
+        }
+        ["bot", "off"] => session.bot_depth = None,
+        ["bot", depth] => match depth.parse::<u8>() {
+            Ok(depth) => {
+                session.bot_depth = Some(depth);
+                match session.bot_move(depth) {
+                    Ok(san) => output.push(format!("bot: {san}")),
+                    Err(err) => output.push(err),
+                }
+                output.push(session.board());
+            }
+            Err(_) => output.push(format!("invalid depth: {depth}")),
+        },
+        [input] => {
+            if session.game_state.game_status.is_game_over() {
+                output.push("the game is over, undo or quit".to_string());
+                return Some(output);
+            }
+            if let Err(err) = session.play_input(input) {
+                output.push(err);
+                return Some(output);
+            }
+            if let Some(depth) = session.bot_depth {
+                if !session.game_state.game_status.is_game_over() {
+                    match session.bot_move(depth) {
+                        Ok(san) => output.push(format!("bot: {san}")),
+                        Err(err) => output.push(err),
+                    }
+                }
+            }
+            output.push(session.board());
+        }
+        _ => output.push(format!("unknown command: {line}\n{HELP}")),
+    }
+    Some(output)
+}
+
+fn main() -> ExitCode {
+    let mut session = match parse_args(std::env::args().skip(1)) {
+        Ok(session) => session,
+        Err(message) => {
+            eprintln!("{message}");
+            return ExitCode::FAILURE;
+        }
+    };
+    println!("{}", session.board());
+    let stdin = std::io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("> ");
+        let _ = std::io::stdout().flush();
+        let Some(Ok(line)) = lines.next() else {
+            break;
+        };
+        let Some(output) = handle_line(&mut session, &line) else {
+            break;
+        };
+        for output_line in output {
+            println!("{output_line}");
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(args: &[&str]) -> Session {
+        parse_args(args.iter().map(|arg| arg.to_string())).expect("valid args")
+    }
+
+    fn run(session: &mut Session, lines: &[&str]) {
+        for line in lines {
+            handle_line(session, line).expect("session still running");
+        }
+    }
+
+    #[test]
+    fn test_undo_takes_back_the_bot_reply() {
+        let mut session = session(&["--bot", "1"]);
+        run(&mut session, &["e4"]);
+        assert_eq!(2, session.plies.len());
+        run(&mut session, &["undo"]);
+        assert!(session.plies.is_empty());
+        assert_eq!(serialize(&session.start), serialize(&session.game_state));
+    }
+
+    #[test]
+    fn test_undo_after_a_game_ending_move() {
+        let mut session = session(&["--fen", "7k/8/5K2/8/8/8/8/R7 w - - 0 1", "--bot", "1"]);
+        // the bot's only reply is Kg8, then Ra8 mates and the bot has nothing to answer
+        run(&mut session, &["Kg6", "Ra8"]);
+        assert_eq!(3, session.plies.len());
+        assert!(session.game_state.game_status.is_game_over());
+        run(&mut session, &["undo"]);
+        assert_eq!(2, session.plies.len());
+        assert!(!session.game_state.game_status.is_game_over());
+    }
+
+    #[test]
+    fn test_undo_without_a_bot_reply() {
+        let mut session = session(&[]);
+        run(&mut session, &["e4", "e5"]);
+        session.bot_depth = Some(1);
+        run(&mut session, &["undo"]);
+        assert_eq!(1, session.plies.len());
+        run(&mut session, &["undo", "undo"]);
+        assert!(session.plies.is_empty());
+        assert_eq!(
+            Some(vec!["nothing to undo".to_string(), session.board()]),
+            handle_line(&mut session, "undo")
+        );
+    }
+
+    #[test]
+    fn test_undo_keeps_earlier_bot_moves() {
+        let mut session = session(&[]);
+        run(&mut session, &["e4", "bot 1", "d4"]);
+        assert_eq!(4, session.plies.len());
+        run(&mut session, &["undo"]);
+        assert_eq!(2, session.plies.len());
+        assert!(session.plies[1].2);
+        assert_eq!(None, handle_line(&mut session, "quit"));
+    }
+}
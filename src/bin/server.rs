@@ -0,0 +1,48 @@
+use rust_chess::book::polyglot_book::PolyglotBook;
+use rust_chess::search::search_options::SearchOptions;
+use rust_chess::server::server::{ApiServer, DEFAULT_ADDRESS};
+use std::process::ExitCode;
+
+const USAGE: &str = "usage: server [--address HOST:PORT] [--book PATH]";
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<(String, SearchOptions), String> {
+    let mut address = DEFAULT_ADDRESS.to_string();
+    let mut bot_options = SearchOptions::new();
+    let mut args = args.peekable();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--address" => address = args.next().ok_or("--address expects a value")?,
+            "--book" => {
+                let path = args.next().ok_or("--book expects a value")?;
+                let book =
+                    PolyglotBook::open(&path).map_err(|err| format!("invalid --book: {err}"))?;
+                // the bot keeps using the book until the server exits
+                bot_options.book = Some(Box::leak(Box::new(book)));
+            }
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+    Ok((address, bot_options))
+}
+
+fn main() -> ExitCode {
+    let (address, bot_options) = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("{message}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let server = match ApiServer::bind(&address, Some(bot_options)) {
+        Ok(server) => server,
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Some(addr) = server.local_addr() {
+        println!("listening on http://{addr}");
+    }
+    server.run();
+    ExitCode::SUCCESS
+}
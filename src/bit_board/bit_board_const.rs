@@ -5,6 +5,7 @@ use crate::utils::custom_struct_iterator::CustomStructIterator;
 pub type BitBoardConstData = u64;
 #[repr(transparent)]
 #[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BitBoardConst {
     data: BitBoardConstData,
 }
@@ -8,6 +8,7 @@ use crate::utils::hash::hash_64bit_numbers;
 use std::hash::{Hash, Hasher};
 
 #[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FullColorPieceBitBoard {
     pub white: FullPieceBitBoard,
     pub black: FullPieceBitBoard,
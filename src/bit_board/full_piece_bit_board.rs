@@ -3,6 +3,7 @@ use crate::piece::piece::Piece;
 use crate::utils::custom_struct_iterator::CustomStructIterator;
 
 #[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FullPieceBitBoard {
     pub pawn: BitBoardConst,
     pub knight: BitBoardConst,
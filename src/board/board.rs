@@ -13,6 +13,7 @@ use crate::utils::custom_struct_iterator::CustomStructIterator;
 const STARTING_BOARD: Board = Board::new();
 
 #[derive(Default, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Board {
     a1: Option<ChessPiece>,
     a2: Option<ChessPiece>,
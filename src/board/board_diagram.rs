@@ -0,0 +1,173 @@
+use crate::board::board::Board;
+use crate::board::board_file::BoardFile;
+use crate::board::board_position::BoardPosition;
+use crate::board::board_rank::BoardRank;
+use crate::color::Color;
+use crate::piece::chess_piece::ChessPiece;
+
+const ANSI_RESET: &str = "\x1b[0m";
+const ANSI_LIGHT_SQUARE: &str = "\x1b[48;5;180m";
+const ANSI_DARK_SQUARE: &str = "\x1b[48;5;137m";
+const ANSI_WHITE_PIECE: &str = "\x1b[1;97m";
+const ANSI_BLACK_PIECE: &str = "\x1b[1;30m";
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum DiagramCharset {
+    #[default]
+    Ascii,
+    Unicode,
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct BoardDiagramOptions {
+    pub charset: DiagramCharset,
+    pub ansi_colors: bool,
+    // black at the bottom
+    pub flipped: bool,
+}
+
+impl BoardDiagramOptions {
+    pub const fn new() -> Self {
+        Self {
+            charset: DiagramCharset::Ascii,
+            ansi_colors: false,
+            flipped: false,
+        }
+    }
+}
+
+fn square_char(maybe_piece: Option<ChessPiece>, options: &BoardDiagramOptions) -> char {
+    match (maybe_piece, options.charset) {
+        (None, _) if options.ansi_colors => ' ',
+        (None, DiagramCharset::Ascii) => '.',
+        (None, DiagramCharset::Unicode) => '·',
+        (Some(chess_piece), DiagramCharset::Ascii) => chess_piece.as_char(),
+        // the filled glyphs read better on a coloured background
+        (Some(chess_piece), DiagramCharset::Unicode) if options.ansi_colors => {
+            ChessPiece::from(Color::Black, chess_piece.as_piece()).as_unicode_char()
+        }
+        (Some(chess_piece), DiagramCharset::Unicode) => chess_piece.as_unicode_char(),
+    }
+}
+
+fn render_square(board: &Board, pos: BoardPosition, options: &BoardDiagramOptions) -> String {
+    let maybe_piece = *board.get(pos);
+    let c = square_char(maybe_piece, options);
+    if !options.ansi_colors {
+        return format!(" {c}");
+    }
+    let is_light = (pos.file().as_zero_based_index() + pos.rank().as_zero_based_index()) % 2 == 1;
+    let background = if is_light {
+        ANSI_LIGHT_SQUARE
+    } else {
+        ANSI_DARK_SQUARE
+    };
+    let foreground = match maybe_piece.map(|chess_piece| chess_piece.as_color()) {
+        Some(Color::Black) => ANSI_BLACK_PIECE,
+        _ => ANSI_WHITE_PIECE,
+    };
+    format!("{background}{foreground} {c} {ANSI_RESET}")
+}
+
+pub fn render_board(board: &Board, options: Option<BoardDiagramOptions>) -> String {
+    let options = options.unwrap_or_default();
+    let mut ranks = (0..8).rev().collect::<Vec<_>>();
+    let mut files = (0..8).collect::<Vec<_>>();
+    if options.flipped {
+        ranks.reverse();
+        files.reverse();
+    }
+    let mut diagram = String::new();
+    for &rank_ix in ranks.iter() {
+        let rank = BoardRank::from_zero_based_index(rank_ix).expect("rank out of range");
+        diagram.push_str(&rank.to_string());
+        for &file_ix in files.iter() {
+            let file = BoardFile::from_zero_based_index(file_ix).expect("file out of range");
+            diagram.push_str(&render_square(board, BoardPosition(file, rank), &options));
+        }
+        diagram.push('\n');
+    }
+    let file_labels = files
+        .iter()
+        .map(|&file_ix| {
+            let file = BoardFile::from_zero_based_index(file_ix).expect("file out of range");
+            file.as_char().to_ascii_lowercase()
+        })
+        .map(|c| match options.ansi_colors {
+            true => format!(" {c} "),
+            false => format!(" {c}"),
+        })
+        .collect::<String>();
+    diagram.push(' ');
+    diagram.push_str(&file_labels);
+    diagram.push('\n');
+    diagram
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::fen::deserialize;
+    use rstest::rstest;
+
+    const AFTER_E4: &str = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1";
+
+    #[rstest]
+    #[case(
+        BoardDiagramOptions::new(),
+        "8 r n b q k b n r
+7 p p p p p p p p
+6 . . . . . . . .
+5 . . . . . . . .
+4 . . . . P . . .
+3 . . . . . . . .
+2 P P P P . P P P
+1 R N B Q K B N R
+  a b c d e f g h
+"
+    )]
+    #[case(
+        BoardDiagramOptions { flipped: true, ..BoardDiagramOptions::new() },
+        "1 R N B K Q B N R
+2 P P P . P P P P
+3 . . . . . . . .
+4 . . . P . . . .
+5 . . . . . . . .
+6 . . . . . . . .
+7 p p p p p p p p
+8 r n b k q b n r
+  h g f e d c b a
+"
+    )]
+    #[case(
+        BoardDiagramOptions { charset: DiagramCharset::Unicode, ..BoardDiagramOptions::new() },
+        "8 ♜ ♞ ♝ ♛ ♚ ♝ ♞ ♜
+7 ♟ ♟ ♟ ♟ ♟ ♟ ♟ ♟
+6 · · · · · · · ·
+5 · · · · · · · ·
+4 · · · · ♙ · · ·
+3 · · · · · · · ·
+2 ♙ ♙ ♙ ♙ · ♙ ♙ ♙
+1 ♖ ♘ ♗ ♕ ♔ ♗ ♘ ♖
+  a b c d e f g h
+"
+    )]
+    fn test_render_board(#[case] options: BoardDiagramOptions, #[case] expected: &str) {
+        let game_state = deserialize(AFTER_E4).expect("bad fen string!");
+        assert_eq!(expected, render_board(&game_state.board, Some(options)));
+    }
+
+    #[test]
+    fn test_render_board_ansi_colors() {
+        let options = BoardDiagramOptions {
+            ansi_colors: true,
+            ..BoardDiagramOptions::new()
+        };
+        let diagram = render_board(&Board::new(), Some(options));
+        // a1 is a dark square
+        assert!(diagram.contains(&format!(
+            "1{ANSI_DARK_SQUARE}{ANSI_WHITE_PIECE} R {ANSI_RESET}"
+        )));
+        assert_eq!(9, diagram.lines().count());
+    }
+}
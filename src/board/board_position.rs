@@ -7,6 +7,7 @@ use std::str::FromStr;
 use thiserror::Error;
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BoardPosition(pub BoardFile, pub BoardRank);
 
 impl BoardPosition {}
@@ -26,6 +27,15 @@ impl BoardPosition {
         let file_index = self.file().as_zero_based_index();
         rank_index * 8 + file_index
     }
+    pub const fn from_pos_index(index: usize) -> Option<Self> {
+        let Ok(file) = BoardFile::from_zero_based_index(index % 8) else {
+            return None;
+        };
+        let Ok(rank) = BoardRank::from_zero_based_index(index / 8) else {
+            return None;
+        };
+        Some(Self(file, rank))
+    }
 
     pub const fn next_pos(self, direction: Direction) -> Option<Self> {
         direction.get_next_pos(self)
@@ -0,0 +1,320 @@
+use crate::board::board::Board;
+use crate::board::board_file::BoardFile;
+use crate::board::board_position::BoardPosition;
+use crate::board::board_rank::BoardRank;
+use crate::color::Color;
+use crate::piece::chess_piece::ChessPiece;
+use crate::piece::piece::Piece;
+use crate::state::game_state::GameState;
+use std::fmt::Write;
+
+const DEFAULT_SQUARE_SIZE: u32 = 45;
+const LIGHT_SQUARE_FILL: &str = "#f0d9b5";
+const DARK_SQUARE_FILL: &str = "#b58863";
+const LAST_MOVE_FILL: &str = "#cdd26a";
+const CHECK_FILL: &str = "#e84b4b";
+const ANNOTATION_STROKE: &str = "#15781b";
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SvgArrow {
+    pub from: BoardPosition,
+    pub to: BoardPosition,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoardSvgOptions {
+    pub square_size: u32,
+    // black at the bottom
+    pub flipped: bool,
+    pub coordinates: bool,
+    pub last_move: Option<(BoardPosition, BoardPosition)>,
+    pub check: Option<BoardPosition>,
+    pub arrows: Vec<SvgArrow>,
+    pub circles: Vec<BoardPosition>,
+}
+
+impl BoardSvgOptions {
+    pub const fn new() -> Self {
+        Self {
+            square_size: DEFAULT_SQUARE_SIZE,
+            flipped: false,
+            coordinates: true,
+            last_move: None,
+            check: None,
+            arrows: Vec::new(),
+            circles: Vec::new(),
+        }
+    }
+}
+
+impl Default for BoardSvgOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct Layout {
+    square_size: u32,
+    margin: u32,
+    flipped: bool,
+}
+
+impl Layout {
+    const fn from_options(options: &BoardSvgOptions) -> Self {
+        Self {
+            square_size: options.square_size,
+            margin: if options.coordinates {
+                options.square_size / 2
+            } else {
+                0
+            },
+            flipped: options.flipped,
+        }
+    }
+    const fn size(&self) -> u32 {
+        self.square_size * 8 + self.margin * 2
+    }
+    // top left corner of the square
+    const fn origin(&self, pos: BoardPosition) -> (u32, u32) {
+        let file_ix = pos.file().as_zero_based_index() as u32;
+        let rank_ix = pos.rank().as_zero_based_index() as u32;
+        let (column, row) = if self.flipped {
+            (7 - file_ix, rank_ix)
+        } else {
+            (file_ix, 7 - rank_ix)
+        };
+        (
+            self.margin + column * self.square_size,
+            self.margin + row * self.square_size,
+        )
+    }
+    const fn center(&self, pos: BoardPosition) -> (u32, u32) {
+        let (x, y) = self.origin(pos);
+        (x + self.square_size / 2, y + self.square_size / 2)
+    }
+}
+
+fn all_positions() -> impl Iterator<Item = BoardPosition> {
+    (0..64).map(|index| BoardPosition::from_pos_index(index).expect("index out of range"))
+}
+
+fn write_square(svg: &mut String, layout: &Layout, pos: BoardPosition, fill: &str) {
+    let (x, y) = layout.origin(pos);
+    let size = layout.square_size;
+    let _ = writeln!(
+        svg,
+        r#"<rect x="{x}" y="{y}" width="{size}" height="{size}" fill="{fill}"/>"#
+    );
+}
+
+fn write_coordinates(svg: &mut String, layout: &Layout) {
+    let font_size = layout.margin * 3 / 5;
+    for ix in 0..8 {
+        let file = BoardFile::from_zero_based_index(ix).expect("file out of range");
+        let rank = BoardRank::from_zero_based_index(ix).expect("rank out of range");
+        let (x, _) = layout.center(BoardPosition(file, BoardRank::One));
+        let (_, y) = layout.center(BoardPosition(BoardFile::A, rank));
+        let bottom = layout.size() - layout.margin / 2;
+        let left = layout.margin / 2;
+        let _ = writeln!(
+            svg,
+            r#"<text x="{x}" y="{bottom}" font-size="{font_size}" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+            file.as_char().to_ascii_lowercase()
+        );
+        let _ = writeln!(
+            svg,
+            r#"<text x="{left}" y="{y}" font-size="{font_size}" text-anchor="middle" dominant-baseline="central">{rank}</text>"#
+        );
+    }
+}
+
+fn write_piece(svg: &mut String, layout: &Layout, pos: BoardPosition, chess_piece: ChessPiece) {
+    let (x, y) = layout.center(pos);
+    let font_size = layout.square_size * 4 / 5;
+    // the filled glyph with an outline keeps white pieces readable on light squares
+    let (fill, stroke) = match chess_piece.as_color() {
+        Color::White => ("#ffffff", "#000000"),
+        Color::Black => ("#000000", "#000000"),
+    };
+    let glyph = ChessPiece::from(Color::Black, chess_piece.as_piece()).as_unicode_char();
+    let _ = writeln!(
+        svg,
+        r#"<text x="{x}" y="{y}" font-size="{font_size}" text-anchor="middle" dominant-baseline="central" fill="{fill}" stroke="{stroke}">{glyph}</text>"#
+    );
+}
+
+fn write_circle(svg: &mut String, layout: &Layout, pos: BoardPosition) {
+    let (x, y) = layout.center(pos);
+    let radius = layout.square_size * 9 / 20;
+    let stroke_width = layout.square_size / 15;
+    let _ = writeln!(
+        svg,
+        r#"<circle cx="{x}" cy="{y}" r="{radius}" fill="none" stroke="{ANNOTATION_STROKE}" stroke-width="{stroke_width}" opacity="0.8"/>"#
+    );
+}
+
+fn write_arrow(svg: &mut String, layout: &Layout, arrow: SvgArrow) {
+    let (x1, y1) = layout.center(arrow.from);
+    let (x2, y2) = layout.center(arrow.to);
+    let stroke_width = layout.square_size / 6;
+    let _ = writeln!(
+        svg,
+        r#"<line x1="{x1}" y1="{y1}" x2="{x2}" y2="{y2}" stroke="{ANNOTATION_STROKE}" stroke-width="{stroke_width}" stroke-linecap="round" marker-end="url(#arrowhead)" opacity="0.8"/>"#
+    );
+}
+
+pub fn render_board_svg(board: &Board, options: Option<BoardSvgOptions>) -> String {
+    let options = options.unwrap_or_default();
+    let layout = Layout::from_options(&options);
+    let size = layout.size();
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {size} {size}" width="{size}" height="{size}">"#
+    );
+    if !options.arrows.is_empty() {
+        let _ = writeln!(
+            svg,
+            r#"<defs><marker id="arrowhead" viewBox="0 0 10 10" refX="5" refY="5" markerWidth="2.5" markerHeight="2.5" orient="auto"><path d="M0,0 L10,5 L0,10 z" fill="{ANNOTATION_STROKE}"/></marker></defs>"#
+        );
+    }
+    for pos in all_positions() {
+        let is_light =
+            (pos.file().as_zero_based_index() + pos.rank().as_zero_based_index()) % 2 == 1;
+        let fill = if is_light {
+            LIGHT_SQUARE_FILL
+        } else {
+            DARK_SQUARE_FILL
+        };
+        write_square(&mut svg, &layout, pos, fill);
+    }
+    if let Some((from, to)) = options.last_move {
+        for pos in [from, to] {
+            write_square(&mut svg, &layout, pos, LAST_MOVE_FILL);
+        }
+    }
+    if let Some(pos) = options.check {
+        write_square(&mut svg, &layout, pos, CHECK_FILL);
+    }
+    if options.coordinates {
+        write_coordinates(&mut svg, &layout);
+    }
+    for pos in all_positions() {
+        if let Some(chess_piece) = *board.get(pos) {
+            write_piece(&mut svg, &layout, pos, chess_piece);
+        }
+    }
+    for &pos in options.circles.iter() {
+        write_circle(&mut svg, &layout, pos);
+    }
+    for &arrow in options.arrows.iter() {
+        write_arrow(&mut svg, &layout, arrow);
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+// fills in the last move and the checked king from the game state when not already set
+pub fn render_game_state_svg(game_state: &GameState, options: Option<BoardSvgOptions>) -> String {
+    let mut options = options.unwrap_or_default();
+    if options.last_move.is_none() {
+        options.last_move = game_state
+            .history
+            .move_history
+            .last()
+            .map(|entry| (entry.from, entry.to));
+    }
+    if options.check.is_none() && game_state.game_status.is_check() {
+        let king = ChessPiece::from(game_state.active_color, Piece::King);
+        options.check = game_state
+            .board
+            .as_iter()
+            .find(|(_, maybe_piece)| *maybe_piece == Some(king))
+            .map(|(pos, _)| pos);
+    }
+    render_board_svg(&game_state.board, Some(options))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::position::*;
+    use crate::chess_move::chess_move_handler::default_chess_move_handler;
+    use crate::notation::fen::deserialize;
+    use crate::notation::san::deserialize_san;
+    use rstest::rstest;
+
+    const EMPTY_BOARD_SVG: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 16 16" width="16" height="16">
+"##;
+
+    #[test]
+    fn test_render_board_svg_snapshot() {
+        let options = BoardSvgOptions {
+            square_size: 2,
+            coordinates: false,
+            ..BoardSvgOptions::new()
+        };
+        let svg = render_board_svg(&Board::empty(), Some(options.clone()));
+        assert!(svg.starts_with(EMPTY_BOARD_SVG));
+        assert_eq!(66, svg.lines().count());
+        assert_eq!(
+            r##"<rect x="0" y="14" width="2" height="2" fill="#b58863"/>"##,
+            svg.lines().nth(1).unwrap()
+        );
+        assert_eq!(svg, render_board_svg(&Board::empty(), Some(options)));
+    }
+
+    #[rstest]
+    #[case(false, A1, (0, 315))]
+    #[case(false, H8, (315, 0))]
+    #[case(true, A1, (315, 0))]
+    #[case(true, H8, (0, 315))]
+    fn test_orientation(
+        #[case] flipped: bool,
+        #[case] pos: BoardPosition,
+        #[case] expected: (u32, u32),
+    ) {
+        let options = BoardSvgOptions {
+            flipped,
+            coordinates: false,
+            ..BoardSvgOptions::new()
+        };
+        assert_eq!(expected, Layout::from_options(&options).origin(pos));
+    }
+
+    #[test]
+    fn test_render_board_svg_annotations() {
+        let options = BoardSvgOptions {
+            coordinates: false,
+            arrows: vec![SvgArrow { from: E2, to: E4 }],
+            circles: vec![D5],
+            ..BoardSvgOptions::new()
+        };
+        let svg = render_board_svg(&Board::new(), Some(options));
+        assert!(svg.contains(r#"marker-end="url(#arrowhead)""#));
+        assert!(svg.contains(r#"<line x1="202" y1="292" x2="202" y2="202""#));
+        assert!(svg.contains(r#"<circle cx="157" cy="157""#));
+        assert_eq!(32, svg.matches("</text>").count());
+    }
+
+    #[test]
+    fn test_render_game_state_svg_highlights() {
+        let mut game_state =
+            deserialize("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").expect("bad fen string!");
+        let chess_move = deserialize_san(&game_state, "Ra8+").expect("bad san!");
+        default_chess_move_handler(&mut game_state, &chess_move, None).expect("bad move!");
+        let svg = render_game_state_svg(&game_state, None);
+        // a1 and a8 as the last move, e8 as the checked king, with the coordinate margin
+        assert!(svg.contains(&format!(
+            r#"<rect x="22" y="337" width="45" height="45" fill="{LAST_MOVE_FILL}"/>"#
+        )));
+        assert!(svg.contains(&format!(
+            r#"<rect x="22" y="22" width="45" height="45" fill="{LAST_MOVE_FILL}"/>"#
+        )));
+        assert!(svg.contains(&format!(
+            r#"<rect x="202" y="22" width="45" height="45" fill="{CHECK_FILL}"/>"#
+        )));
+        assert!(svg.contains(">a</text>"));
+        assert!(svg.contains(">8</text>"));
+    }
+}
@@ -1,4 +1,6 @@
 pub mod board;
+pub mod board_diagram;
+pub mod board_svg;
 pub mod board_file;
 pub mod board_position;
 pub mod board_rank;
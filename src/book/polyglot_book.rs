@@ -0,0 +1,309 @@
+use crate::board::board_position::BoardPosition;
+use crate::book::polyglot_key::polyglot_key;
+use crate::chess_move::chess_move::{ChessMove, ChessMoveType};
+use crate::chess_move::chess_move_handler::try_handle_chess_move_and_apply;
+use crate::chess_move::chess_move_search::unchecked_chess_move_search;
+use crate::piece::promotion_piece::PromotionPiece;
+use crate::state::castle_rights::CastleRights;
+use crate::state::game_state::GameState;
+use crate::utils::random::Random;
+use std::path::Path;
+use thiserror::Error;
+
+pub const POLYGLOT_ENTRY_SIZE: usize = 16;
+
+#[derive(Error, Debug)]
+pub enum PolyglotBookError {
+    #[error("Failed to read polyglot book: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid polyglot book size: {0} is not a multiple of {POLYGLOT_ENTRY_SIZE} bytes")]
+    InvalidSize(usize),
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum BookSelection {
+    Best,
+    #[default]
+    Weighted,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PolyglotEntry {
+    pub key: u64,
+    pub raw_move: u16,
+    pub weight: u16,
+    pub learn: u32,
+}
+
+impl PolyglotEntry {
+    pub fn from_bytes(bytes: &[u8; POLYGLOT_ENTRY_SIZE]) -> Self {
+        let (key, rest) = bytes.split_at(8);
+        let (raw_move, rest) = rest.split_at(2);
+        let (weight, learn) = rest.split_at(2);
+        Self {
+            key: u64::from_be_bytes(key.try_into().expect("8 bytes")),
+            raw_move: u16::from_be_bytes(raw_move.try_into().expect("2 bytes")),
+            weight: u16::from_be_bytes(weight.try_into().expect("2 bytes")),
+            learn: u32::from_be_bytes(learn.try_into().expect("4 bytes")),
+        }
+    }
+    pub fn to_bytes(&self) -> [u8; POLYGLOT_ENTRY_SIZE] {
+        let mut bytes = [0; POLYGLOT_ENTRY_SIZE];
+        bytes[0..8].copy_from_slice(&self.key.to_be_bytes());
+        bytes[8..10].copy_from_slice(&self.raw_move.to_be_bytes());
+        bytes[10..12].copy_from_slice(&self.weight.to_be_bytes());
+        bytes[12..16].copy_from_slice(&self.learn.to_be_bytes());
+        bytes
+    }
+}
+
+// to file: 3 bits | to row: 3 bits | from file: 3 bits | from row: 3 bits | promotion: 3 bits
+pub fn encode_polyglot_move(game_state: &GameState, chess_move: &ChessMove) -> u16 {
+    let to = match chess_move.move_type {
+        // polyglot encodes castling as the king capturing its own rook
+        ChessMoveType::Castle(castle_side) => {
+            let rook_file = game_state
+                .castle_rights
+                .for_color(game_state.active_color)
+                .and_then(|castle_rights| castle_rights.rook_file(castle_side))
+                .unwrap_or(CastleRights::standard_rook_file(castle_side));
+            BoardPosition::from(rook_file, *chess_move.from.rank())
+        }
+        _ => chess_move.to,
+    };
+    let promotion = match chess_move.move_type {
+        ChessMoveType::Promotion(PromotionPiece::Knight) => 1,
+        ChessMoveType::Promotion(PromotionPiece::Bishop) => 2,
+        ChessMoveType::Promotion(PromotionPiece::Rook) => 3,
+        ChessMoveType::Promotion(PromotionPiece::Queen) => 4,
+        _ => 0,
+    };
+    to.as_pos_index() as u16 | (chess_move.from.as_pos_index() as u16) << 6 | promotion << 12
+}
+
+fn decode_promotion(raw_move: u16) -> Option<PromotionPiece> {
+    match raw_move >> 12 & 0b111 {
+        1 => Some(PromotionPiece::Knight),
+        2 => Some(PromotionPiece::Bishop),
+        3 => Some(PromotionPiece::Rook),
+        4 => Some(PromotionPiece::Queen),
+        _ => None,
+    }
+}
+
+// book moves are only trusted once they match a legal move in the current position
+pub fn decode_polyglot_move(game_state: &GameState, raw_move: u16) -> Option<ChessMove> {
+    let from = BoardPosition::from_pos_index((raw_move >> 6 & 0x3F) as usize)?;
+    let to = BoardPosition::from_pos_index((raw_move & 0x3F) as usize)?;
+    let promotion = decode_promotion(raw_move);
+    let chess_move = unchecked_chess_move_search(game_state, None)
+        .into_iter()
+        .find(|chess_move| {
+            chess_move.from == from
+                && match chess_move.move_type {
+                    ChessMoveType::Castle(_) => {
+                        encode_polyglot_move(game_state, chess_move) == raw_move
+                    }
+                    ChessMoveType::Promotion(promotion_piece) => {
+                        chess_move.to == to && promotion == Some(promotion_piece)
+                    }
+                    _ => chess_move.to == to && promotion.is_none(),
+                }
+        })?;
+    let mut new_game_state = game_state.clone();
+    try_handle_chess_move_and_apply(&mut new_game_state, &chess_move, None).ok()?;
+    Some(chess_move)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PolyglotBook {
+    entries: Vec<PolyglotEntry>,
+}
+
+impl PolyglotBook {
+    pub fn from_entries(mut entries: Vec<PolyglotEntry>) -> Self {
+        // lookups binary search on the key, books in the wild aren't always sorted
+        entries.sort_by_key(|entry| entry.key);
+        Self { entries }
+    }
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, PolyglotBookError> {
+        if !bytes.len().is_multiple_of(POLYGLOT_ENTRY_SIZE) {
+            return Err(PolyglotBookError::InvalidSize(bytes.len()));
+        }
+        let entries = bytes
+            .chunks_exact(POLYGLOT_ENTRY_SIZE)
+            .map(|chunk| PolyglotEntry::from_bytes(chunk.try_into().expect("16 bytes")))
+            .collect();
+        Ok(Self::from_entries(entries))
+    }
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, PolyglotBookError> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.entries
+            .iter()
+            .flat_map(PolyglotEntry::to_bytes)
+            .collect()
+    }
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+    pub fn entries_for_key(&self, key: u64) -> &[PolyglotEntry] {
+        let start = self.entries.partition_point(|entry| entry.key < key);
+        let end = self.entries.partition_point(|entry| entry.key <= key);
+        &self.entries[start..end]
+    }
+    pub fn book_moves(&self, game_state: &GameState) -> Vec<(ChessMove, u16)> {
+        self.entries_for_key(polyglot_key(game_state))
+            .iter()
+            .filter_map(|entry| {
+                decode_polyglot_move(game_state, entry.raw_move)
+                    .map(|chess_move| (chess_move, entry.weight))
+            })
+            .collect()
+    }
+    pub fn choose_move(
+        &self,
+        game_state: &GameState,
+        selection: BookSelection,
+        random: &mut Random,
+    ) -> Option<ChessMove> {
+        let book_moves = self.book_moves(game_state);
+        match selection {
+            // first entry wins ties so the choice is stable
+            BookSelection::Best => book_moves
+                .iter()
+                .rev()
+                .max_by_key(|(_, weight)| *weight)
+                .map(|(chess_move, _)| chess_move.clone()),
+            BookSelection::Weighted => {
+                let total_weight = book_moves
+                    .iter()
+                    .map(|(_, weight)| *weight as u64)
+                    .sum::<u64>();
+                if total_weight == 0 {
+                    return book_moves
+                        .into_iter()
+                        .next()
+                        .map(|(chess_move, _)| chess_move);
+                }
+                let mut pick = random.next_below(total_weight);
+                book_moves.into_iter().find_map(|(chess_move, weight)| {
+                    if pick < weight as u64 {
+                        Some(chess_move)
+                    } else {
+                        pick -= weight as u64;
+                        None
+                    }
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::position::*;
+    use crate::notation::fen::{deserialize, FEN_STARTING_POS};
+    use crate::piece::chess_piece::ChessPiece;
+    use rstest::rstest;
+
+    fn starting_book() -> PolyglotBook {
+        let game_state = deserialize(FEN_STARTING_POS).expect("bad fen string!");
+        let key = polyglot_key(&game_state);
+        let entry = |from: BoardPosition, to: BoardPosition, weight: u16| PolyglotEntry {
+            key,
+            raw_move: encode_polyglot_move(
+                &game_state,
+                &ChessMove::create_normal(ChessPiece::WhitePawn, from, to),
+            ),
+            weight,
+            learn: 0,
+        };
+        PolyglotBook::from_entries(vec![
+            entry(C2, C4, 10),
+            entry(E2, E4, 30),
+            entry(D2, D4, 30),
+            PolyglotEntry {
+                key: key + 1,
+                raw_move: 0,
+                weight: 1,
+                learn: 0,
+            },
+        ])
+    }
+
+    #[test]
+    fn test_round_trip_bytes() {
+        let book = starting_book();
+        let bytes = book.to_bytes();
+        assert_eq!(4 * POLYGLOT_ENTRY_SIZE, bytes.len());
+        assert_eq!(book, PolyglotBook::from_bytes(&bytes).expect("valid book"));
+        assert!(matches!(
+            PolyglotBook::from_bytes(&bytes[1..]),
+            Err(PolyglotBookError::InvalidSize(63))
+        ));
+    }
+
+    #[test]
+    fn test_book_moves() {
+        let book = starting_book();
+        let game_state = deserialize(FEN_STARTING_POS).expect("bad fen string!");
+        let book_moves = book.book_moves(&game_state);
+        assert_eq!(3, book_moves.len());
+        let best_move = book
+            .choose_move(&game_state, BookSelection::Best, &mut Random::new(0))
+            .expect("expected a book move");
+        assert_eq!((E2, E4), (best_move.from, best_move.to));
+        let out_of_book = deserialize("8/8/8/8/8/8/8/K6k w - - 0 1").expect("bad fen string!");
+        assert!(book.book_moves(&out_of_book).is_empty());
+    }
+
+    #[test]
+    fn test_weighted_selection() {
+        let book = starting_book();
+        let game_state = deserialize(FEN_STARTING_POS).expect("bad fen string!");
+        let mut random = Random::new(1);
+        let picks = (0..200)
+            .map(|_| {
+                let chess_move = book
+                    .choose_move(&game_state, BookSelection::Weighted, &mut random)
+                    .expect("expected a book move");
+                chess_move.from
+            })
+            .collect::<Vec<_>>();
+        for from in [C2, D2, E2] {
+            assert!(picks.contains(&from));
+        }
+        let c_pawn_picks = picks.iter().filter(|from| **from == C2).count();
+        let d_pawn_picks = picks.iter().filter(|from| **from == D2).count();
+        assert!(c_pawn_picks < d_pawn_picks);
+    }
+
+    #[rstest]
+    #[case("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1", E1, H1, G1)]
+    #[case("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1", E1, A1, C1)]
+    #[case("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1", E8, H8, G8)]
+    #[case("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1", E8, A8, C8)]
+    #[case("4k3/8/8/8/8/8/8/RK5R w HA - 0 1", B1, H1, G1)]
+    #[case("4k3/8/8/8/8/8/8/RK5R w HA - 0 1", B1, A1, C1)]
+    #[case("1r4kr/8/8/8/8/8/8/4K3 b hb - 0 1", G8, H8, G8)]
+    #[case("1r4kr/8/8/8/8/8/8/4K3 b hb - 0 1", G8, B8, C8)]
+    fn test_decode_castle(
+        #[case] fen: &'static str,
+        #[case] from: BoardPosition,
+        #[case] rook_pos: BoardPosition,
+        #[case] expected_to: BoardPosition,
+    ) {
+        let game_state = deserialize(fen).expect("bad fen string!");
+        let raw_move = rook_pos.as_pos_index() as u16 | (from.as_pos_index() as u16) << 6;
+        let chess_move = decode_polyglot_move(&game_state, raw_move).expect("expected castle");
+        assert!(matches!(chess_move.move_type, ChessMoveType::Castle(_)));
+        assert_eq!(expected_to, chess_move.to);
+        assert_eq!(raw_move, encode_polyglot_move(&game_state, &chess_move));
+    }
+}
@@ -0,0 +1,268 @@
+use crate::book::polyglot_book::{encode_polyglot_move, PolyglotBook, PolyglotEntry};
+use crate::book::polyglot_key::polyglot_key;
+use crate::color::Color;
+use crate::notation::pgn::deserialize::PgnParsingError;
+use crate::notation::pgn::{PgnGame, PgnReplayError, PgnResult};
+use std::collections::HashMap;
+use thiserror::Error;
+
+const DEFAULT_MAX_PLY: usize = 16;
+
+#[derive(Error, Debug, Clone)]
+pub enum PolyglotBuilderError {
+    #[error("Failed to parse pgn: {0}")]
+    InvalidPgn(#[from] PgnParsingError),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PolyglotBuilderOptions {
+    pub max_ply: usize,
+    pub min_games: u32,
+}
+
+impl PolyglotBuilderOptions {
+    pub const fn new() -> Self {
+        Self {
+            max_ply: DEFAULT_MAX_PLY,
+            min_games: 1,
+        }
+    }
+}
+
+impl Default for PolyglotBuilderOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct MoveStats {
+    pub games: u32,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+impl MoveStats {
+    // the usual polyglot scoring, a win is worth two draws and losses count for nothing
+    pub const fn weight(&self) -> u32 {
+        self.wins * 2 + self.draws
+    }
+}
+
+// games that can't be replayed are skipped, the rest of the file still counts
+#[derive(Clone, Debug, Default)]
+pub struct PgnImport {
+    pub added: usize,
+    // the index of each skipped game in the file
+    pub rejected: Vec<(usize, PgnReplayError)>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct PolyglotBuilder {
+    options: PolyglotBuilderOptions,
+    stats: HashMap<(u64, u16), MoveStats>,
+    games: usize,
+}
+
+impl PolyglotBuilder {
+    pub fn new(options: Option<PolyglotBuilderOptions>) -> Self {
+        Self {
+            options: options.unwrap_or_default(),
+            ..Self::default()
+        }
+    }
+    pub fn games(&self) -> usize {
+        self.games
+    }
+    pub fn stats(&self, key: u64, raw_move: u16) -> Option<MoveStats> {
+        self.stats.get(&(key, raw_move)).copied()
+    }
+    pub fn add_game(&mut self, game: &PgnGame) -> Result<(), PgnReplayError> {
+        let plies = game.replay(Some(self.options.max_ply))?;
+        for (game_state, chess_move) in plies {
+            let move_stats = self
+                .stats
+                .entry((
+                    polyglot_key(&game_state),
+                    encode_polyglot_move(&game_state, &chess_move),
+                ))
+                .or_default();
+            move_stats.games += 1;
+            match (game.result, game_state.active_color) {
+                (PgnResult::WhiteWins, Color::White) | (PgnResult::BlackWins, Color::Black) => {
+                    move_stats.wins += 1
+                }
+                (PgnResult::WhiteWins, Color::Black) | (PgnResult::BlackWins, Color::White) => {
+                    move_stats.losses += 1
+                }
+                (PgnResult::Draw, _) => move_stats.draws += 1,
+                (PgnResult::Unknown, _) => {}
+            }
+        }
+        self.games += 1;
+        Ok(())
+    }
+    pub fn add_pgn(&mut self, pgn: &str) -> Result<PgnImport, PolyglotBuilderError> {
+        let games = crate::notation::pgn::deserialize(pgn)?;
+        let mut import = PgnImport::default();
+        for (ix, game) in games.iter().enumerate() {
+            match self.add_game(game) {
+                Ok(()) => import.added += 1,
+                Err(err) => import.rejected.push((ix, err)),
+            }
+        }
+        Ok(import)
+    }
+    pub fn build(&self) -> PolyglotBook {
+        let mut max_weights: HashMap<u64, u32> = HashMap::new();
+        let candidates = self
+            .stats
+            .iter()
+            .filter(|(_, move_stats)| {
+                move_stats.games >= self.options.min_games && move_stats.weight() > 0
+            })
+            .collect::<Vec<_>>();
+        for ((key, _), move_stats) in candidates.iter() {
+            let max_weight = max_weights.entry(*key).or_default();
+            *max_weight = (*max_weight).max(move_stats.weight());
+        }
+        let entries = candidates
+            .into_iter()
+            .map(|((key, raw_move), move_stats)| {
+                // weights only have 16 bits, scale each position down if its best move overflows
+                let max_weight = max_weights[key].max(u16::MAX as u32) as u64;
+                let weight = move_stats.weight() as u64 * u16::MAX as u64 / max_weight;
+                PolyglotEntry {
+                    key: *key,
+                    raw_move: *raw_move,
+                    weight: weight.max(1) as u16,
+                    learn: 0,
+                }
+            })
+            .collect::<Vec<_>>();
+        PolyglotBook::from_entries(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::position::*;
+    use crate::book::polyglot_book::BookSelection;
+    use crate::chess_move::chess_move::ChessMove;
+    use crate::notation::fen::{deserialize, FEN_STARTING_POS};
+    use crate::piece::chess_piece::ChessPiece;
+    use crate::utils::random::Random;
+
+    const PGN: &str = r#"
+[Result "1-0"]
+1. e4 e5 2. Nf3 Nc6 1-0
+
+[Result "1/2-1/2"]
+1. e4 c5 2. Nf3 d6 1/2-1/2
+
+[Result "0-1"]
+1. d4 d5 2. c4 e6 0-1
+
+[Result "1-0"]
+1. e4 e5 2. Bc4 Nf6 1-0
+"#;
+
+    #[test]
+    fn test_build_book() {
+        let mut builder = PolyglotBuilder::new(Some(PolyglotBuilderOptions {
+            max_ply: 2,
+            min_games: 1,
+        }));
+        assert_eq!(4, builder.add_pgn(PGN).expect("valid pgn").added);
+        let game_state = deserialize(FEN_STARTING_POS).expect("bad fen string!");
+        let key = polyglot_key(&game_state);
+        let e4 = builder
+            .stats(
+                key,
+                encode_polyglot_move(
+                    &game_state,
+                    &ChessMove::create_normal(ChessPiece::WhitePawn, E2, E4),
+                ),
+            )
+            .expect("e4 was played");
+        assert_eq!(
+            MoveStats {
+                games: 3,
+                wins: 2,
+                draws: 1,
+                losses: 0
+            },
+            e4
+        );
+
+        let book = PolyglotBook::from_bytes(&builder.build().to_bytes()).expect("valid book");
+        // d4 only lost so it never makes it into the book
+        let book_moves = book.book_moves(&game_state);
+        assert_eq!(1, book_moves.len());
+        let best_move = book
+            .choose_move(&game_state, BookSelection::Best, &mut Random::new(0))
+            .expect("book move");
+        assert_eq!(E4, best_move.to);
+        // black's drawing reply c5 is in book too
+        assert!(book.len() > 1);
+        assert_eq!(1, book.entries_for_key(key).len());
+    }
+
+    #[test]
+    fn test_add_pgn_skips_unreplayable_games() {
+        let pgn = r#"
+[Result "1-0"]
+1. e4 e5 1-0
+
+[Result "0-1"]
+1. e4 c5 0-1
+
+[Result "1-0"]
+1. e4 Ke6 1-0
+
+[Result "1/2-1/2"]
+1. d4 d5 1/2-1/2
+"#;
+        let mut builder = PolyglotBuilder::new(Some(PolyglotBuilderOptions {
+            max_ply: 2,
+            min_games: 1,
+        }));
+        let import = builder.add_pgn(pgn).expect("valid pgn");
+        assert_eq!(3, import.added);
+        assert_eq!(
+            vec![2],
+            import
+                .rejected
+                .iter()
+                .map(|(ix, _)| *ix)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(3, builder.games());
+        let game_state = deserialize(FEN_STARTING_POS).expect("bad fen string!");
+        let e4 = builder
+            .stats(
+                polyglot_key(&game_state),
+                encode_polyglot_move(
+                    &game_state,
+                    &ChessMove::create_normal(ChessPiece::WhitePawn, E2, E4),
+                ),
+            )
+            .expect("e4 was played");
+        // the rejected game never touched the stats
+        assert_eq!(2, e4.games);
+    }
+
+    #[test]
+    fn test_min_games_filter() {
+        let mut builder = PolyglotBuilder::new(Some(PolyglotBuilderOptions {
+            max_ply: 4,
+            min_games: 2,
+        }));
+        builder.add_pgn(PGN).expect("valid pgn");
+        let book = builder.build();
+        // 1... e5 was played twice but lost both times
+        assert_eq!(1, book.len());
+    }
+}
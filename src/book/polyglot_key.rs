@@ -0,0 +1,124 @@
+use crate::board::board_position::BoardPosition;
+use crate::book::polyglot_random::POLYGLOT_RANDOM;
+use crate::color::Color;
+use crate::direction::castle_side::CastleSide;
+use crate::piece::chess_piece::ChessPiece;
+use crate::piece::piece::Piece;
+use crate::state::game_state::GameState;
+
+const CASTLE_OFFSET: usize = 768;
+const EN_PASSANT_OFFSET: usize = 772;
+const TURN_OFFSET: usize = 780;
+
+const fn piece_kind(chess_piece: ChessPiece) -> usize {
+    let role = match chess_piece.as_piece() {
+        Piece::Pawn => 0,
+        Piece::Knight => 1,
+        Piece::Bishop => 2,
+        Piece::Rook => 3,
+        Piece::Queen => 4,
+        Piece::King => 5,
+    };
+    match chess_piece.as_color() {
+        Color::Black => role * 2,
+        Color::White => role * 2 + 1,
+    }
+}
+
+// polyglot only hashes the en passant file when the side to move has a pawn that can capture
+fn has_en_passant_capture(game_state: &GameState, en_passant_target_pos: BoardPosition) -> bool {
+    let target_index = en_passant_target_pos.as_pos_index();
+    let pawn_index = match game_state.active_color {
+        Color::White => target_index.checked_sub(8),
+        Color::Black => Some(target_index + 8),
+    };
+    let Some(pawn_pos) = pawn_index.and_then(BoardPosition::from_pos_index) else {
+        return false;
+    };
+    let capturing_pawn = ChessPiece::from(game_state.active_color, Piece::Pawn);
+    [pawn_pos.file().prev(), pawn_pos.file().next()]
+        .into_iter()
+        .flatten()
+        .any(|file| {
+            *game_state
+                .board
+                .get(BoardPosition::from(file, *pawn_pos.rank()))
+                == Some(capturing_pawn)
+        })
+}
+
+pub fn polyglot_key(game_state: &GameState) -> u64 {
+    let mut key = 0;
+    for (pos, maybe_piece) in game_state.board.as_iter() {
+        let Some(piece) = maybe_piece else { continue };
+        key ^= POLYGLOT_RANDOM[64 * piece_kind(piece) + pos.as_pos_index()];
+    }
+    for (ix, color) in [Color::White, Color::Black].into_iter().enumerate() {
+        let Some(castle_rights) = game_state.castle_rights.for_color(color) else {
+            continue;
+        };
+        if castle_rights.has(CastleSide::King) {
+            key ^= POLYGLOT_RANDOM[CASTLE_OFFSET + ix * 2];
+        }
+        if castle_rights.has(CastleSide::Queen) {
+            key ^= POLYGLOT_RANDOM[CASTLE_OFFSET + ix * 2 + 1];
+        }
+    }
+    if let Some(en_passant_target_pos) = game_state.en_passant_target_pos {
+        if has_en_passant_capture(game_state, en_passant_target_pos) {
+            key ^= POLYGLOT_RANDOM
+                [EN_PASSANT_OFFSET + en_passant_target_pos.file().as_zero_based_index()];
+        }
+    }
+    if game_state.active_color == Color::White {
+        key ^= POLYGLOT_RANDOM[TURN_OFFSET];
+    }
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::fen::{deserialize, FEN_STARTING_POS};
+    use rstest::rstest;
+
+    // reference keys from the polyglot book format specification
+    #[rstest]
+    #[case(FEN_STARTING_POS, 0x463B_9618_1691_FC9C)]
+    #[case(
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+        0x823C_9B50_FD11_4196
+    )]
+    #[case(
+        "rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 2",
+        0x0756_B944_61C5_0FB0
+    )]
+    #[case(
+        "rnbqkbnr/ppp1pppp/8/3pP3/8/8/PPPP1PPP/RNBQKBNR b KQkq - 0 2",
+        0x662F_AFB9_65DB_29D4
+    )]
+    #[case(
+        "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+        0x22A4_8B5A_8E47_FF78
+    )]
+    #[case(
+        "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPPKPPP/RNBQ1BNR b kq - 0 3",
+        0x652A_607C_A3F2_42C1
+    )]
+    #[case(
+        "rnbq1bnr/ppp1pkpp/8/3pPp2/8/8/PPPPKPPP/RNBQ1BNR w - - 0 4",
+        0x00FD_D303_C946_BDD9
+    )]
+    #[case(
+        "rnbqkbnr/p1pppppp/8/8/PpP4P/8/1P1PPPP1/RNBQKBNR b KQkq c3 0 3",
+        0x3C81_23EA_7B06_7637
+    )]
+    #[case(
+        "rnbqkbnr/p1pppppp/8/8/P6P/R1p5/1P1PPPP1/1NBQKBNR b Kkq - 0 4",
+        0x5C3F_9B82_9B27_9560
+    )]
+    fn test_polyglot_key(#[case] fen: &'static str, #[case] expected: u64) {
+        let game_state = deserialize(fen).expect("bad fen string!");
+        assert_eq!(expected, polyglot_key(&game_state));
+    }
+}
@@ -5,6 +5,7 @@ use crate::piece::promotion_piece::PromotionPiece;
 use std::fmt::{Display, Formatter};
 
 #[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ChessMoveType {
     Normal,
     EnPassant(BoardPosition),
@@ -13,6 +14,7 @@ pub enum ChessMoveType {
 }
 
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ChessMove {
     pub move_type: ChessMoveType,
     pub piece: ChessPiece,
@@ -1,13 +1,11 @@
-use crate::board::board_file::BoardFile;
 use crate::board::board_position::BoardPosition;
 use crate::board::board_rank::BoardRank;
 use crate::chess_move::chess_move::{ChessMove, ChessMoveType};
 use crate::chess_move::invalid_chess_move_error::InvalidChessMoveError;
 use crate::color::Color;
-use crate::direction::castle_side::CastleSide;
+use crate::direction::direction::Direction;
 use crate::piece::chess_piece::ChessPiece;
 use crate::piece::piece::Piece;
-use crate::state::castle_rights::CastleRights;
 use crate::state::game_state::GameState;
 use crate::state::game_status::{
     is_check, is_check_for_color, is_check_mate, is_stalemate, GameStatus,
@@ -68,51 +66,54 @@ pub fn default_chess_move_handler(
             if is_in_check {
                 return Err(InvalidChessMoveError::CastleWhileInCheck);
             }
-            let mut maybe_capture: Option<ChessPiece>;
+            let back_rank = *requested_chess_move.from.rank();
+            let Some(rook_file) = game_state
+                .castle_rights
+                .for_color(moving_piece_color)
+                .and_then(|castle_rights| castle_rights.rook_file(castle_side))
+            else {
+                return Err(InvalidChessMoveError::InvalidMove(
+                    requested_chess_move.from,
+                    requested_chess_move.to,
+                ));
+            };
+            let rook_start_pos = BoardPosition(rook_file, back_rank);
+            let rook_end_pos = BoardPosition(castle_side.rook_destination_file(), back_rank);
+            // lift the rook first, in chess960 it can stand on a square the king passes through
+            let rook = game_state.board.get_mut(rook_start_pos).take();
+            game_state.game_status = GameStatus::InProgress;
+            let mut maybe_capture: Option<ChessPiece> = None;
             let mut last_pos = requested_chess_move.from;
+            // in chess960 the king can move either way, or not at all
+            let direction = if requested_chess_move.to.file() > requested_chess_move.from.file() {
+                Direction::East
+            } else {
+                Direction::West
+            };
             while last_pos != requested_chess_move.to {
-                if let Some(next_pos) =
-                    last_pos.next_pos(castle_side.as_simple_direction().as_direction())
-                {
-                    maybe_capture = chess_move_unchecked(game_state, last_pos, next_pos);
-                    // shouldn't have replaced any pieces a long the way
-                    if maybe_capture != requested_chess_move.captured_piece {
-                        return Err(InvalidChessMoveError::UnexpectedCapture(
-                            requested_chess_move.captured_piece,
-                            maybe_capture,
-                        ));
-                    }
-                    last_pos = next_pos;
-                    if is_check_for_color(game_state, moving_piece_color) {
-                        if is_in_check {
-                            return Err(InvalidChessMoveError::StillInCheck);
-                        }
-                        return Err(InvalidChessMoveError::MoveIntoCheck);
-                    }
+                let Some(next_pos) = last_pos.next_pos(direction) else {
+                    panic!("bad game state or requested chess_move")
+                };
+                maybe_capture = chess_move_unchecked(game_state, last_pos, next_pos);
+                // shouldn't have replaced any pieces a long the way
+                if maybe_capture != requested_chess_move.captured_piece {
+                    return Err(InvalidChessMoveError::UnexpectedCapture(
+                        requested_chess_move.captured_piece,
+                        maybe_capture,
+                    ));
+                }
+                last_pos = next_pos;
+                if is_check_for_color(game_state, moving_piece_color) {
+                    return Err(InvalidChessMoveError::MoveIntoCheck);
                 }
             }
             // handle moving the rook
-            let Some(rook_end_pos) =
-                last_pos.next_pos(castle_side.as_simple_direction().as_direction().reverse())
-            else {
+            if game_state.board.replace(rook_end_pos, rook).is_some() {
                 panic!("bad game state or requested chess_move")
-            };
-            let Some(mut rook_start_pos) =
-                last_pos.next_pos(castle_side.as_simple_direction().as_direction())
-            else {
-                panic!("bad game state or requested chess_move");
-            };
-            // go west one square for queen side rook
-            if *rook_start_pos.file() == BoardFile::B {
-                rook_start_pos = BoardPosition(BoardFile::A, *rook_start_pos.rank());
             }
-            maybe_capture = chess_move_unchecked(game_state, rook_start_pos, rook_end_pos);
-            // shouldn't have replaced any pieces
-            if maybe_capture != requested_chess_move.captured_piece {
-                return Err(InvalidChessMoveError::UnexpectedCapture(
-                    requested_chess_move.captured_piece,
-                    maybe_capture,
-                ));
+            // the rook leaving its square can open a line onto the king in chess960
+            if is_check_for_color(game_state, moving_piece_color) {
+                return Err(InvalidChessMoveError::MoveIntoCheck);
             }
             maybe_capture
         }
@@ -226,18 +227,39 @@ pub fn default_chess_move_handler(
         game_state.move_counter.full_move += 1;
     }
 
-    if moving_piece_type == Piece::King && was_at_starting_pos {
-        // remove castle rights when the king moves
+    if moving_piece_type == Piece::King {
+        // remove castle rights when the king moves, it can start anywhere on the back rank in chess960
         game_state.castle_rights.for_color_mut(active_color).take();
     }
 
-    if moving_piece_type == Piece::Rook && was_at_starting_pos {
-        // empty out the castle rights
-        if let Some(castle_rights) = game_state.castle_rights.for_color_mut(active_color).take() {
-            // re apply new rights, if any
-            *game_state.castle_rights.for_color_mut(active_color) = castle_rights.without(
-                CastleRights::from_castle_side(CastleSide::from_pos(requested_chess_move.from)),
-            );
+    if moving_piece_type == Piece::Rook
+        && *requested_chess_move.from.rank() == active_color.back_rank()
+    {
+        let castle_rights = game_state.castle_rights.for_color_mut(active_color);
+        // only the castle side belonging to this rook is lost
+        if let Some(castle_side) = castle_rights.and_then(|castle_rights| {
+            castle_rights.castle_side_for_rook_file(*requested_chess_move.from.file())
+        }) {
+            *castle_rights =
+                castle_rights.and_then(|castle_rights| castle_rights.without(castle_side));
+        }
+    }
+
+    if let Some(captured_piece) = maybe_capture {
+        let captured_color = captured_piece.as_color();
+        let castle_rights = game_state.castle_rights.for_color_mut(captured_color);
+        // capturing a rook that could still castle takes that right away
+        if let Some(castle_side) = castle_rights
+            .filter(|_| {
+                captured_piece.as_piece() == Piece::Rook
+                    && *requested_chess_move.to.rank() == captured_color.back_rank()
+            })
+            .and_then(|castle_rights| {
+                castle_rights.castle_side_for_rook_file(*requested_chess_move.to.file())
+            })
+        {
+            *castle_rights =
+                castle_rights.and_then(|castle_rights| castle_rights.without(castle_side));
         }
     }
 
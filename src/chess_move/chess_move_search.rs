@@ -1,3 +1,5 @@
+use crate::board::board::Board;
+use crate::board::board_file::BoardFile;
 use crate::board::board_position::BoardPosition;
 use crate::board::board_rank::BoardRank;
 use crate::board::board_scanner::BoardScanner;
@@ -11,7 +13,6 @@ use crate::direction::castle_side::CastleSide;
 use crate::piece::chess_piece_move_ruleset::ChessPieceMoveSet;
 use crate::piece::piece::Piece;
 use crate::piece::promotion_piece::PromotionPiece;
-use crate::state::castle_rights::CastleRights;
 use crate::state::game_state::GameState;
 
 #[derive(Debug, Default, Copy, Clone)]
@@ -298,6 +299,26 @@ pub fn provisional_chess_moves_for_capture_only(
     unchecked_moves
 }
 
+fn files_between(a: BoardFile, b: BoardFile) -> impl Iterator<Item = BoardFile> {
+    let (min, max) = if a < b { (a, b) } else { (b, a) };
+    (min.as_zero_based_index()..=max.as_zero_based_index())
+        .filter_map(|ix| BoardFile::from_zero_based_index(ix).ok())
+}
+
+// every square either piece passes through or lands on has to be empty, apart from the castling king and rook
+pub fn is_castle_path_clear(
+    board: &Board,
+    king_pos: BoardPosition,
+    rook_pos: BoardPosition,
+    castle_side: CastleSide,
+) -> bool {
+    let rank = *king_pos.rank();
+    files_between(*king_pos.file(), castle_side.king_destination_file())
+        .chain(files_between(*rook_pos.file(), castle_side.rook_destination_file()))
+        .map(|file| BoardPosition(file, rank))
+        .all(|pos| pos == king_pos || pos == rook_pos || board.get(pos).is_none())
+}
+
 pub fn provisional_chess_moves_for_castle(
     game_state: &GameState,
     from_pos: BoardPosition,
@@ -326,37 +347,28 @@ pub fn provisional_chess_moves_for_castle(
             let Ok(castle_side) = CastleSide::try_from_direction(da.direction()) else {
                 panic!("bad ruleset!")
             };
-            if !castle_rights.has(CastleRights::from_castle_side(castle_side)) {
+            let Some(rook_file) = castle_rights.rook_file(castle_side) else {
+                return unchecked_moves;
+            };
+            let back_rank = piece.as_color().back_rank();
+            if *from_pos.rank() != back_rank {
                 return unchecked_moves;
             }
-            let mut amount_remaining = da.amount();
-            let mut target_pos: Option<BoardPosition> = None;
-            for (pos, maybe_blocking_piece) in
-                BoardScanner::from_pos(&game_state.board, from_pos, da.direction())
-            {
-                // prevent underflow since we go beyond the original amount to make sure a rook is present
-                amount_remaining = amount_remaining.saturating_sub(1);
-                if amount_remaining == 0 && target_pos.is_none() {
-                    target_pos = Some(pos);
-                }
-                if let Some(blocking_piece) = maybe_blocking_piece {
-                    let Some(target_pos) = target_pos else {
-                        return unchecked_moves;
-                    };
-                    if blocking_piece.as_color() != piece.as_color()
-                        || blocking_piece.as_piece() != Piece::Rook
-                    {
-                        return unchecked_moves;
-                    }
-                    // if starting pos requirement is set, check the rook as well
-                    if ruleset.only_from_starting_pos && game_state.board.is_pos_starting_pos(pos) {
-                        unchecked_moves.push(
-                            ChessMove::create_castle(piece, from_pos, target_pos, castle_side)
-                        );
-                    }
-                    break;
-                }
+            // king and rook can start on any file in chess960, castle rights say which rook it is
+            let rook_pos = BoardPosition(rook_file, back_rank);
+            let Some(rook) = game_state.board.get(rook_pos) else {
+                return unchecked_moves;
+            };
+            if rook.as_color() != piece.as_color() || rook.as_piece() != Piece::Rook {
+                return unchecked_moves;
+            }
+            if !is_castle_path_clear(&game_state.board, from_pos, rook_pos, castle_side) {
+                return unchecked_moves;
             }
+            let target_pos = BoardPosition(castle_side.king_destination_file(), back_rank);
+            unchecked_moves.push(
+                ChessMove::create_castle(piece, from_pos, target_pos, castle_side)
+            );
         }
     }
     unchecked_moves
@@ -371,7 +383,11 @@ pub fn provisional_chess_moves_from_rulesets(
     let options = options.unwrap_or_default();
     let mut valid_moves = Vec::new();
     for ruleset in move_rulesets {
-        if ruleset.only_from_starting_pos && !game_state.board.is_pos_starting_pos(from_pos) {
+        // castle rights already track whether the king has moved, which in chess960 isn't on the e file
+        if ruleset.only_from_starting_pos
+            && !matches!(ruleset.move_type, ChessMoveRulesetType::Castle)
+            && !game_state.board.is_pos_starting_pos(from_pos)
+        {
             continue;
         }
         let mut new_valid_moves = match ruleset.move_type {
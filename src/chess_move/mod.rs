@@ -3,3 +3,4 @@ pub mod chess_move_handler;
 pub mod chess_move_ruleset;
 pub mod chess_move_search;
 pub mod invalid_chess_move_error;
+pub mod perft;
@@ -0,0 +1,57 @@
+use crate::chess_move::chess_move_handler::{try_handle_chess_move, ChessMoveHandlerOptions};
+use crate::chess_move::chess_move_search::unchecked_chess_move_search;
+use crate::state::game_state::GameState;
+
+// mate and stalemate fall out of the move count, so the status bookkeeping can be skipped
+const PERFT_HANDLER_OPTIONS: ChessMoveHandlerOptions = ChessMoveHandlerOptions {
+    color_override: None,
+    skip_updating_game_status: true,
+    skip_check_mate_check: true,
+    skip_stale_mate_check: true,
+};
+
+pub fn perft(game_state: &GameState, depth: u8) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    unchecked_chess_move_search(game_state, None)
+        .iter()
+        .filter_map(|chess_move| {
+            try_handle_chess_move(game_state, chess_move, Some(PERFT_HANDLER_OPTIONS)).ok()
+        })
+        .map(|next_game_state| {
+            if depth == 1 {
+                1
+            } else {
+                perft(&next_game_state, depth - 1)
+            }
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::fen::{deserialize, FEN_STARTING_POS};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(FEN_STARTING_POS, 1, 20)]
+    #[case(FEN_STARTING_POS, 2, 400)]
+    #[case(FEN_STARTING_POS, 3, 8902)]
+    #[case(
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        1,
+        48
+    )]
+    #[case(
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        2,
+        2039
+    )]
+    #[case("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", 3, 2812)]
+    fn test_perft(#[case] fen: &str, #[case] depth: u8, #[case] expected: u64) {
+        let game_state = deserialize(fen).expect("bad fen string!");
+        assert_eq!(expected, perft(&game_state, depth));
+    }
+}
@@ -0,0 +1,241 @@
+use crate::clock::time_control::{TimeBonus, TimeControl};
+use crate::clock::time_source::{SystemTimeSource, TimeSource};
+use crate::color::Color;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct SideClock {
+    // as of the start of the current turn
+    remaining: Duration,
+    stage: usize,
+    stage_moves: u32,
+    moves: u32,
+}
+
+#[derive(Clone, Debug)]
+pub struct Clock {
+    time_control: TimeControl,
+    time_source: Arc<dyn TimeSource>,
+    white: SideClock,
+    black: SideClock,
+    // whose clock is running and when it was started
+    running: Option<(Color, Duration)>,
+}
+
+impl Clock {
+    pub fn new(time_control: TimeControl, time_source: Arc<dyn TimeSource>) -> Self {
+        let side = SideClock {
+            remaining: time_control.stage(0).time,
+            stage: 0,
+            stage_moves: 0,
+            moves: 0,
+        };
+        Self {
+            time_control,
+            time_source,
+            white: side,
+            black: side,
+            running: None,
+        }
+    }
+    pub fn with_system_time(time_control: TimeControl) -> Self {
+        Self::new(time_control, Arc::new(SystemTimeSource::new()))
+    }
+    pub const fn time_control(&self) -> &TimeControl {
+        &self.time_control
+    }
+    const fn side(&self, color: Color) -> &SideClock {
+        match color {
+            Color::White => &self.white,
+            Color::Black => &self.black,
+        }
+    }
+    fn side_mut(&mut self, color: Color) -> &mut SideClock {
+        match color {
+            Color::White => &mut self.white,
+            Color::Black => &mut self.black,
+        }
+    }
+    pub fn running(&self) -> Option<Color> {
+        self.running.map(|(color, _)| color)
+    }
+    pub fn moves(&self, color: Color) -> u32 {
+        self.side(color).moves
+    }
+    // the time used this turn once any delay is taken off
+    fn charged(&self, color: Color, elapsed: Duration) -> Duration {
+        match self.time_control.stage(self.side(color).stage).bonus {
+            TimeBonus::Delay(delay) => elapsed.saturating_sub(delay),
+            _ => elapsed,
+        }
+    }
+    fn elapsed(&self, now: Duration) -> Option<(Color, Duration)> {
+        self.running
+            .map(|(color, started)| (color, now.saturating_sub(started)))
+    }
+    pub fn remaining(&self, color: Color) -> Duration {
+        let side = self.side(color);
+        match self.elapsed(self.time_source.now()) {
+            Some((running, elapsed)) if running == color => {
+                side.remaining.saturating_sub(self.charged(color, elapsed))
+            }
+            _ => side.remaining,
+        }
+    }
+    // a running clock that has reached zero
+    pub fn flagged(&self) -> Option<Color> {
+        self.running()
+            .filter(|color| self.remaining(*color).is_zero())
+    }
+    pub fn start(&mut self, color: Color) {
+        self.stop();
+        self.running = Some((color, self.time_source.now()));
+    }
+    // pauses the running clock, there's no bonus since no move was made
+    pub fn stop(&mut self) {
+        if let Some(color) = self.running() {
+            let remaining = self.remaining(color);
+            self.side_mut(color).remaining = remaining;
+            self.running = None;
+        }
+    }
+    // ends color's turn and starts the opponent's clock, returns color if its flag fell first,
+    // the first move of a clock that was never started is free
+    pub fn press(&mut self, color: Color) -> Option<Color> {
+        let now = self.time_source.now();
+        let elapsed = match self.elapsed(now) {
+            Some((running, elapsed)) if running == color => elapsed,
+            Some(_) => return None,
+            None => Duration::ZERO,
+        };
+        let charged = self.charged(color, elapsed);
+        let side = *self.side(color);
+        if charged >= side.remaining {
+            self.side_mut(color).remaining = Duration::ZERO;
+            self.running = None;
+            return Some(color);
+        }
+        let stage = *self.time_control.stage(side.stage);
+        let mut remaining = side.remaining - charged
+            + match stage.bonus {
+                TimeBonus::Increment(increment) => increment,
+                TimeBonus::Bronstein(delay) => elapsed.min(delay),
+                TimeBonus::None | TimeBonus::Delay(_) => Duration::ZERO,
+            };
+        let mut next = SideClock {
+            moves: side.moves + 1,
+            stage_moves: side.stage_moves + 1,
+            ..side
+        };
+        if stage.moves.is_some_and(|moves| next.stage_moves >= moves) {
+            next.stage = self.time_control.next_stage_index(side.stage);
+            next.stage_moves = 0;
+            remaining += self.time_control.stage(next.stage).time;
+        }
+        next.remaining = remaining;
+        *self.side_mut(color) = next;
+        self.running = Some((color.as_inverse(), now));
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::time_control::TimeControlStage;
+    use crate::clock::time_source::ManualTimeSource;
+    use rstest::rstest;
+
+    const fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    fn clock(time_control: TimeControl) -> (Clock, Arc<ManualTimeSource>) {
+        let time = Arc::new(ManualTimeSource::new());
+        (Clock::new(time_control, time.clone()), time)
+    }
+
+    // white thinks for each of think and black moves instantly
+    #[rstest]
+    #[case(TimeControl::sudden_death(secs(60)), &[10, 20], secs(30))]
+    #[case(TimeControl::fischer(secs(60), secs(2)), &[10, 20], secs(34))]
+    #[case(TimeControl::simple_delay(secs(60), secs(5)), &[3, 20], secs(45))]
+    #[case(TimeControl::bronstein(secs(60), secs(5)), &[3, 20], secs(45))]
+    fn test_time_bonus(
+        #[case] time_control: TimeControl,
+        #[case] think: &[u64],
+        #[case] expected: Duration,
+    ) {
+        let (mut clock, time) = clock(time_control);
+        clock.start(Color::White);
+        for think in think {
+            time.advance(secs(*think));
+            assert_eq!(None, clock.press(Color::White));
+            assert_eq!(None, clock.press(Color::Black));
+        }
+        assert_eq!(expected, clock.remaining(Color::White));
+        assert_eq!(Some(Color::White), clock.running());
+    }
+
+    #[test]
+    fn test_remaining_while_running() {
+        let (mut clock, time) = clock(TimeControl::simple_delay(secs(60), secs(5)));
+        clock.start(Color::White);
+        time.advance(secs(4));
+        assert_eq!(secs(60), clock.remaining(Color::White));
+        time.advance(secs(6));
+        assert_eq!(secs(55), clock.remaining(Color::White));
+        assert_eq!(secs(60), clock.remaining(Color::Black));
+        clock.stop();
+        time.advance(secs(100));
+        assert_eq!(secs(55), clock.remaining(Color::White));
+        assert_eq!(None, clock.running());
+    }
+
+    #[test]
+    fn test_flag() {
+        let (mut clock, time) = clock(TimeControl::fischer(secs(10), secs(1)));
+        clock.start(Color::White);
+        time.advance(secs(9));
+        assert_eq!(None, clock.flagged());
+        time.advance(secs(1));
+        assert_eq!(Some(Color::White), clock.flagged());
+        assert_eq!(Some(Color::White), clock.press(Color::White));
+        assert_eq!(Duration::ZERO, clock.remaining(Color::White));
+        assert_eq!(None, clock.running());
+    }
+
+    #[test]
+    fn test_multi_stage() {
+        // 2 moves in 10s then 5s for the rest, with a 1s increment throughout
+        let stages = vec![
+            TimeControlStage::moves(2, secs(10), TimeBonus::Increment(secs(1))),
+            TimeControlStage::sudden_death(secs(5), TimeBonus::Increment(secs(1))),
+        ];
+        let (mut clock, time) = clock(TimeControl::new(stages));
+        clock.start(Color::White);
+        for expected in [secs(8), secs(5 + 6)] {
+            time.advance(secs(3));
+            clock.press(Color::White);
+            clock.press(Color::Black);
+            assert_eq!(expected, clock.remaining(Color::White));
+        }
+        time.advance(secs(3));
+        clock.press(Color::White);
+        assert_eq!(secs(9), clock.remaining(Color::White));
+        assert_eq!(3, clock.moves(Color::White));
+    }
+
+    #[test]
+    fn test_press_out_of_turn() {
+        let (mut clock, time) = clock(TimeControl::sudden_death(secs(60)));
+        clock.press(Color::White);
+        assert_eq!(Some(Color::Black), clock.running());
+        time.advance(secs(5));
+        assert_eq!(None, clock.press(Color::White));
+        assert_eq!(Some(Color::Black), clock.running());
+        assert_eq!(secs(55), clock.remaining(Color::Black));
+        assert_eq!(secs(60), clock.remaining(Color::White));
+    }
+}
@@ -0,0 +1,4 @@
+pub mod clock;
+pub mod time_control;
+pub mod time_source;
+pub mod timed_move_handler;
@@ -0,0 +1,185 @@
+use std::str::FromStr;
+use std::time::Duration;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum TimeControlParsingError {
+    #[error("Empty time control")]
+    Empty,
+    #[error("Invalid time control period: {0}")]
+    InvalidPeriod(String),
+}
+
+// what a player gets back for each move
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TimeBonus {
+    None,
+    // fischer, added after every move
+    Increment(Duration),
+    // simple or us delay, the clock only starts counting down once the delay has passed
+    Delay(Duration),
+    // the time used is given back, up to the delay
+    Bronstein(Duration),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TimeControlStage {
+    // None for the rest of the game
+    pub moves: Option<u32>,
+    pub time: Duration,
+    pub bonus: TimeBonus,
+}
+
+impl TimeControlStage {
+    pub const fn sudden_death(time: Duration, bonus: TimeBonus) -> Self {
+        Self {
+            moves: None,
+            time,
+            bonus,
+        }
+    }
+    pub const fn moves(moves: u32, time: Duration, bonus: TimeBonus) -> Self {
+        Self {
+            moves: Some(moves),
+            time,
+            bonus,
+        }
+    }
+}
+
+// the time of each stage is added on top of whatever is left when the previous one ends,
+// when every stage has a move count the last one repeats
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TimeControl {
+    pub stages: Vec<TimeControlStage>,
+}
+
+impl TimeControl {
+    pub fn new(stages: Vec<TimeControlStage>) -> Self {
+        assert!(
+            !stages.is_empty(),
+            "a time control needs at least one stage"
+        );
+        Self { stages }
+    }
+    pub fn sudden_death(time: Duration) -> Self {
+        Self::new(vec![TimeControlStage::sudden_death(time, TimeBonus::None)])
+    }
+    pub fn fischer(time: Duration, increment: Duration) -> Self {
+        Self::new(vec![TimeControlStage::sudden_death(
+            time,
+            TimeBonus::Increment(increment),
+        )])
+    }
+    pub fn simple_delay(time: Duration, delay: Duration) -> Self {
+        Self::new(vec![TimeControlStage::sudden_death(
+            time,
+            TimeBonus::Delay(delay),
+        )])
+    }
+    pub fn bronstein(time: Duration, delay: Duration) -> Self {
+        Self::new(vec![TimeControlStage::sudden_death(
+            time,
+            TimeBonus::Bronstein(delay),
+        )])
+    }
+    pub fn stage(&self, index: usize) -> &TimeControlStage {
+        &self.stages[index.min(self.stages.len() - 1)]
+    }
+    // the stage after this one, repeating the last
+    pub fn next_stage_index(&self, index: usize) -> usize {
+        (index + 1).min(self.stages.len() - 1)
+    }
+}
+
+fn parse_seconds(s: &str, period: &str) -> Result<Duration, TimeControlParsingError> {
+    s.parse::<u64>()
+        .map(Duration::from_secs)
+        .map_err(|_| TimeControlParsingError::InvalidPeriod(period.to_string()))
+}
+
+// the pgn TimeControl tag, periods of [moves/]seconds[+increment] separated by colons
+impl FromStr for TimeControl {
+    type Err = TimeControlParsingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(TimeControlParsingError::Empty);
+        }
+        let stages = s
+            .split(':')
+            .map(|period| {
+                let (moves, rest) = match period.split_once('/') {
+                    Some((moves, rest)) => {
+                        let moves = moves.parse::<u32>().map_err(|_| {
+                            TimeControlParsingError::InvalidPeriod(period.to_string())
+                        })?;
+                        (Some(moves), rest)
+                    }
+                    None => (None, period),
+                };
+                let (time, bonus) = match rest.split_once('+') {
+                    Some((time, increment)) => (
+                        parse_seconds(time, period)?,
+                        TimeBonus::Increment(parse_seconds(increment, period)?),
+                    ),
+                    None => (parse_seconds(rest, period)?, TimeBonus::None),
+                };
+                Ok(TimeControlStage { moves, time, bonus })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::new(stages))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const fn minutes(minutes: u64) -> Duration {
+        Duration::from_secs(minutes * 60)
+    }
+
+    #[test]
+    fn test_parse_classical() {
+        let time_control: TimeControl = "40/5400+30:1800+30".parse().expect("valid time control");
+        assert_eq!(
+            TimeControl::new(vec![
+                TimeControlStage::moves(
+                    40,
+                    minutes(90),
+                    TimeBonus::Increment(Duration::from_secs(30))
+                ),
+                TimeControlStage::sudden_death(
+                    minutes(30),
+                    TimeBonus::Increment(Duration::from_secs(30))
+                ),
+            ]),
+            time_control
+        );
+    }
+
+    #[rstest]
+    #[case("300", TimeControl::sudden_death(minutes(5)))]
+    #[case("180+2", TimeControl::fischer(minutes(3), Duration::from_secs(2)))]
+    fn test_parse(#[case] s: &str, #[case] expected: TimeControl) {
+        assert_eq!(Ok(expected), s.parse());
+    }
+
+    #[rstest]
+    #[case("", TimeControlParsingError::Empty)]
+    #[case("40/", TimeControlParsingError::InvalidPeriod("40/".to_string()))]
+    #[case("5m+2", TimeControlParsingError::InvalidPeriod("5m+2".to_string()))]
+    fn test_parse_invalid(#[case] s: &str, #[case] expected: TimeControlParsingError) {
+        assert_eq!(Err(expected), s.parse::<TimeControl>());
+    }
+
+    #[test]
+    fn test_last_stage_repeats() {
+        let time_control: TimeControl = "40/7200".parse().expect("valid time control");
+        assert_eq!(0, time_control.next_stage_index(0));
+        assert_eq!(Some(40), time_control.stage(3).moves);
+    }
+}
@@ -0,0 +1,60 @@
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+// only the difference between two readings matters, so any fixed starting point will do
+pub trait TimeSource: Debug + Send + Sync {
+    fn now(&self) -> Duration;
+}
+
+#[derive(Debug)]
+pub struct SystemTimeSource {
+    start: Instant,
+}
+
+impl SystemTimeSource {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemTimeSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TimeSource for SystemTimeSource {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+// only moves when told to, for tests and replaying games with recorded times
+#[derive(Debug, Default)]
+pub struct ManualTimeSource {
+    nanos: AtomicU64,
+}
+
+impl ManualTimeSource {
+    pub const fn new() -> Self {
+        Self {
+            nanos: AtomicU64::new(0),
+        }
+    }
+    pub fn advance(&self, duration: Duration) {
+        self.nanos
+            .fetch_add(duration.as_nanos() as u64, Ordering::SeqCst);
+    }
+    pub fn set(&self, now: Duration) {
+        self.nanos.store(now.as_nanos() as u64, Ordering::SeqCst);
+    }
+}
+
+impl TimeSource for ManualTimeSource {
+    fn now(&self) -> Duration {
+        Duration::from_nanos(self.nanos.load(Ordering::SeqCst))
+    }
+}
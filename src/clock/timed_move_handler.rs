@@ -0,0 +1,159 @@
+use crate::chess_move::chess_move::ChessMove;
+use crate::chess_move::chess_move_handler::{default_chess_move_handler, ChessMoveHandlerOptions};
+use crate::chess_move::invalid_chess_move_error::InvalidChessMoveError;
+use crate::color::Color;
+use crate::state::game_state::GameState;
+use crate::state::game_status::{has_mating_material, GameStatus};
+
+// running out of time only loses if the opponent could still have mated
+pub fn flag_fall_status(game_state: &GameState, flagged: Color) -> GameStatus {
+    if has_mating_material(game_state, flagged.as_inverse()) {
+        GameStatus::TimeForfeit(flagged)
+    } else {
+        GameStatus::Draw
+    }
+}
+
+// ends the game when the side on move has run out of time, returns whether it did
+pub fn check_flag(game_state: &mut GameState) -> bool {
+    if game_state.game_status.is_game_over() {
+        return false;
+    }
+    let Some(flagged) = game_state.clock.as_ref().and_then(|clock| clock.flagged()) else {
+        return false;
+    };
+    game_state.game_status = flag_fall_status(game_state, flagged);
+    if let Some(clock) = game_state.clock.as_mut() {
+        clock.stop();
+    }
+    true
+}
+
+// the move handler for games on a clock, a move made after the flag fell is refused,
+// and one that ends the game stops the clock
+pub fn timed_chess_move_handler(
+    game_state: &mut GameState,
+    requested_chess_move: &ChessMove,
+    options: Option<ChessMoveHandlerOptions>,
+) -> Result<(), InvalidChessMoveError> {
+    if check_flag(game_state) {
+        return Err(InvalidChessMoveError::GameOver(game_state.game_status));
+    }
+    let moving_color = options
+        .and_then(|options| options.color_override)
+        .unwrap_or(game_state.active_color);
+    default_chess_move_handler(game_state, requested_chess_move, options)?;
+    let Some(clock) = game_state.clock.as_mut() else {
+        return Ok(());
+    };
+    let flagged = clock.press(moving_color);
+    if game_state.game_status.is_game_over() {
+        clock.stop();
+    } else if let Some(flagged) = flagged {
+        game_state.game_status = flag_fall_status(game_state, flagged);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::clock::Clock;
+    use crate::clock::time_control::TimeControl;
+    use crate::clock::time_source::ManualTimeSource;
+    use crate::notation::fen::deserialize;
+    use crate::notation::pgn::PgnResult;
+    use crate::notation::san::deserialize_san;
+    use rstest::rstest;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn on_clock(fen: &str, time: &Arc<ManualTimeSource>) -> GameState {
+        let mut game_state = deserialize(fen).expect("bad fen string!");
+        let mut clock = Clock::new(
+            TimeControl::fischer(Duration::from_secs(60), Duration::from_secs(1)),
+            time.clone(),
+        );
+        clock.start(game_state.active_color);
+        game_state.clock = Some(clock);
+        game_state
+    }
+
+    fn play(game_state: &mut GameState, san: &str) -> Result<(), InvalidChessMoveError> {
+        let chess_move = deserialize_san(game_state, san).expect("bad san!");
+        timed_chess_move_handler(game_state, &chess_move, None)
+    }
+
+    #[test]
+    fn test_timed_moves() {
+        let time = Arc::new(ManualTimeSource::new());
+        let mut game_state = on_clock(crate::notation::fen::FEN_STARTING_POS, &time);
+        time.advance(Duration::from_secs(10));
+        play(&mut game_state, "e4").expect("in time");
+        time.advance(Duration::from_secs(5));
+        play(&mut game_state, "e5").expect("in time");
+        let clock = game_state.clock.as_ref().expect("clock");
+        assert_eq!(Duration::from_secs(51), clock.remaining(Color::White));
+        assert_eq!(Duration::from_secs(56), clock.remaining(Color::Black));
+        assert_eq!(Some(Color::White), clock.running());
+    }
+
+    // black flags in each, with the white pieces that are left
+    #[rstest]
+    #[case(
+        "4k3/8/8/8/8/8/4P3/4K3 b - - 0 1",
+        GameStatus::TimeForfeit(Color::Black)
+    )]
+    #[case("4k3/8/8/8/8/8/8/4K3 b - - 0 1", GameStatus::Draw)]
+    #[case("4k3/8/8/8/8/8/8/3NK3 b - - 0 1", GameStatus::Draw)]
+    #[case(
+        "4k3/p7/8/8/8/8/8/3NK3 b - - 0 1",
+        GameStatus::TimeForfeit(Color::Black)
+    )]
+    #[case(
+        "4k3/8/8/8/8/8/8/2B1KB2 b - - 0 1",
+        GameStatus::TimeForfeit(Color::Black)
+    )]
+    #[case("4k3/8/8/8/8/8/8/2B1K1B1 b - - 0 1", GameStatus::Draw)]
+    fn test_flag_fall(#[case] fen: &str, #[case] expected: GameStatus) {
+        let time = Arc::new(ManualTimeSource::new());
+        let mut game_state = on_clock(fen, &time);
+        time.advance(Duration::from_secs(60));
+        assert!(matches!(
+            play(&mut game_state, "Kd7"),
+            Err(InvalidChessMoveError::GameOver(_))
+        ));
+        assert_eq!(expected, game_state.game_status);
+        assert_eq!(
+            None,
+            game_state.clock.as_ref().and_then(|clock| clock.running())
+        );
+    }
+
+    #[test]
+    fn test_flag_fall_result() {
+        let time = Arc::new(ManualTimeSource::new());
+        let mut game_state = on_clock("4k3/4p3/8/8/8/8/4P3/4K3 w - - 0 1", &time);
+        assert!(!check_flag(&mut game_state));
+        time.advance(Duration::from_secs(61));
+        assert!(check_flag(&mut game_state));
+        assert_eq!(
+            PgnResult::BlackWins,
+            PgnResult::from_game_status(game_state.game_status)
+        );
+    }
+
+    #[test]
+    fn test_mate_stops_the_clock() {
+        let time = Arc::new(ManualTimeSource::new());
+        let mut game_state = on_clock("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1", &time);
+        time.advance(Duration::from_secs(30));
+        play(&mut game_state, "Ra8#").expect("in time");
+        assert_eq!(GameStatus::CheckMate(Color::Black), game_state.game_status);
+        time.advance(Duration::from_secs(100));
+        assert!(!check_flag(&mut game_state));
+        let clock = game_state.clock.as_ref().expect("clock");
+        assert_eq!(None, clock.running());
+        assert_eq!(Duration::from_secs(60), clock.remaining(Color::Black));
+    }
+}
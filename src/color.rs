@@ -1,6 +1,8 @@
+use crate::board::board_rank::BoardRank;
 use crate::direction::facing_direction::FacingDirection;
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Color {
     White,
     Black,
@@ -19,6 +21,12 @@ impl Color {
             Self::Black => FacingDirection::South,
         }
     }
+    pub const fn back_rank(&self) -> BoardRank {
+        match self {
+            Self::White => BoardRank::One,
+            Self::Black => BoardRank::Eight,
+        }
+    }
     pub const fn as_score(&self, maximizing_color: Color) -> i32 {
         match (self, maximizing_color) {
             (Self::White, Self::White) | (Self::Black, Self::Black) => 1,
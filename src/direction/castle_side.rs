@@ -10,6 +10,7 @@ pub enum CastleSideConversionError {
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CastleSide {
     King,
     Queen,
@@ -31,6 +32,19 @@ impl CastleSide {
             Self::Queen => SimpleDirection::West,
         }
     }
+    // where king and rook end up, regardless of where they started in chess960
+    pub const fn king_destination_file(&self) -> BoardFile {
+        match self {
+            Self::King => BoardFile::G,
+            Self::Queen => BoardFile::C,
+        }
+    }
+    pub const fn rook_destination_file(&self) -> BoardFile {
+        match self {
+            Self::King => BoardFile::F,
+            Self::Queen => BoardFile::D,
+        }
+    }
     pub const fn from_pos(pos: BoardPosition) -> Self {
         let BoardPosition(file, _rank) = pos;
         match file {
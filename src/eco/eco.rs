@@ -0,0 +1,111 @@
+use crate::book::polyglot_key::polyglot_key;
+use crate::chess_move::chess_move_handler::default_chess_move_handler;
+use crate::eco::eco_table::ECO_TABLE;
+use crate::notation::san::deserialize_san;
+use crate::state::game_state::GameState;
+use crate::state::history::history_covers_whole_game;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Eco {
+    pub code: &'static str,
+    pub name: &'static str,
+    pub variation: Option<&'static str>,
+}
+
+impl Eco {
+    pub const fn new(
+        code: &'static str,
+        name: &'static str,
+        variation: Option<&'static str>,
+    ) -> Self {
+        Self {
+            code,
+            name,
+            variation,
+        }
+    }
+}
+
+static ECO_POSITIONS: OnceLock<HashMap<u64, Eco>> = OnceLock::new();
+
+pub(crate) fn replay_eco_line(moves: &str) -> Option<GameState> {
+    let mut game_state = GameState::new();
+    for san in moves.split_whitespace() {
+        let chess_move = deserialize_san(&game_state, san).ok()?;
+        default_chess_move_handler(&mut game_state, &chess_move, None).ok()?;
+    }
+    Some(game_state)
+}
+
+fn eco_positions() -> &'static HashMap<u64, Eco> {
+    ECO_POSITIONS.get_or_init(|| {
+        let mut positions = HashMap::new();
+        for (eco, moves) in ECO_TABLE.iter() {
+            if let Some(game_state) = replay_eco_line(moves) {
+                positions.entry(polyglot_key(&game_state)).or_insert(*eco);
+            }
+        }
+        positions
+    })
+}
+
+// ECO_TABLE only covers common openings, see the note in eco_table.rs
+pub fn classify_opening(game_state: &GameState) -> Option<Eco> {
+    let positions = eco_positions();
+    if let Some(eco) = positions.get(&polyglot_key(game_state)) {
+        return Some(*eco);
+    }
+    if !history_covers_whole_game(game_state) {
+        return None;
+    }
+    let plies = game_state.history.replay().ok()?;
+    plies
+        .iter()
+        .rev()
+        .find_map(|(game_state, _)| positions.get(&polyglot_key(game_state)).copied())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::fen::deserialize;
+    use rstest::rstest;
+
+    #[test]
+    fn test_eco_table_lines_are_legal() {
+        for (eco, moves) in ECO_TABLE.iter() {
+            let game_state = replay_eco_line(moves);
+            assert!(game_state.is_some(), "{} {moves}", eco.code);
+            // a line shadowed by an earlier one with the same position could never be found
+            assert_eq!(
+                Some(*eco),
+                classify_opening(&game_state.expect("legal line")),
+                "{moves}"
+            );
+        }
+    }
+
+    #[rstest]
+    #[case("e4 e5 Nf3 Nc6 Bb5 a6 Ba4 d6", "C70")]
+    #[case("Nf3 d5 d4", "D02")]
+    #[case("c4 e6 d4 d5 Nc3", "D31")]
+    #[case("d4 d5 c4 e6 Nc3 c6 Nf3 Nf6", "D43")]
+    #[case("e4 c5 Nf3 d6 d4 cxd4 Nxd4 Nf6 Nc3 a6 Be3 e5 Nb3", "B90")]
+    fn test_classify_opening(#[case] moves: &'static str, #[case] code: &'static str) {
+        let game_state = replay_eco_line(moves).expect("legal line");
+        assert_eq!(
+            Some(code),
+            classify_opening(&game_state).map(|eco| eco.code)
+        );
+    }
+
+    #[test]
+    fn test_classify_unknown_opening() {
+        let game_state = replay_eco_line("a3 a6 h3").expect("legal line");
+        assert_eq!(None, classify_opening(&game_state));
+        let game_state = deserialize("4k3/8/8/8/8/8/8/4K3 w - - 0 40").expect("bad fen string!");
+        assert_eq!(None, classify_opening(&game_state));
+    }
+}
@@ -0,0 +1,340 @@
+use crate::eco::eco::Eco;
+
+// each line is replayed from the starting position and keyed by the position it reaches,
+// so the move order listed here is only one of the ways to get there
+//
+// this is not the full A00-E99 set: only the main line of about a hundred common codes is
+// listed, so sidelines and rarer openings classify as the nearest listed ancestor or not at all
+pub static ECO_TABLE: &[(Eco, &str)] = &[
+    (Eco::new("A00", "Polish Opening", None), "b4"),
+    (Eco::new("A00", "Grob Opening", None), "g4"),
+    (Eco::new("A00", "Hungarian Opening", None), "g3"),
+    (Eco::new("A01", "Nimzo-Larsen Attack", None), "b3"),
+    (Eco::new("A02", "Bird Opening", None), "f4"),
+    (
+        Eco::new("A03", "Bird Opening", Some("Dutch Variation")),
+        "f4 d5",
+    ),
+    (Eco::new("A04", "Zukertort Opening", None), "Nf3"),
+    (Eco::new("A06", "Reti Opening", None), "Nf3 d5"),
+    (
+        Eco::new("A09", "Reti Opening", Some("Reti Gambit")),
+        "Nf3 d5 c4",
+    ),
+    (Eco::new("A10", "English Opening", None), "c4"),
+    (
+        Eco::new("A15", "English Opening", Some("Anglo-Indian Defense")),
+        "c4 Nf6",
+    ),
+    (
+        Eco::new("A20", "English Opening", Some("King's English Variation")),
+        "c4 e5",
+    ),
+    (
+        Eco::new("A30", "English Opening", Some("Symmetrical Variation")),
+        "c4 c5",
+    ),
+    (Eco::new("A40", "Queen's Pawn Game", None), "d4"),
+    (
+        Eco::new("A43", "Benoni Defense", Some("Old Benoni")),
+        "d4 c5",
+    ),
+    (Eco::new("A45", "Indian Defense", None), "d4 Nf6"),
+    (Eco::new("A45", "Trompowsky Attack", None), "d4 Nf6 Bg5"),
+    (
+        Eco::new("A46", "Indian Defense", Some("Knights Variation")),
+        "d4 Nf6 Nf3",
+    ),
+    (
+        Eco::new("A50", "Indian Defense", Some("Normal Variation")),
+        "d4 Nf6 c4",
+    ),
+    (Eco::new("A51", "Budapest Defense", None), "d4 Nf6 c4 e5"),
+    (Eco::new("A56", "Benoni Defense", None), "d4 Nf6 c4 c5"),
+    (Eco::new("A57", "Benko Gambit", None), "d4 Nf6 c4 c5 d5 b5"),
+    (
+        Eco::new("A60", "Benoni Defense", Some("Modern Variation")),
+        "d4 Nf6 c4 c5 d5 e6",
+    ),
+    (Eco::new("A80", "Dutch Defense", None), "d4 f5"),
+    (Eco::new("B00", "King's Pawn Game", None), "e4"),
+    (Eco::new("B00", "Nimzowitsch Defense", None), "e4 Nc6"),
+    (Eco::new("B01", "Scandinavian Defense", None), "e4 d5"),
+    (
+        Eco::new(
+            "B01",
+            "Scandinavian Defense",
+            Some("Mieses-Kotroc Variation"),
+        ),
+        "e4 d5 exd5 Qxd5",
+    ),
+    (Eco::new("B02", "Alekhine Defense", None), "e4 Nf6"),
+    (Eco::new("B06", "Modern Defense", None), "e4 g6"),
+    (Eco::new("B07", "Pirc Defense", None), "e4 d6 d4 Nf6"),
+    (Eco::new("B10", "Caro-Kann Defense", None), "e4 c6"),
+    (
+        Eco::new("B12", "Caro-Kann Defense", Some("Advance Variation")),
+        "e4 c6 d4 d5 e5",
+    ),
+    (
+        Eco::new("B13", "Caro-Kann Defense", Some("Exchange Variation")),
+        "e4 c6 d4 d5 exd5 cxd5",
+    ),
+    (
+        Eco::new("B15", "Caro-Kann Defense", None),
+        "e4 c6 d4 d5 Nc3",
+    ),
+    (
+        Eco::new("B18", "Caro-Kann Defense", Some("Classical Variation")),
+        "e4 c6 d4 d5 Nc3 dxe4 Nxe4 Bf5",
+    ),
+    (Eco::new("B20", "Sicilian Defense", None), "e4 c5"),
+    (
+        Eco::new("B21", "Sicilian Defense", Some("Smith-Morra Gambit")),
+        "e4 c5 d4 cxd4 c3",
+    ),
+    (
+        Eco::new("B22", "Sicilian Defense", Some("Alapin Variation")),
+        "e4 c5 c3",
+    ),
+    (
+        Eco::new("B23", "Sicilian Defense", Some("Closed")),
+        "e4 c5 Nc3",
+    ),
+    (Eco::new("B27", "Sicilian Defense", None), "e4 c5 Nf3"),
+    (
+        Eco::new("B30", "Sicilian Defense", Some("Old Sicilian")),
+        "e4 c5 Nf3 Nc6",
+    ),
+    (
+        Eco::new("B33", "Sicilian Defense", Some("Open")),
+        "e4 c5 Nf3 Nc6 d4 cxd4 Nxd4 Nf6",
+    ),
+    (
+        Eco::new("B40", "Sicilian Defense", Some("French Variation")),
+        "e4 c5 Nf3 e6",
+    ),
+    (Eco::new("B50", "Sicilian Defense", None), "e4 c5 Nf3 d6"),
+    (
+        Eco::new("B54", "Sicilian Defense", Some("Open")),
+        "e4 c5 Nf3 d6 d4 cxd4 Nxd4",
+    ),
+    (
+        Eco::new("B70", "Sicilian Defense", Some("Dragon Variation")),
+        "e4 c5 Nf3 d6 d4 cxd4 Nxd4 Nf6 Nc3 g6",
+    ),
+    (
+        Eco::new("B80", "Sicilian Defense", Some("Scheveningen Variation")),
+        "e4 c5 Nf3 d6 d4 cxd4 Nxd4 Nf6 Nc3 e6",
+    ),
+    (
+        Eco::new("B90", "Sicilian Defense", Some("Najdorf Variation")),
+        "e4 c5 Nf3 d6 d4 cxd4 Nxd4 Nf6 Nc3 a6",
+    ),
+    (Eco::new("C00", "French Defense", None), "e4 e6"),
+    (
+        Eco::new("C01", "French Defense", Some("Exchange Variation")),
+        "e4 e6 d4 d5 exd5",
+    ),
+    (
+        Eco::new("C02", "French Defense", Some("Advance Variation")),
+        "e4 e6 d4 d5 e5",
+    ),
+    (
+        Eco::new("C03", "French Defense", Some("Tarrasch Variation")),
+        "e4 e6 d4 d5 Nd2",
+    ),
+    (
+        Eco::new("C10", "French Defense", Some("Paulsen Variation")),
+        "e4 e6 d4 d5 Nc3",
+    ),
+    (
+        Eco::new("C11", "French Defense", Some("Classical Variation")),
+        "e4 e6 d4 d5 Nc3 Nf6",
+    ),
+    (
+        Eco::new("C15", "French Defense", Some("Winawer Variation")),
+        "e4 e6 d4 d5 Nc3 Bb4",
+    ),
+    (Eco::new("C20", "King's Pawn Game", None), "e4 e5"),
+    (Eco::new("C23", "Bishop's Opening", None), "e4 e5 Bc4"),
+    (Eco::new("C25", "Vienna Game", None), "e4 e5 Nc3"),
+    (Eco::new("C30", "King's Gambit", None), "e4 e5 f4"),
+    (
+        Eco::new("C33", "King's Gambit Accepted", None),
+        "e4 e5 f4 exf4",
+    ),
+    (Eco::new("C40", "King's Knight Opening", None), "e4 e5 Nf3"),
+    (Eco::new("C41", "Philidor Defense", None), "e4 e5 Nf3 d6"),
+    (Eco::new("C42", "Petrov's Defense", None), "e4 e5 Nf3 Nf6"),
+    (
+        Eco::new("C44", "King's Knight Opening", Some("Normal Variation")),
+        "e4 e5 Nf3 Nc6",
+    ),
+    (Eco::new("C44", "Scotch Game", None), "e4 e5 Nf3 Nc6 d4"),
+    (
+        Eco::new("C45", "Scotch Game", None),
+        "e4 e5 Nf3 Nc6 d4 exd4 Nxd4",
+    ),
+    (
+        Eco::new("C46", "Three Knights Opening", None),
+        "e4 e5 Nf3 Nc6 Nc3",
+    ),
+    (
+        Eco::new("C47", "Four Knights Game", None),
+        "e4 e5 Nf3 Nc6 Nc3 Nf6",
+    ),
+    (Eco::new("C50", "Italian Game", None), "e4 e5 Nf3 Nc6 Bc4"),
+    (
+        Eco::new("C50", "Italian Game", Some("Giuoco Piano")),
+        "e4 e5 Nf3 Nc6 Bc4 Bc5",
+    ),
+    (
+        Eco::new("C51", "Italian Game", Some("Evans Gambit")),
+        "e4 e5 Nf3 Nc6 Bc4 Bc5 b4",
+    ),
+    (
+        Eco::new("C55", "Italian Game", Some("Two Knights Defense")),
+        "e4 e5 Nf3 Nc6 Bc4 Nf6",
+    ),
+    (Eco::new("C60", "Ruy Lopez", None), "e4 e5 Nf3 Nc6 Bb5"),
+    (
+        Eco::new("C65", "Ruy Lopez", Some("Berlin Defense")),
+        "e4 e5 Nf3 Nc6 Bb5 Nf6",
+    ),
+    (
+        Eco::new("C68", "Ruy Lopez", Some("Exchange Variation")),
+        "e4 e5 Nf3 Nc6 Bb5 a6 Bxc6",
+    ),
+    (
+        Eco::new("C70", "Ruy Lopez", Some("Morphy Defense")),
+        "e4 e5 Nf3 Nc6 Bb5 a6 Ba4",
+    ),
+    (
+        Eco::new("C78", "Ruy Lopez", Some("Morphy Defense")),
+        "e4 e5 Nf3 Nc6 Bb5 a6 Ba4 Nf6 O-O",
+    ),
+    (
+        Eco::new("C84", "Ruy Lopez", Some("Closed")),
+        "e4 e5 Nf3 Nc6 Bb5 a6 Ba4 Nf6 O-O Be7",
+    ),
+    (
+        Eco::new("C88", "Ruy Lopez", Some("Closed")),
+        "e4 e5 Nf3 Nc6 Bb5 a6 Ba4 Nf6 O-O Be7 Re1 b5 Bb3",
+    ),
+    (Eco::new("D00", "Queen's Pawn Game", None), "d4 d5"),
+    (
+        Eco::new(
+            "D00",
+            "Queen's Pawn Game",
+            Some("Accelerated London System"),
+        ),
+        "d4 d5 Bf4",
+    ),
+    (
+        Eco::new("D02", "Queen's Pawn Game", Some("Zukertort Variation")),
+        "d4 d5 Nf3",
+    ),
+    (
+        Eco::new("D02", "Queen's Pawn Game", Some("London System")),
+        "d4 d5 Nf3 Nf6 Bf4",
+    ),
+    (Eco::new("D06", "Queen's Gambit", None), "d4 d5 c4"),
+    (
+        Eco::new("D07", "Queen's Gambit Declined", Some("Chigorin Defense")),
+        "d4 d5 c4 Nc6",
+    ),
+    (
+        Eco::new(
+            "D08",
+            "Queen's Gambit Declined",
+            Some("Albin Countergambit"),
+        ),
+        "d4 d5 c4 e5",
+    ),
+    (Eco::new("D10", "Slav Defense", None), "d4 d5 c4 c6"),
+    (Eco::new("D11", "Slav Defense", None), "d4 d5 c4 c6 Nf3"),
+    (
+        Eco::new("D15", "Slav Defense", Some("Three Knights Variation")),
+        "d4 d5 c4 c6 Nf3 Nf6 Nc3",
+    ),
+    (
+        Eco::new("D20", "Queen's Gambit Accepted", None),
+        "d4 d5 c4 dxc4",
+    ),
+    (
+        Eco::new("D30", "Queen's Gambit Declined", None),
+        "d4 d5 c4 e6",
+    ),
+    (
+        Eco::new("D31", "Queen's Gambit Declined", None),
+        "d4 d5 c4 e6 Nc3",
+    ),
+    (
+        Eco::new("D35", "Queen's Gambit Declined", Some("Exchange Variation")),
+        "d4 d5 c4 e6 Nc3 Nf6 cxd5",
+    ),
+    (
+        Eco::new("D37", "Queen's Gambit Declined", None),
+        "d4 d5 c4 e6 Nc3 Nf6 Nf3",
+    ),
+    (
+        Eco::new("D43", "Semi-Slav Defense", None),
+        "d4 d5 c4 c6 Nf3 Nf6 Nc3 e6",
+    ),
+    (
+        Eco::new("D80", "Grunfeld Defense", None),
+        "d4 Nf6 c4 g6 Nc3 d5",
+    ),
+    (
+        Eco::new("D85", "Grunfeld Defense", Some("Exchange Variation")),
+        "d4 Nf6 c4 g6 Nc3 d5 cxd5 Nxd5",
+    ),
+    (Eco::new("E00", "Indian Defense", None), "d4 Nf6 c4 e6"),
+    (Eco::new("E01", "Catalan Opening", None), "d4 Nf6 c4 e6 g3"),
+    (Eco::new("E10", "Indian Defense", None), "d4 Nf6 c4 e6 Nf3"),
+    (
+        Eco::new("E11", "Bogo-Indian Defense", None),
+        "d4 Nf6 c4 e6 Nf3 Bb4+",
+    ),
+    (
+        Eco::new("E12", "Queen's Indian Defense", None),
+        "d4 Nf6 c4 e6 Nf3 b6",
+    ),
+    (
+        Eco::new("E20", "Nimzo-Indian Defense", None),
+        "d4 Nf6 c4 e6 Nc3 Bb4",
+    ),
+    (
+        Eco::new("E32", "Nimzo-Indian Defense", Some("Classical Variation")),
+        "d4 Nf6 c4 e6 Nc3 Bb4 Qc2",
+    ),
+    (
+        Eco::new("E40", "Nimzo-Indian Defense", Some("Rubinstein Variation")),
+        "d4 Nf6 c4 e6 Nc3 Bb4 e3",
+    ),
+    (
+        Eco::new("E60", "King's Indian Defense", None),
+        "d4 Nf6 c4 g6",
+    ),
+    (
+        Eco::new("E61", "King's Indian Defense", None),
+        "d4 Nf6 c4 g6 Nc3 Bg7",
+    ),
+    (
+        Eco::new("E70", "King's Indian Defense", Some("Normal Variation")),
+        "d4 Nf6 c4 g6 Nc3 Bg7 e4 d6",
+    ),
+    (
+        Eco::new("E80", "King's Indian Defense", Some("Samisch Variation")),
+        "d4 Nf6 c4 g6 Nc3 Bg7 e4 d6 f3",
+    ),
+    (
+        Eco::new("E90", "King's Indian Defense", None),
+        "d4 Nf6 c4 g6 Nc3 Bg7 e4 d6 Nf3",
+    ),
+    (
+        Eco::new("E92", "King's Indian Defense", Some("Orthodox Variation")),
+        "d4 Nf6 c4 g6 Nc3 Bg7 e4 d6 Nf3 O-O Be2 e5",
+    ),
+];
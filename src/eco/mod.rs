@@ -0,0 +1,2 @@
+pub mod eco;
+pub mod eco_table;
@@ -0,0 +1,188 @@
+use crate::chess_move::invalid_chess_move_error::InvalidChessMoveError;
+use crate::notation::coordinate::CoordinateParsingError;
+use crate::notation::fen::deserialize::FenParsingError;
+use std::ffi::{c_char, c_int, CStr};
+
+// 0 is success, 1xx are fen errors and 2xx are move errors so callers can bucket by range
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ChessErrorCode {
+    Ok = 0,
+    NullPointer = 1,
+    InvalidUtf8 = 2,
+    BufferTooSmall = 3,
+    InvalidMoveNotation = 4,
+    InvalidFenString = 100,
+    InvalidActiveColor = 101,
+    InvalidCastleRights = 102,
+    InvalidEnPassant = 103,
+    InvalidBoardString = 104,
+    InvalidMoveCounter = 105,
+    InvalidPosition = 106,
+    GameOver = 200,
+    NoPieceAtOrigin = 201,
+    NotCurrentTurn = 202,
+    UnexpectedCapture = 203,
+    CastleWhileInCheck = 204,
+    MoveIntoCheck = 205,
+    StillInCheck = 206,
+    IllegalMove = 207,
+}
+
+impl ChessErrorCode {
+    // c callers can hand over any int, not just the codes this library returned
+    pub const fn from_code(code: c_int) -> Option<Self> {
+        Some(match code {
+            0 => Self::Ok,
+            1 => Self::NullPointer,
+            2 => Self::InvalidUtf8,
+            3 => Self::BufferTooSmall,
+            4 => Self::InvalidMoveNotation,
+            100 => Self::InvalidFenString,
+            101 => Self::InvalidActiveColor,
+            102 => Self::InvalidCastleRights,
+            103 => Self::InvalidEnPassant,
+            104 => Self::InvalidBoardString,
+            105 => Self::InvalidMoveCounter,
+            106 => Self::InvalidPosition,
+            200 => Self::GameOver,
+            201 => Self::NoPieceAtOrigin,
+            202 => Self::NotCurrentTurn,
+            203 => Self::UnexpectedCapture,
+            204 => Self::CastleWhileInCheck,
+            205 => Self::MoveIntoCheck,
+            206 => Self::StillInCheck,
+            207 => Self::IllegalMove,
+            _ => return None,
+        })
+    }
+    pub const fn message(&self) -> &'static CStr {
+        match self {
+            Self::Ok => c"Ok",
+            Self::NullPointer => c"Null pointer",
+            Self::InvalidUtf8 => c"String is not valid utf-8",
+            Self::BufferTooSmall => c"Buffer too small",
+            Self::InvalidMoveNotation => c"Invalid uci move notation",
+            Self::InvalidFenString => c"Invalid fen string",
+            Self::InvalidActiveColor => c"Invalid active color",
+            Self::InvalidCastleRights => c"Invalid castle rights",
+            Self::InvalidEnPassant => c"Invalid en passant",
+            Self::InvalidBoardString => c"Invalid board string",
+            Self::InvalidMoveCounter => c"Invalid move counter",
+            Self::InvalidPosition => c"Invalid position",
+            Self::GameOver => c"Game is over",
+            Self::NoPieceAtOrigin => c"No piece at origin",
+            Self::NotCurrentTurn => c"Not the current turn",
+            Self::UnexpectedCapture => c"Unexpected capture",
+            Self::CastleWhileInCheck => c"Can't castle when in check",
+            Self::MoveIntoCheck => c"Can't move into check",
+            Self::StillInCheck => c"Still in check",
+            Self::IllegalMove => c"Illegal move",
+        }
+    }
+}
+
+impl From<&FenParsingError> for ChessErrorCode {
+    fn from(err: &FenParsingError) -> Self {
+        match err {
+            FenParsingError::InvalidFenString(_) => Self::InvalidFenString,
+            FenParsingError::InvalidActiveColorString(_)
+            | FenParsingError::InvalidActiveColorChar(_) => Self::InvalidActiveColor,
+            FenParsingError::InvalidCastleRights(_) => Self::InvalidCastleRights,
+            FenParsingError::InvalidEnPassant(_) => Self::InvalidEnPassant,
+            FenParsingError::InvalidBoardString(_)
+            | FenParsingError::InvalidBoardStringBoardFileParseError(_)
+            | FenParsingError::InvalidBoardStringBoardRankParseError(_) => Self::InvalidBoardString,
+            FenParsingError::InvalidMoveCounter(_) => Self::InvalidMoveCounter,
+            FenParsingError::InvalidPosition(_) => Self::InvalidPosition,
+        }
+    }
+}
+
+impl From<&InvalidChessMoveError> for ChessErrorCode {
+    fn from(err: &InvalidChessMoveError) -> Self {
+        match err {
+            InvalidChessMoveError::GameOver(_) => Self::GameOver,
+            InvalidChessMoveError::NoPieceAtOrigin(_) => Self::NoPieceAtOrigin,
+            InvalidChessMoveError::NotCurrentTurn(_) => Self::NotCurrentTurn,
+            InvalidChessMoveError::UnexpectedCapture(_, _) => Self::UnexpectedCapture,
+            InvalidChessMoveError::CastleWhileInCheck => Self::CastleWhileInCheck,
+            InvalidChessMoveError::MoveIntoCheck => Self::MoveIntoCheck,
+            InvalidChessMoveError::StillInCheck => Self::StillInCheck,
+            InvalidChessMoveError::InvalidMove(_, _) => Self::IllegalMove,
+        }
+    }
+}
+
+impl From<&CoordinateParsingError> for ChessErrorCode {
+    fn from(err: &CoordinateParsingError) -> Self {
+        match err {
+            CoordinateParsingError::InvalidCoordinate(_) => Self::InvalidMoveNotation,
+            CoordinateParsingError::IllegalMove(_) => Self::IllegalMove,
+        }
+    }
+}
+
+// static nul terminated string, the caller must not free it
+#[no_mangle]
+pub extern "C" fn chess_error_message(code: c_int) -> *const c_char {
+    match ChessErrorCode::from_code(code) {
+        Some(code) => code.message().as_ptr(),
+        None => c"Unknown error".as_ptr(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::position::*;
+    use crate::notation::fen::deserialize;
+    use crate::state::game_status::GameStatus;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("8/8/8/8/8/8/8/8 w - -", ChessErrorCode::InvalidFenString)]
+    #[case("4k3/8/8/8/8/8/8/4K3 x - - 0 1", ChessErrorCode::InvalidActiveColor)]
+    #[case("4k3/8/8/8/8/8/8/4K3 w X - 0 1", ChessErrorCode::InvalidCastleRights)]
+    #[case("4k3/8/8/8/8/8/8/4K3 w - z9 0 1", ChessErrorCode::InvalidEnPassant)]
+    #[case("4k3/8/8/8/8/8/8/4K3 w - - x 1", ChessErrorCode::InvalidMoveCounter)]
+    fn test_fen_error_codes(#[case] fen: &str, #[case] expected: ChessErrorCode) {
+        let err = deserialize(fen).expect_err("expected a bad fen");
+        assert_eq!(expected, ChessErrorCode::from(&err));
+    }
+
+    #[rstest]
+    #[case(
+        InvalidChessMoveError::GameOver(GameStatus::Stalemate),
+        ChessErrorCode::GameOver
+    )]
+    #[case(
+        InvalidChessMoveError::InvalidMove(E2, E5),
+        ChessErrorCode::IllegalMove
+    )]
+    #[case(InvalidChessMoveError::MoveIntoCheck, ChessErrorCode::MoveIntoCheck)]
+    fn test_move_error_codes(#[case] err: InvalidChessMoveError, #[case] expected: ChessErrorCode) {
+        assert_eq!(expected, ChessErrorCode::from(&err));
+    }
+
+    #[rstest]
+    #[case(ChessErrorCode::GameOver as c_int, "Game is over")]
+    #[case(ChessErrorCode::InvalidPosition as c_int, "Invalid position")]
+    #[case(ChessErrorCode::Ok as c_int, "Ok")]
+    #[case(5, "Unknown error")]
+    #[case(-1, "Unknown error")]
+    #[case(c_int::MAX, "Unknown error")]
+    fn test_error_message(#[case] code: c_int, #[case] expected: &str) {
+        let message = unsafe { CStr::from_ptr(chess_error_message(code)) };
+        assert_eq!(expected, message.to_str().unwrap());
+    }
+
+    #[rstest]
+    #[case(ChessErrorCode::NullPointer)]
+    #[case(ChessErrorCode::InvalidMoveNotation)]
+    #[case(ChessErrorCode::InvalidFenString)]
+    #[case(ChessErrorCode::IllegalMove)]
+    fn test_from_code(#[case] code: ChessErrorCode) {
+        assert_eq!(Some(code), ChessErrorCode::from_code(code as c_int));
+    }
+}
@@ -0,0 +1,459 @@
+// the safety contract of every function is documented in include/rust_chess.h
+#![allow(clippy::missing_safety_doc)]
+
+use crate::chess_move::chess_move::ChessMove;
+use crate::chess_move::chess_move_handler::default_chess_move_handler;
+use crate::chess_move::invalid_chess_move_error::InvalidChessMoveError;
+use crate::color::Color;
+use crate::ffi::ffi_error::ChessErrorCode;
+use crate::notation::coordinate::{deserialize_coordinate, serialize_coordinate};
+use crate::notation::fen::{deserialize_strict, serialize};
+use crate::notation::san::legal_moves_with_status;
+use crate::search::search_handle::SearchHandle;
+use crate::search::search_limits::SearchLimits;
+use crate::search::search_options::SearchOptions;
+use crate::state::game_state::GameState;
+use crate::state::game_status::GameStatus;
+use std::ffi::{c_char, CStr};
+use std::time::Duration;
+
+// longest uci move (e7e8q) plus the nul terminator
+pub const CHESS_UCI_MOVE_LEN: usize = 6;
+// no legal position has more moves than this
+pub const CHESS_MAX_MOVES: usize = 256;
+pub const CHESS_DEFAULT_SEARCH_DEPTH: u8 = 4;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ChessColor {
+    White = 0,
+    Black = 1,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ChessGameStatus {
+    New = 0,
+    InProgress = 1,
+    Check = 2,
+    Checkmate = 3,
+    Stalemate = 4,
+    Draw = 5,
+    TimeForfeit = 6,
+}
+
+impl From<GameStatus> for ChessGameStatus {
+    fn from(game_status: GameStatus) -> Self {
+        match game_status {
+            GameStatus::New => Self::New,
+            GameStatus::InProgress => Self::InProgress,
+            GameStatus::Check(_) => Self::Check,
+            GameStatus::CheckMate(_) => Self::Checkmate,
+            GameStatus::Stalemate => Self::Stalemate,
+            GameStatus::Draw => Self::Draw,
+            GameStatus::TimeForfeit(_) => Self::TimeForfeit,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ChessUciMove {
+    pub uci: [c_char; CHESS_UCI_MOVE_LEN],
+}
+
+impl From<&ChessMove> for ChessUciMove {
+    fn from(chess_move: &ChessMove) -> Self {
+        let mut uci = [0; CHESS_UCI_MOVE_LEN];
+        for (c, byte) in uci.iter_mut().zip(serialize_coordinate(chess_move).bytes()) {
+            *c = byte as c_char;
+        }
+        Self { uci }
+    }
+}
+
+// zero means no limit, all zero searches to CHESS_DEFAULT_SEARCH_DEPTH
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ChessSearchLimits {
+    pub depth: u8,
+    pub nodes: u64,
+    pub move_time_ms: u64,
+}
+
+impl From<ChessSearchLimits> for SearchLimits {
+    fn from(limits: ChessSearchLimits) -> Self {
+        let depth = match limits {
+            ChessSearchLimits {
+                depth: 0,
+                nodes: 0,
+                move_time_ms: 0,
+            } => Some(CHESS_DEFAULT_SEARCH_DEPTH),
+            ChessSearchLimits { depth: 0, .. } => None,
+            ChessSearchLimits { depth, .. } => Some(depth),
+        };
+        Self {
+            depth,
+            nodes: (limits.nodes > 0).then_some(limits.nodes),
+            move_time: (limits.move_time_ms > 0)
+                .then(|| Duration::from_millis(limits.move_time_ms)),
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ChessSearchResult {
+    pub best_move: ChessUciMove,
+    pub score: i32,
+    pub depth: u8,
+    pub nodes: u64,
+}
+
+unsafe fn game_ref<'a>(game: *const GameState) -> Result<&'a GameState, ChessErrorCode> {
+    game.as_ref().ok_or(ChessErrorCode::NullPointer)
+}
+
+unsafe fn game_mut<'a>(game: *mut GameState) -> Result<&'a mut GameState, ChessErrorCode> {
+    game.as_mut().ok_or(ChessErrorCode::NullPointer)
+}
+
+unsafe fn read_str<'a>(s: *const c_char) -> Result<&'a str, ChessErrorCode> {
+    if s.is_null() {
+        return Err(ChessErrorCode::NullPointer);
+    }
+    CStr::from_ptr(s)
+        .to_str()
+        .map_err(|_| ChessErrorCode::InvalidUtf8)
+}
+
+fn parse_fen(fen: &str) -> Result<GameState, ChessErrorCode> {
+    deserialize_strict(fen).map_err(|err| ChessErrorCode::from(&err))
+}
+
+fn to_code(result: Result<(), ChessErrorCode>) -> ChessErrorCode {
+    result.err().unwrap_or(ChessErrorCode::Ok)
+}
+
+#[no_mangle]
+pub extern "C" fn chess_game_new() -> *mut GameState {
+    Box::into_raw(Box::new(GameState::new()))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn chess_game_free(game: *mut GameState) {
+    if !game.is_null() {
+        drop(Box::from_raw(game));
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn chess_game_from_fen(
+    fen: *const c_char,
+    out_game: *mut *mut GameState,
+) -> ChessErrorCode {
+    to_code((|| {
+        if out_game.is_null() {
+            return Err(ChessErrorCode::NullPointer);
+        }
+        let game_state = parse_fen(read_str(fen)?)?;
+        *out_game = Box::into_raw(Box::new(game_state));
+        Ok(())
+    })())
+}
+
+// the game is left untouched when the fen is invalid
+#[no_mangle]
+pub unsafe extern "C" fn chess_game_load_fen(
+    game: *mut GameState,
+    fen: *const c_char,
+) -> ChessErrorCode {
+    to_code((|| {
+        let game = game_mut(game)?;
+        *game = parse_fen(read_str(fen)?)?;
+        Ok(())
+    })())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn chess_game_fen(
+    game: *const GameState,
+    buffer: *mut c_char,
+    buffer_len: usize,
+) -> ChessErrorCode {
+    to_code((|| {
+        let game = game_ref(game)?;
+        if buffer.is_null() {
+            return Err(ChessErrorCode::NullPointer);
+        }
+        let fen = serialize(game);
+        let bytes = fen.get_str().as_bytes();
+        if bytes.len() >= buffer_len {
+            return Err(ChessErrorCode::BufferTooSmall);
+        }
+        std::ptr::copy_nonoverlapping(bytes.as_ptr().cast::<c_char>(), buffer, bytes.len());
+        *buffer.add(bytes.len()) = 0;
+        Ok(())
+    })())
+}
+
+// out_count is always set, so a caller with too small a buffer knows how much to allocate
+#[no_mangle]
+pub unsafe extern "C" fn chess_game_legal_moves(
+    game: *const GameState,
+    out_moves: *mut ChessUciMove,
+    capacity: usize,
+    out_count: *mut usize,
+) -> ChessErrorCode {
+    to_code((|| {
+        let game = game_ref(game)?;
+        if out_count.is_null() {
+            return Err(ChessErrorCode::NullPointer);
+        }
+        let moves = legal_moves_with_status(game);
+        *out_count = moves.len();
+        if moves.len() > capacity {
+            return Err(ChessErrorCode::BufferTooSmall);
+        }
+        if out_moves.is_null() && !moves.is_empty() {
+            return Err(ChessErrorCode::NullPointer);
+        }
+        for (ix, (chess_move, _)) in moves.iter().enumerate() {
+            *out_moves.add(ix) = ChessUciMove::from(chess_move);
+        }
+        Ok(())
+    })())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn chess_game_make_move(
+    game: *mut GameState,
+    uci: *const c_char,
+) -> ChessErrorCode {
+    to_code((|| {
+        let game = game_mut(game)?;
+        let uci = read_str(uci)?;
+        if game.game_status.is_game_over() {
+            return Err(ChessErrorCode::from(&InvalidChessMoveError::GameOver(
+                game.game_status,
+            )));
+        }
+        let chess_move =
+            deserialize_coordinate(game, uci).map_err(|err| ChessErrorCode::from(&err))?;
+        default_chess_move_handler(game, &chess_move, None)
+            .map_err(|err| ChessErrorCode::from(&err))?;
+        Ok(())
+    })())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn chess_game_status(
+    game: *const GameState,
+    out_status: *mut ChessGameStatus,
+) -> ChessErrorCode {
+    to_code((|| {
+        let game = game_ref(game)?;
+        let out_status = out_status.as_mut().ok_or(ChessErrorCode::NullPointer)?;
+        *out_status = ChessGameStatus::from(game.game_status);
+        Ok(())
+    })())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn chess_game_active_color(
+    game: *const GameState,
+    out_color: *mut ChessColor,
+) -> ChessErrorCode {
+    to_code((|| {
+        let game = game_ref(game)?;
+        let out_color = out_color.as_mut().ok_or(ChessErrorCode::NullPointer)?;
+        *out_color = match game.active_color {
+            Color::White => ChessColor::White,
+            Color::Black => ChessColor::Black,
+        };
+        Ok(())
+    })())
+}
+
+// blocks until the search finishes, the game itself is not changed
+#[no_mangle]
+pub unsafe extern "C" fn chess_game_search(
+    game: *const GameState,
+    limits: ChessSearchLimits,
+    out_result: *mut ChessSearchResult,
+) -> ChessErrorCode {
+    to_code((|| {
+        let game = game_ref(game)?;
+        let out_result = out_result.as_mut().ok_or(ChessErrorCode::NullPointer)?;
+        if game.game_status.is_game_over() {
+            return Err(ChessErrorCode::GameOver);
+        }
+        let result = SearchHandle::start(game, limits.into(), SearchOptions::new()).join();
+        let best_move = result.best_move.ok_or(ChessErrorCode::GameOver)?;
+        *out_result = ChessSearchResult {
+            best_move: ChessUciMove::from(&best_move),
+            score: result.score,
+            depth: result.depth,
+            nodes: result.nodes,
+        };
+        Ok(())
+    })())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::fen::FEN_STARTING_POS;
+    use std::ffi::CString;
+    use std::ptr;
+
+    const HEADER: &str = include_str!("../../include/rust_chess.h");
+
+    fn uci_str(chess_uci_move: &ChessUciMove) -> String {
+        unsafe { CStr::from_ptr(chess_uci_move.uci.as_ptr()) }
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    fn fen_of(game: *const GameState) -> String {
+        let mut buffer = [0 as c_char; 128];
+        let code = unsafe { chess_game_fen(game, buffer.as_mut_ptr(), buffer.len()) };
+        assert_eq!(ChessErrorCode::Ok, code);
+        unsafe { CStr::from_ptr(buffer.as_ptr()) }
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn test_game_lifecycle() {
+        let game = chess_game_new();
+        assert_eq!(FEN_STARTING_POS, fen_of(game));
+
+        let mut moves = [ChessUciMove { uci: [0; 6] }; CHESS_MAX_MOVES];
+        let mut count = 0;
+        let code =
+            unsafe { chess_game_legal_moves(game, moves.as_mut_ptr(), moves.len(), &mut count) };
+        assert_eq!(ChessErrorCode::Ok, code);
+        assert_eq!(20, count);
+        assert!(moves[..count].iter().any(|m| uci_str(m) == "e2e4"));
+
+        let e2e4 = CString::new("e2e4").unwrap();
+        assert_eq!(ChessErrorCode::Ok, unsafe {
+            chess_game_make_move(game, e2e4.as_ptr())
+        });
+        assert_eq!(ChessErrorCode::IllegalMove, unsafe {
+            chess_game_make_move(game, e2e4.as_ptr())
+        });
+        let bad = CString::new("e2").unwrap();
+        assert_eq!(ChessErrorCode::InvalidMoveNotation, unsafe {
+            chess_game_make_move(game, bad.as_ptr())
+        });
+
+        let mut color = ChessColor::White;
+        let mut status = ChessGameStatus::New;
+        unsafe {
+            assert_eq!(
+                ChessErrorCode::Ok,
+                chess_game_active_color(game, &mut color)
+            );
+            assert_eq!(ChessErrorCode::Ok, chess_game_status(game, &mut status));
+            chess_game_free(game);
+        }
+        assert_eq!(ChessColor::Black, color);
+        assert_eq!(ChessGameStatus::InProgress, status);
+    }
+
+    #[test]
+    fn test_fen_round_trip_and_errors() {
+        let fen = CString::new("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1").unwrap();
+        let mut game = ptr::null_mut();
+        assert_eq!(ChessErrorCode::Ok, unsafe {
+            chess_game_from_fen(fen.as_ptr(), &mut game)
+        });
+        assert_eq!(fen.to_str().unwrap(), fen_of(game));
+
+        let bad_fen = CString::new("4k3/8/8/8/8/8/8/4K3 w - - x 1").unwrap();
+        assert_eq!(ChessErrorCode::InvalidMoveCounter, unsafe {
+            chess_game_load_fen(game, bad_fen.as_ptr())
+        });
+        assert_eq!(fen.to_str().unwrap(), fen_of(game));
+
+        let mut small = [0 as c_char; 8];
+        assert_eq!(ChessErrorCode::BufferTooSmall, unsafe {
+            chess_game_fen(game, small.as_mut_ptr(), small.len())
+        });
+        let mut count = 0;
+        assert_eq!(ChessErrorCode::BufferTooSmall, unsafe {
+            chess_game_legal_moves(game, ptr::null_mut(), 0, &mut count)
+        });
+        assert!(count > 0);
+        assert_eq!(ChessErrorCode::NullPointer, unsafe {
+            chess_game_load_fen(ptr::null_mut(), fen.as_ptr())
+        });
+        unsafe { chess_game_free(game) };
+    }
+
+    #[test]
+    fn test_search() {
+        let fen = CString::new("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1").unwrap();
+        let mut game = ptr::null_mut();
+        let mut result = ChessSearchResult {
+            best_move: ChessUciMove { uci: [0; 6] },
+            score: 0,
+            depth: 0,
+            nodes: 0,
+        };
+        let limits = ChessSearchLimits {
+            depth: 2,
+            nodes: 0,
+            move_time_ms: 0,
+        };
+        unsafe {
+            assert_eq!(
+                ChessErrorCode::Ok,
+                chess_game_from_fen(fen.as_ptr(), &mut game)
+            );
+            assert_eq!(
+                ChessErrorCode::Ok,
+                chess_game_search(game, limits, &mut result)
+            );
+        }
+        assert_eq!("a1a8", uci_str(&result.best_move));
+
+        let a1a8 = CString::new("a1a8").unwrap();
+        unsafe {
+            assert_eq!(
+                ChessErrorCode::Ok,
+                chess_game_make_move(game, a1a8.as_ptr())
+            );
+            assert_eq!(
+                ChessErrorCode::GameOver,
+                chess_game_make_move(game, a1a8.as_ptr())
+            );
+            assert_eq!(
+                ChessErrorCode::GameOver,
+                chess_game_search(game, limits, &mut result)
+            );
+            chess_game_free(game);
+        }
+    }
+
+    #[test]
+    fn test_header_declares_every_export() {
+        let sources = [include_str!("ffi_game.rs"), include_str!("ffi_error.rs")];
+        let exports = sources
+            .iter()
+            .flat_map(|source| source.split("extern \"C\" fn ").skip(1))
+            .filter_map(|rest| rest.split('(').next())
+            .collect::<Vec<_>>();
+        assert_eq!(11, exports.len());
+        for export in exports {
+            assert!(
+                HEADER.contains(&format!("{export}(")),
+                "{export} missing from header"
+            );
+        }
+    }
+}
@@ -0,0 +1,2 @@
+pub mod ffi_error;
+pub mod ffi_game;
@@ -1,10 +1,24 @@
+pub mod arena;
 pub mod bit_board;
 pub mod board;
+pub mod book;
 pub mod bot;
 pub mod chess_move;
+pub mod clock;
 pub mod color;
 pub mod direction;
+pub mod eco;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 pub mod notation;
 pub mod piece;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod search;
+#[cfg(feature = "server")]
+pub mod server;
 pub mod state;
+pub mod tablebase;
 pub mod utils;
+#[cfg(feature = "wasm")]
+pub mod wasm;
@@ -0,0 +1,134 @@
+use crate::board::board_position::BoardPosition;
+use crate::chess_move::chess_move::{ChessMove, ChessMoveType};
+use crate::notation::san::legal_moves_with_status;
+use crate::piece::piece::Piece;
+use crate::piece::promotion_piece::PromotionPiece;
+use crate::state::game_state::GameState;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum CoordinateParsingError {
+    #[error("Invalid coordinate move: {0}")]
+    InvalidCoordinate(String),
+    #[error("Illegal move for position: {0}")]
+    IllegalMove(String),
+}
+
+fn square_str(board_position: &BoardPosition) -> String {
+    board_position.to_string().to_ascii_lowercase()
+}
+
+// long algebraic as spoken by uci, e.g. e2e4 or e7e8q, castles are written as the king's move
+pub fn serialize_coordinate(chess_move: &ChessMove) -> String {
+    let promotion = match chess_move.move_type {
+        ChessMoveType::Promotion(promotion_piece) => promotion_piece
+            .as_piece()
+            .as_char()
+            .to_ascii_lowercase()
+            .to_string(),
+        _ => String::new(),
+    };
+    format!(
+        "{}{}{promotion}",
+        square_str(&chess_move.from),
+        square_str(&chess_move.to)
+    )
+}
+
+fn parse_promotion(c: char) -> Option<PromotionPiece> {
+    Some(match Piece::from_char(c).ok()? {
+        Piece::Queen => PromotionPiece::Queen,
+        Piece::Rook => PromotionPiece::Rook,
+        Piece::Bishop => PromotionPiece::Bishop,
+        Piece::Knight => PromotionPiece::Knight,
+        _ => return None,
+    })
+}
+
+// the square of the rook a castle move uses, chess960 guis send king takes rook
+fn castle_rook_pos(game_state: &GameState, chess_move: &ChessMove) -> Option<BoardPosition> {
+    let ChessMoveType::Castle(castle_side) = chess_move.move_type else {
+        return None;
+    };
+    let rook_file = game_state
+        .castle_rights
+        .for_color(game_state.active_color)?
+        .rook_file(castle_side)?;
+    Some(BoardPosition(
+        rook_file,
+        game_state.active_color.back_rank(),
+    ))
+}
+
+pub fn deserialize_coordinate(
+    game_state: &GameState,
+    s: &str,
+) -> Result<ChessMove, CoordinateParsingError> {
+    let invalid = || CoordinateParsingError::InvalidCoordinate(s.to_string());
+    let s = s.trim();
+    if !matches!(s.len(), 4 | 5) || !s.is_ascii() {
+        return Err(invalid());
+    }
+    let from = BoardPosition::from_str(&s[0..2]).map_err(|_| invalid())?;
+    let to = BoardPosition::from_str(&s[2..4]).map_err(|_| invalid())?;
+    let promotion = match s.chars().nth(4) {
+        Some(c) => Some(parse_promotion(c).ok_or_else(invalid)?),
+        None => None,
+    };
+    legal_moves_with_status(game_state)
+        .into_iter()
+        .map(|(chess_move, _)| chess_move)
+        .find(|chess_move| {
+            let move_promotion = match chess_move.move_type {
+                ChessMoveType::Promotion(promotion_piece) => Some(promotion_piece),
+                _ => None,
+            };
+            chess_move.from == from
+                && (chess_move.to == to || castle_rook_pos(game_state, chess_move) == Some(to))
+                && move_promotion == promotion
+        })
+        .ok_or(CoordinateParsingError::IllegalMove(s.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::fen::{deserialize, FEN_STARTING_POS};
+    use crate::notation::san::serialize_san;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(FEN_STARTING_POS, "e2e4", "e4")]
+    #[case(FEN_STARTING_POS, "g1f3", "Nf3")]
+    #[case("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1", "e1g1", "O-O")]
+    #[case("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1", "e8c8", "O-O-O")]
+    #[case("k7/6P1/8/8/8/8/8/4K3 w - - 0 1", "g7g8n", "g8=N")]
+    fn test_coordinate_round_trip(
+        #[case] fen: &'static str,
+        #[case] coordinate: &'static str,
+        #[case] san: &'static str,
+    ) {
+        let game_state = deserialize(fen).expect("bad fen string!");
+        let chess_move = deserialize_coordinate(&game_state, coordinate).expect("valid move");
+        assert_eq!(san, serialize_san(&game_state, &chess_move));
+        assert_eq!(coordinate, serialize_coordinate(&chess_move));
+    }
+
+    #[test]
+    fn test_coordinate_king_takes_rook_castle() {
+        let game_state =
+            deserialize("rk5r/8/8/8/8/8/8/RK5R w HAha - 0 1").expect("bad fen string!");
+        let chess_move = deserialize_coordinate(&game_state, "b1h1").expect("valid move");
+        assert_eq!("O-O", serialize_san(&game_state, &chess_move));
+    }
+
+    #[rstest]
+    #[case(FEN_STARTING_POS, "e2e5")]
+    #[case(FEN_STARTING_POS, "e2")]
+    #[case(FEN_STARTING_POS, "e2e4k")]
+    #[case(FEN_STARTING_POS, "z2e4")]
+    fn test_coordinate_invalid(#[case] fen: &'static str, #[case] coordinate: &'static str) {
+        let game_state = deserialize(fen).expect("bad fen string!");
+        assert!(deserialize_coordinate(&game_state, coordinate).is_err());
+    }
+}
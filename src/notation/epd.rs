@@ -0,0 +1,307 @@
+use crate::chess_move::chess_move::ChessMove;
+use crate::notation::fen::deserialize;
+use crate::notation::fen::deserialize::FenParsingError;
+use crate::notation::san::{deserialize_san, SanParsingError};
+use crate::state::game_state::GameState;
+use thiserror::Error;
+
+pub const ID_OPCODE: &str = "id";
+pub const BEST_MOVE_OPCODE: &str = "bm";
+pub const AVOID_MOVE_OPCODE: &str = "am";
+pub const ANALYSIS_DEPTH_OPCODE: &str = "acd";
+pub const COMMENT_OPCODE: &str = "c0";
+const HALF_MOVE_CLOCK_OPCODE: &str = "hmvc";
+const FULL_MOVE_NUMBER_OPCODE: &str = "fmvn";
+
+#[derive(Error, Debug, Clone)]
+pub enum EpdParsingError {
+    #[error("Invalid epd string: {0}")]
+    InvalidEpdString(String),
+    #[error("Invalid epd position: {0}")]
+    InvalidPosition(FenParsingError),
+    #[error("Unterminated string in epd: {0}")]
+    UnterminatedString(String),
+    #[error("Invalid epd operand for {0}: {1}")]
+    InvalidOperand(String, String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EpdOperation {
+    pub opcode: String,
+    pub operands: Vec<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Epd {
+    pub game_state: GameState,
+    pub operations: Vec<EpdOperation>,
+}
+
+impl Epd {
+    pub fn operation(&self, opcode: &str) -> Option<&[String]> {
+        self.operations
+            .iter()
+            .find(|operation| operation.opcode == opcode)
+            .map(|operation| operation.operands.as_slice())
+    }
+    fn first_operand(&self, opcode: &str) -> Option<&str> {
+        self.operation(opcode)
+            .and_then(|operands| operands.first())
+            .map(String::as_str)
+    }
+    pub fn id(&self) -> Option<&str> {
+        self.first_operand(ID_OPCODE)
+    }
+    pub fn comment(&self) -> Option<&str> {
+        self.first_operand(COMMENT_OPCODE)
+    }
+    pub fn analysis_depth(&self) -> Option<u8> {
+        self.first_operand(ANALYSIS_DEPTH_OPCODE)
+            .and_then(|depth| depth.parse().ok())
+    }
+    fn moves(&self, opcode: &str) -> Result<Vec<ChessMove>, SanParsingError> {
+        self.operation(opcode)
+            .unwrap_or_default()
+            .iter()
+            .map(|san| deserialize_san(&self.game_state, san))
+            .collect()
+    }
+    pub fn best_moves(&self) -> Result<Vec<ChessMove>, SanParsingError> {
+        self.moves(BEST_MOVE_OPCODE)
+    }
+    pub fn avoid_moves(&self) -> Result<Vec<ChessMove>, SanParsingError> {
+        self.moves(AVOID_MOVE_OPCODE)
+    }
+    // STS style suites keep partial credit in c0, e.g. "f5=10, Be5+=2, Bf2=3"
+    pub fn move_points(&self) -> Vec<(ChessMove, u32)> {
+        let Some(comment) = self.comment() else {
+            return vec![];
+        };
+        comment
+            .split(',')
+            .filter_map(|entry| {
+                let (san, points) = entry.trim().split_once('=')?;
+                let points = points.trim().parse().ok()?;
+                let chess_move = deserialize_san(&self.game_state, san.trim()).ok()?;
+                Some((chess_move, points))
+            })
+            .collect()
+    }
+}
+
+// operands are separated by whitespace, double quoted strings may contain both whitespace and ';'
+fn split_operations(operations_str: &str) -> Result<Vec<Vec<String>>, EpdParsingError> {
+    let mut operations = vec![];
+    let mut tokens = vec![];
+    let mut chars = operations_str.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            ';' => operations.push(std::mem::take(&mut tokens)),
+            '"' => {
+                let mut token = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => token.extend(chars.next()),
+                        Some(c) => token.push(c),
+                        None => {
+                            return Err(EpdParsingError::UnterminatedString(
+                                operations_str.to_string(),
+                            ))
+                        }
+                    }
+                }
+                tokens.push(token);
+            }
+            c if c.is_whitespace() => {}
+            c => {
+                let mut token = String::from(c);
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == ';' || c == '"' {
+                        break;
+                    }
+                    token.push(c);
+                    chars.next();
+                }
+                tokens.push(token);
+            }
+        }
+    }
+    // the final ';' is optional in the wild
+    if !tokens.is_empty() {
+        operations.push(tokens);
+    }
+    Ok(operations)
+}
+
+fn parse_operations(operations_str: &str) -> Result<Vec<EpdOperation>, EpdParsingError> {
+    split_operations(operations_str)?
+        .into_iter()
+        .filter(|tokens| !tokens.is_empty())
+        .map(|mut tokens| {
+            let opcode = tokens.remove(0);
+            if !opcode
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_')
+            {
+                return Err(EpdParsingError::InvalidEpdString(opcode));
+            }
+            Ok(EpdOperation {
+                opcode,
+                operands: tokens,
+            })
+        })
+        .collect()
+}
+
+fn clock_operand(
+    operations: &[EpdOperation],
+    opcode: &str,
+    default: &'static str,
+) -> Result<String, EpdParsingError> {
+    let Some(operation) = operations
+        .iter()
+        .find(|operation| operation.opcode == opcode)
+    else {
+        return Ok(default.to_string());
+    };
+    match operation.operands.as_slice() {
+        [value] if value.parse::<u16>().is_ok() => Ok(value.clone()),
+        _ => Err(EpdParsingError::InvalidOperand(
+            opcode.to_string(),
+            operation.operands.join(" "),
+        )),
+    }
+}
+
+pub fn deserialize_epd(epd_str: &str) -> Result<Epd, EpdParsingError> {
+    let epd_str = epd_str.trim();
+    let position_fields = epd_str.split_whitespace().take(4).collect::<Vec<_>>();
+    if position_fields.len() != 4 {
+        return Err(EpdParsingError::InvalidEpdString(epd_str.to_string()));
+    }
+    let last_field = position_fields[3];
+    // the fields are slices of epd_str, the operations start right after the last one
+    let operations_start =
+        last_field.as_ptr() as usize - epd_str.as_ptr() as usize + last_field.len();
+    let operations = parse_operations(&epd_str[operations_start..])?;
+    // epd has no clock fields, hmvc and fmvn fill them in when present
+    let fen_str = format!(
+        "{} {} {}",
+        position_fields.join(" "),
+        clock_operand(&operations, HALF_MOVE_CLOCK_OPCODE, "0")?,
+        clock_operand(&operations, FULL_MOVE_NUMBER_OPCODE, "1")?,
+    );
+    let game_state = deserialize(&fen_str).map_err(EpdParsingError::InvalidPosition)?;
+    Ok(Epd {
+        game_state,
+        operations,
+    })
+}
+
+// blank lines and lines starting with '#' are skipped
+pub fn deserialize_epd_suite(suite_str: &str) -> Result<Vec<Epd>, EpdParsingError> {
+    suite_str
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(deserialize_epd)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::position::*;
+    use crate::piece::chess_piece::ChessPiece;
+    use rstest::rstest;
+
+    #[test]
+    fn test_deserialize_epd() {
+        let epd = deserialize_epd(
+            "2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - bm Qg6; id \"WAC.001\";",
+        )
+        .expect("bad epd string!");
+        assert_eq!(Some("WAC.001"), epd.id());
+        assert_eq!(
+            vec![ChessMove::create_normal(ChessPiece::WhiteQueen, G3, G6)],
+            epd.best_moves().expect("bad best move!")
+        );
+        assert!(epd.avoid_moves().expect("bad avoid move!").is_empty());
+        assert_eq!(1, epd.game_state.move_counter.full_move);
+    }
+
+    #[test]
+    fn test_deserialize_epd_operations() {
+        let epd = deserialize_epd(
+            "r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - am Qxf7+ Nc3; acd 12; c0 \"semi; colon\"; hmvc 4; fmvn 4",
+        )
+        .expect("bad epd string!");
+        assert_eq!(
+            vec![
+                ChessMove::create_normal_capture(
+                    ChessPiece::WhiteQueen,
+                    H5,
+                    F7,
+                    ChessPiece::BlackPawn
+                ),
+                ChessMove::create_normal(ChessPiece::WhiteKnight, B1, C3),
+            ],
+            epd.avoid_moves().expect("bad avoid move!")
+        );
+        assert_eq!(Some(12), epd.analysis_depth());
+        assert_eq!(Some("semi; colon"), epd.comment());
+        assert_eq!(4, epd.game_state.move_counter.half_move);
+        assert_eq!(4, epd.game_state.move_counter.full_move);
+    }
+
+    #[test]
+    fn test_deserialize_epd_repeated_whitespace() {
+        let epd =
+            deserialize_epd("6k1/5ppp/8/8/8/8/5PPP/R5K1  w\t-   -  bm Ra8#;  id \"two  spaces\";")
+                .expect("bad epd string!");
+        assert_eq!(
+            vec![ChessMove::create_normal(ChessPiece::WhiteRook, A1, A8)],
+            epd.best_moves().expect("bad best move!")
+        );
+        assert_eq!(Some("two  spaces"), epd.id());
+        let epd = deserialize_epd("6k1/5ppp/8/8/8/8/5PPP/R5K1 w  -  -").expect("bad epd string!");
+        assert!(epd.operations.is_empty());
+    }
+
+    #[test]
+    fn test_move_points() {
+        let epd = deserialize_epd(
+            "6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - bm Ra8#; c0 \"Ra8#=10, Ra7=3, Kf1=1\";",
+        )
+        .expect("bad epd string!");
+        assert_eq!(
+            vec![
+                (ChessMove::create_normal(ChessPiece::WhiteRook, A1, A8), 10),
+                (ChessMove::create_normal(ChessPiece::WhiteRook, A1, A7), 3),
+                (ChessMove::create_normal(ChessPiece::WhiteKing, G1, F1), 1),
+            ],
+            epd.move_points()
+        );
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("8/8/8/8/8/8/8/8 w -")]
+    #[case("2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - id \"WAC.001")]
+    #[case("2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - hmvc x;")]
+    #[case("2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 x - - bm Qg6;")]
+    fn test_deserialize_invalid_epd(#[case] epd_str: &str) {
+        assert!(deserialize_epd(epd_str).is_err());
+    }
+
+    #[test]
+    fn test_deserialize_epd_suite() {
+        let suite = "# mate in one\n\n6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - bm Ra8#; id \"mate.1\";\n6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - bm Ra8#; id \"mate.2\";\n";
+        let epds = deserialize_epd_suite(suite).expect("bad epd suite!");
+        assert_eq!(
+            vec![Some("mate.1"), Some("mate.2")],
+            epds.iter().map(Epd::id).collect::<Vec<_>>()
+        );
+    }
+}
@@ -10,6 +10,7 @@ use crate::state::color_castle_rights::ColorCastleRights;
 use crate::state::game_state::GameState;
 use crate::state::game_status::{GameStatus, is_check, is_check_mate};
 use crate::state::state_history::StateHistoryContainer;
+use crate::state::validate::{validate, PositionError};
 
 #[derive(Error, Debug, Clone)]
 pub enum FenParsingError {
@@ -29,6 +30,18 @@ pub enum FenParsingError {
     InvalidBoardStringBoardRankParseError(BoardRankError),
     #[error("Invalid fen string: {0}")]
     InvalidFenString(String),
+    #[error("Invalid move counter: {0}")]
+    InvalidMoveCounter(String),
+    #[error("Invalid position: {}", format_position_errors(.0))]
+    InvalidPosition(Vec<PositionError>),
+}
+
+fn format_position_errors(errors: &[PositionError]) -> String {
+    errors
+        .iter()
+        .map(PositionError::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
 pub fn get_parts(fen_str: &str) -> Result<FenParts, FenParsingError> {
@@ -84,16 +97,12 @@ pub fn deserialize(fen_str: &str) -> Result<GameState, FenParsingError> {
         Ok(color) => color.as_color(),
         Err(e) => return Err(e),
     };
-    game_state.castle_rights = match ColorCastleRights::from_str(parts.castle_rights_str) {
-        Ok(castle_rights) => castle_rights,
-        Err(e) => return Err(FenParsingError::InvalidCastleRights(e)),
-    };
     game_state.move_counter.half_move = parts.half_move_clock_str
         .parse::<u16>()
-        .expect("invalid half_move_clock_str");
+        .map_err(|_| FenParsingError::InvalidMoveCounter(parts.half_move_clock_str.to_string()))?;
     game_state.move_counter.full_move = parts.full_move_num_str
         .parse::<u16>()
-        .expect("invalid full_move_clock_str");
+        .map_err(|_| FenParsingError::InvalidMoveCounter(parts.full_move_num_str.to_string()))?;
     game_state.en_passant_target_pos = match get_en_passant_pos_from_str(parts.en_passant_str) {
         Ok(pos) => pos,
         Err(e) => return Err(FenParsingError::InvalidEnPassant(e)),
@@ -142,9 +151,19 @@ pub fn deserialize(fen_str: &str) -> Result<GameState, FenParsingError> {
             }
         }
     }
+    // x-fen castle rights refer to rooks on the board
+    game_state.castle_rights = match ColorCastleRights::from_fen_str(parts.castle_rights_str, &game_state.board) {
+        Ok(castle_rights) => castle_rights,
+        Err(e) => return Err(FenParsingError::InvalidCastleRights(e)),
+    };
     game_state.history.state_history = Some(StateHistoryContainer::New(
         game_state.board.as_bit_boards_const(),
     ));
+    // so the moves played from here can be replayed later
+    let start_fen = crate::notation::fen::serialize(&game_state);
+    if start_fen.get_str() != crate::notation::fen::FEN_STARTING_POS {
+        game_state.history.start_fen = Some(start_fen.get_str().to_string());
+    }
     if is_check(&game_state) {
         game_state.game_status = GameStatus::Check(game_state.active_color);
         if is_check_mate(&game_state) {
@@ -153,3 +172,76 @@ pub fn deserialize(fen_str: &str) -> Result<GameState, FenParsingError> {
     }
     Ok(game_state)
 }
+
+fn validate_squares_str(squares_str: &str) -> Result<(), FenParsingError> {
+    let rows = squares_str.split(BOARD_TERMINATOR).collect::<Vec<_>>();
+    if rows.len() != 8 {
+        return Err(FenParsingError::InvalidBoardString(squares_str.to_string()));
+    }
+    for row in rows {
+        let mut files = 0;
+        for char in row.chars() {
+            files += match char.to_digit(10) {
+                Some(blanks @ 1..=8) => blanks,
+                Some(_) => return Err(FenParsingError::InvalidBoardString(row.to_string())),
+                None => 1,
+            };
+        }
+        if files != 8 {
+            return Err(FenParsingError::InvalidBoardString(row.to_string()));
+        }
+    }
+    Ok(())
+}
+
+// rejects anything deserialize would silently accept or panic on, including positions that can't be reached
+pub fn deserialize_strict(fen_str: &str) -> Result<GameState, FenParsingError> {
+    let parts = get_parts(fen_str)?;
+    validate_squares_str(parts.squares_str)?;
+    for move_counter_str in [parts.half_move_clock_str, parts.full_move_num_str] {
+        if move_counter_str.parse::<u16>().is_err() {
+            return Err(FenParsingError::InvalidMoveCounter(move_counter_str.to_string()));
+        }
+    }
+    let game_state = deserialize(fen_str)?;
+    validate(&game_state).map_err(FenParsingError::InvalidPosition)?;
+    Ok(game_state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::fen::FEN_STARTING_POS;
+    use rstest::rstest;
+
+    #[test]
+    fn test_deserialize_strict() {
+        assert!(deserialize_strict(FEN_STARTING_POS).is_ok());
+    }
+
+    #[rstest]
+    #[case("rnbqkbnr/pppppppp/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1")]
+    #[case("rnbqkbnr/pppppppp/9/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1")]
+    #[case("rnbqkbnr/ppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1")]
+    #[case("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - x 1")]
+    #[case("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 -1")]
+    fn test_deserialize_strict_invalid_fen(#[case] fen: &str) {
+        assert!(deserialize_strict(fen).is_err());
+    }
+
+    #[test]
+    fn test_deserialize_strict_invalid_position() {
+        let fen = "4k3/8/8/8/8/8/8/4Q1K1 w - e6 0 1";
+        assert!(deserialize(fen).is_ok());
+        let Err(FenParsingError::InvalidPosition(errors)) = deserialize_strict(fen) else {
+            panic!("expected an invalid position");
+        };
+        assert_eq!(
+            vec![
+                PositionError::InactiveSideInCheck(crate::color::Color::Black),
+                PositionError::InvalidEnPassant(crate::board::position::E6),
+            ],
+            errors
+        );
+    }
+}
@@ -2,8 +2,8 @@ pub mod serialize;
 pub mod deserialize;
 mod fen_parts;
 
-pub use serialize::serialize;
-pub use deserialize::deserialize;
+pub use serialize::{serialize, serialize_with_castling_notation};
+pub use deserialize::{deserialize, deserialize_strict};
 
 use std::fmt::{Display, Formatter};
 use crate::color::Color;
@@ -3,7 +3,7 @@ use crate::board::board_position::BoardPosition;
 use crate::notation::fen::{BOARD_TERMINATOR, Fen};
 use crate::notation::fen::ActiveColor;
 use crate::piece::chess_piece::ChessPiece;
-use crate::state::color_castle_rights::ColorCastleRights;
+use crate::state::color_castle_rights::CastlingNotation;
 use crate::state::game_state::GameState;
 
 const EMPTY: &str = "";
@@ -13,7 +13,7 @@ const DASH: char = '-';
 struct FenData {
     squares: [[Option<ChessPiece>; 8]; 8],
     active_color: ActiveColor,
-    castle: ColorCastleRights,
+    castle: String,
     en_passant: Option<BoardPosition>,
     half_move_clock: u16,
     full_move_num: u16,
@@ -49,7 +49,7 @@ impl FenData {
         let str: String = [
             board_str,
             self.active_color.as_char().to_string(),
-            self.castle.as_str().to_string(),
+            self.castle.clone(),
             if self.en_passant.is_some() {
                 self.en_passant.unwrap().to_string().to_lowercase()
             } else {
@@ -67,10 +67,14 @@ impl FenData {
 }
 
 pub fn serialize(game_state: &GameState) -> Fen {
+    serialize_with_castling_notation(game_state, CastlingNotation::XFen)
+}
+
+pub fn serialize_with_castling_notation(game_state: &GameState, castling_notation: CastlingNotation) -> Fen {
     let mut fen = FenData {
         squares: [[None; 8]; 8],
         active_color: ActiveColor::from_color(game_state.active_color),
-        castle: game_state.castle_rights.clone(),
+        castle: game_state.castle_rights.to_fen_string(&game_state.board, castling_notation),
         en_passant: game_state.en_passant_target_pos,
         half_move_clock: game_state.move_counter.half_move,
         full_move_num: game_state.move_counter.full_move,
@@ -95,6 +99,7 @@ pub fn serialize_without_clock_and_active_color(game_state: GameState) -> String
 mod tests {
     use rstest::rstest;
     use crate::notation::fen::*;
+    use crate::state::color_castle_rights::CastlingNotation;
     use crate::state::game_state::GameState;
 
     #[rstest]
@@ -103,4 +108,16 @@ mod tests {
     pub fn fen_serialize(#[case] game_state: GameState, #[case] expected: &'static str) {
         assert_eq!(expected, serialize(&game_state).get_str())
     }
+
+    #[rstest]
+    #[case(FEN_STARTING_POS, "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w HAha - 0 1")]
+    #[case("nrbqkbrn/pppppppp/8/8/8/8/PPPPPPPP/NRBQKBRN w KQkq - 0 1", "nrbqkbrn/pppppppp/8/8/8/8/PPPPPPPP/NRBQKBRN w GBgb - 0 1")]
+    #[case("4k3/8/8/8/8/8/8/R2RK2R w KD - 0 1", "4k3/8/8/8/8/8/8/R2RK2R w HD - 0 1")]
+    pub fn fen_serialize_shredder(#[case] x_fen: &'static str, #[case] shredder_fen: &'static str) {
+        let game_state = deserialize(x_fen).expect("bad fen string!");
+        assert_eq!(x_fen, serialize(&game_state).get_str());
+        assert_eq!(shredder_fen, serialize_with_castling_notation(&game_state, CastlingNotation::Shredder).get_str());
+        let game_state = deserialize(shredder_fen).expect("bad fen string!");
+        assert_eq!(x_fen, serialize(&game_state).get_str());
+    }
 }
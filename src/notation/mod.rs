@@ -1,2 +1,6 @@
+pub mod coordinate;
+pub mod epd;
 pub mod fen;
+pub mod packed;
 pub mod pgn;
+pub mod san;
@@ -0,0 +1,258 @@
+use crate::board::board_file::BoardFile;
+use crate::board::board_position::BoardPosition;
+use crate::color::Color;
+use crate::direction::castle_side::CastleSide;
+use crate::piece::chess_piece::ChessPiece;
+use crate::state::castle_rights::CastleRights;
+use crate::state::game_state::GameState;
+use crate::state::game_status::{is_check, is_check_mate, GameStatus};
+use crate::state::state_history::StateHistoryContainer;
+use thiserror::Error;
+
+// occupancy bitboard, a nibble per occupied square, then side, castling, en passant and clocks
+pub const PACKED_POSITION_SIZE: usize = 32;
+const MAX_PIECES: usize = 32;
+const PIECES_OFFSET: usize = 8;
+const ACTIVE_COLOR_OFFSET: usize = 24;
+const WHITE_CASTLE_RIGHTS_OFFSET: usize = 25;
+const BLACK_CASTLE_RIGHTS_OFFSET: usize = 26;
+const EN_PASSANT_OFFSET: usize = 27;
+const HALF_MOVE_OFFSET: usize = 28;
+const FULL_MOVE_OFFSET: usize = 30;
+
+const PIECE_CODES: [ChessPiece; 12] = [
+    ChessPiece::WhitePawn,
+    ChessPiece::WhiteKnight,
+    ChessPiece::WhiteBishop,
+    ChessPiece::WhiteRook,
+    ChessPiece::WhiteQueen,
+    ChessPiece::WhiteKing,
+    ChessPiece::BlackPawn,
+    ChessPiece::BlackKnight,
+    ChessPiece::BlackBishop,
+    ChessPiece::BlackRook,
+    ChessPiece::BlackQueen,
+    ChessPiece::BlackKing,
+];
+
+pub type PackedPosition = [u8; PACKED_POSITION_SIZE];
+
+#[derive(Error, Debug, Copy, Clone, PartialEq)]
+pub enum PackedPositionError {
+    #[error("Too many pieces to pack, expected at most {MAX_PIECES}, received: {0}")]
+    TooManyPieces(usize),
+    #[error("Invalid packed position length, expected {PACKED_POSITION_SIZE}, received: {0}")]
+    InvalidLength(usize),
+    #[error("Invalid packed piece code: {0}")]
+    InvalidPieceCode(u8),
+    #[error("Invalid packed active color: {0}")]
+    InvalidActiveColor(u8),
+    #[error("Invalid packed castle rights: {0}")]
+    InvalidCastleRights(u8),
+    #[error("Invalid packed en passant square: {0}")]
+    InvalidEnPassant(u8),
+}
+
+fn piece_code(chess_piece: ChessPiece) -> u8 {
+    PIECE_CODES
+        .iter()
+        .position(|&other| other == chess_piece)
+        .expect("every chess piece has a code") as u8
+}
+
+// 0 means no rights, otherwise the rook file plus one
+fn pack_rook_file(rook_file: Option<BoardFile>) -> u8 {
+    rook_file.map_or(0, |file| file.as_zero_based_index() as u8 + 1)
+}
+
+fn unpack_rook_file(nibble: u8, byte: u8) -> Result<Option<BoardFile>, PackedPositionError> {
+    match nibble {
+        0 => Ok(None),
+        _ => BoardFile::from_zero_based_index(nibble as usize - 1)
+            .map(Some)
+            .map_err(|_| PackedPositionError::InvalidCastleRights(byte)),
+    }
+}
+
+fn pack_castle_rights(castle_rights: Option<CastleRights>) -> u8 {
+    let Some(castle_rights) = castle_rights else {
+        return 0;
+    };
+    pack_rook_file(castle_rights.rook_file(CastleSide::King))
+        | pack_rook_file(castle_rights.rook_file(CastleSide::Queen)) << 4
+}
+
+fn unpack_castle_rights(byte: u8) -> Result<Option<CastleRights>, PackedPositionError> {
+    let mut castle_rights = None;
+    for (castle_side, nibble) in [
+        (CastleSide::King, byte & 0xF),
+        (CastleSide::Queen, byte >> 4),
+    ] {
+        if let Some(rook_file) = unpack_rook_file(nibble, byte)? {
+            castle_rights = CastleRights::with_option(castle_rights, castle_side, rook_file);
+        }
+    }
+    Ok(castle_rights)
+}
+
+pub fn serialize_packed(game_state: &GameState) -> Result<PackedPosition, PackedPositionError> {
+    let mut packed = [0u8; PACKED_POSITION_SIZE];
+    let mut occupancy = 0u64;
+    let mut piece_count = 0;
+    for index in 0..64 {
+        let pos = BoardPosition::from_pos_index(index).expect("index out of range");
+        let Some(chess_piece) = *game_state.board.get(pos) else {
+            continue;
+        };
+        if piece_count < MAX_PIECES {
+            packed[PIECES_OFFSET + piece_count / 2] |=
+                piece_code(chess_piece) << (piece_count % 2 * 4);
+        }
+        occupancy |= 1 << index;
+        piece_count += 1;
+    }
+    if piece_count > MAX_PIECES {
+        return Err(PackedPositionError::TooManyPieces(piece_count));
+    }
+    packed[..PIECES_OFFSET].copy_from_slice(&occupancy.to_le_bytes());
+    packed[ACTIVE_COLOR_OFFSET] = (game_state.active_color == Color::Black) as u8;
+    packed[WHITE_CASTLE_RIGHTS_OFFSET] = pack_castle_rights(game_state.castle_rights.white);
+    packed[BLACK_CASTLE_RIGHTS_OFFSET] = pack_castle_rights(game_state.castle_rights.black);
+    packed[EN_PASSANT_OFFSET] = game_state
+        .en_passant_target_pos
+        .map_or(0, |pos| pos.as_pos_index() as u8 + 1);
+    packed[HALF_MOVE_OFFSET..FULL_MOVE_OFFSET]
+        .copy_from_slice(&game_state.move_counter.half_move.to_le_bytes());
+    packed[FULL_MOVE_OFFSET..].copy_from_slice(&game_state.move_counter.full_move.to_le_bytes());
+    Ok(packed)
+}
+
+pub fn deserialize_packed(bytes: &[u8]) -> Result<GameState, PackedPositionError> {
+    let packed: &PackedPosition = bytes
+        .try_into()
+        .map_err(|_| PackedPositionError::InvalidLength(bytes.len()))?;
+    let mut game_state = GameState::empty();
+    let occupancy = u64::from_le_bytes(
+        packed[..PIECES_OFFSET]
+            .try_into()
+            .expect("occupancy is 8 bytes"),
+    );
+    let piece_count = occupancy.count_ones() as usize;
+    if piece_count > MAX_PIECES {
+        return Err(PackedPositionError::TooManyPieces(piece_count));
+    }
+    let occupied = (0..64).filter(|index| occupancy & (1 << index) != 0);
+    for (piece_ix, index) in occupied.enumerate() {
+        let code = packed[PIECES_OFFSET + piece_ix / 2] >> (piece_ix % 2 * 4) & 0xF;
+        let chess_piece = *PIECE_CODES
+            .get(code as usize)
+            .ok_or(PackedPositionError::InvalidPieceCode(code))?;
+        let pos = BoardPosition::from_pos_index(index).expect("index out of range");
+        game_state.board.set(pos, Some(chess_piece));
+    }
+    game_state.active_color = match packed[ACTIVE_COLOR_OFFSET] {
+        0 => Color::White,
+        1 => Color::Black,
+        byte => return Err(PackedPositionError::InvalidActiveColor(byte)),
+    };
+    game_state.castle_rights.white = unpack_castle_rights(packed[WHITE_CASTLE_RIGHTS_OFFSET])?;
+    game_state.castle_rights.black = unpack_castle_rights(packed[BLACK_CASTLE_RIGHTS_OFFSET])?;
+    game_state.en_passant_target_pos = match packed[EN_PASSANT_OFFSET] {
+        0 => None,
+        byte => Some(
+            BoardPosition::from_pos_index(byte as usize - 1)
+                .ok_or(PackedPositionError::InvalidEnPassant(byte))?,
+        ),
+    };
+    game_state.move_counter.half_move =
+        u16::from_le_bytes([packed[HALF_MOVE_OFFSET], packed[HALF_MOVE_OFFSET + 1]]);
+    game_state.move_counter.full_move =
+        u16::from_le_bytes([packed[FULL_MOVE_OFFSET], packed[FULL_MOVE_OFFSET + 1]]);
+    // same starting point as a position loaded from fen
+    game_state.history.state_history = Some(StateHistoryContainer::New(
+        game_state.board.as_bit_boards_const(),
+    ));
+    if is_check(&game_state) {
+        game_state.game_status = GameStatus::Check(game_state.active_color);
+        if is_check_mate(&game_state) {
+            game_state.game_status = GameStatus::CheckMate(game_state.active_color);
+        }
+    }
+    Ok(game_state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::fen::{
+        deserialize, serialize, serialize_with_castling_notation, FEN_STARTING_POS,
+    };
+    use crate::state::color_castle_rights::CastlingNotation;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(FEN_STARTING_POS)]
+    #[case("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1")]
+    #[case("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")]
+    #[case("4k3/8/8/8/8/8/8/4K2R w K - 49 300")]
+    #[case("6k1/5ppp/8/8/8/8/5PPP/R5K1 b - - 1 40")]
+    #[case("8/8/8/8/8/8/8/8 w - - 0 1")]
+    // chess960 rights on inner rooks
+    #[case("bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9")]
+    #[case("4k3/8/8/8/8/8/8/R2RK2R w HD - 0 1")]
+    fn test_packed_round_trip(#[case] fen: &str) {
+        let game_state = deserialize(fen).expect("bad fen string!");
+        let packed = serialize_packed(&game_state).expect("failed to pack position!");
+        let unpacked = deserialize_packed(&packed).expect("failed to unpack position!");
+        assert_eq!(
+            serialize(&game_state).get_str(),
+            serialize(&unpacked).get_str()
+        );
+        assert_eq!(
+            serialize_with_castling_notation(&game_state, CastlingNotation::Shredder).get_str(),
+            serialize_with_castling_notation(&unpacked, CastlingNotation::Shredder).get_str()
+        );
+        assert_eq!(game_state.game_status, unpacked.game_status);
+        assert_eq!(
+            packed,
+            serialize_packed(&unpacked).expect("failed to pack position!")
+        );
+    }
+
+    #[test]
+    fn test_packed_rejects_too_many_pieces() {
+        let game_state = deserialize("qqqqkqqq/pppppppp/8/8/8/Q7/PPPPPPPP/QQQQKQQQ w - - 0 1")
+            .expect("bad fen string!");
+        assert_eq!(
+            Err(PackedPositionError::TooManyPieces(33)),
+            serialize_packed(&game_state)
+        );
+    }
+
+    #[rstest]
+    #[case(&[0u8; 31], PackedPositionError::InvalidLength(31))]
+    #[case(&{
+        let mut packed = [0u8; PACKED_POSITION_SIZE];
+        packed[0] = 1;
+        packed[PIECES_OFFSET] = 12;
+        packed
+    }, PackedPositionError::InvalidPieceCode(12))]
+    #[case(&{
+        let mut packed = [0u8; PACKED_POSITION_SIZE];
+        packed[ACTIVE_COLOR_OFFSET] = 2;
+        packed
+    }, PackedPositionError::InvalidActiveColor(2))]
+    #[case(&{
+        let mut packed = [0u8; PACKED_POSITION_SIZE];
+        packed[WHITE_CASTLE_RIGHTS_OFFSET] = 9;
+        packed
+    }, PackedPositionError::InvalidCastleRights(9))]
+    #[case(&{
+        let mut packed = [0u8; PACKED_POSITION_SIZE];
+        packed[EN_PASSANT_OFFSET] = 65;
+        packed
+    }, PackedPositionError::InvalidEnPassant(65))]
+    fn test_packed_invalid(#[case] bytes: &[u8], #[case] expected: PackedPositionError) {
+        assert_eq!(Some(expected), deserialize_packed(bytes).err());
+    }
+}
@@ -1,3 +0,0 @@
-#[repr(transparent)]
-#[derive(Clone)]
-pub struct Pgn(String);
@@ -0,0 +1,488 @@
+use crate::notation::pgn::{PgnGame, PgnGameTree, PgnResult, PgnTreeError, FEN_TAG};
+use crate::state::annotations::nag_from_suffix;
+use crate::state::game_state::GameState;
+use crate::state::game_tree::{GameTree, NodeId};
+use std::iter::Peekable;
+use std::str::Chars;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum PgnParsingError {
+    #[error("Invalid pgn tag: {0}")]
+    InvalidTag(String),
+    #[error("Unterminated pgn {0}")]
+    Unterminated(&'static str),
+    #[error("Unexpected character in pgn: {0}")]
+    UnexpectedChar(char),
+}
+
+const TOKEN_TERMINATORS: [char; 7] = ['{', '}', '(', ')', '[', ']', ';'];
+
+fn read_until(chars: &mut Peekable<Chars>, terminator: char) -> Option<String> {
+    let mut s = String::new();
+    for c in chars.by_ref() {
+        if c == terminator {
+            return Some(s);
+        }
+        s.push(c);
+    }
+    None
+}
+
+fn parse_tag(tag: &str) -> Result<(String, String), PgnParsingError> {
+    let tag = tag.trim();
+    let (name, value) = tag
+        .split_once(char::is_whitespace)
+        .ok_or_else(|| PgnParsingError::InvalidTag(tag.to_string()))?;
+    let value = value
+        .trim()
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .ok_or_else(|| PgnParsingError::InvalidTag(tag.to_string()))?;
+    Ok((
+        name.to_string(),
+        value.replace("\\\"", "\"").replace("\\\\", "\\"),
+    ))
+}
+
+// variations are skipped, only the main line is kept
+fn skip_variation(chars: &mut Peekable<Chars>) -> Result<(), PgnParsingError> {
+    let mut depth = 1;
+    while depth > 0 {
+        match chars.next() {
+            Some('(') => depth += 1,
+            Some(')') => depth -= 1,
+            Some('{') => {
+                read_until(chars, '}').ok_or(PgnParsingError::Unterminated("comment"))?;
+            }
+            Some(_) => {}
+            None => return Err(PgnParsingError::Unterminated("variation")),
+        }
+    }
+    Ok(())
+}
+
+// strips move numbers like "12." or "12..." that may be glued to the move
+fn strip_move_number(token: &str) -> &str {
+    let without_number = token.trim_start_matches(|c: char| c.is_ascii_digit());
+    if without_number.len() != token.len() && without_number.starts_with('.') {
+        without_number.trim_start_matches('.')
+    } else {
+        token
+    }
+}
+
+pub fn deserialize(pgn: &str) -> Result<Vec<PgnGame>, PgnParsingError> {
+    let mut games = vec![];
+    let mut game = PgnGame::new();
+    let mut has_content = false;
+    let mut chars = pgn.chars().peekable();
+    let mut at_line_start = true;
+
+    while let Some(&c) = chars.peek() {
+        // "%" escapes the rest of the line, but only in the first column
+        if at_line_start && c == '%' {
+            read_until(&mut chars, '\n');
+            continue;
+        }
+        at_line_start = c == '\n';
+        match c {
+            _ if c.is_whitespace() => {
+                chars.next();
+            }
+            '[' => {
+                chars.next();
+                if !game.moves.is_empty() {
+                    // a game without a result token, start the next one
+                    games.push(std::mem::take(&mut game));
+                }
+                let tag =
+                    read_until(&mut chars, ']').ok_or(PgnParsingError::Unterminated("tag"))?;
+                game.tags.push(parse_tag(&tag)?);
+                has_content = true;
+            }
+            '{' => {
+                chars.next();
+                read_until(&mut chars, '}').ok_or(PgnParsingError::Unterminated("comment"))?;
+            }
+            ';' => {
+                read_until(&mut chars, '\n');
+                at_line_start = true;
+            }
+            '(' => {
+                chars.next();
+                skip_variation(&mut chars)?;
+            }
+            ')' | ']' | '}' => return Err(PgnParsingError::UnexpectedChar(c)),
+            _ => {
+                let mut token = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || TOKEN_TERMINATORS.contains(&c) {
+                        break;
+                    }
+                    token.push(c);
+                    chars.next();
+                }
+                if let Some(result) = PgnResult::try_from_str(&token) {
+                    game.result = result;
+                    games.push(std::mem::take(&mut game));
+                    has_content = false;
+                    continue;
+                }
+                has_content = true;
+                // numeric annotation glyphs
+                if token.starts_with('$') {
+                    continue;
+                }
+                let san = strip_move_number(&token);
+                if !san.is_empty() {
+                    game.moves.push(san.to_string());
+                }
+            }
+        }
+    }
+    if has_content {
+        games.push(game);
+    }
+    Ok(games)
+}
+
+// builds the tree of one game as its movetext is read
+struct GameTreeBuilder {
+    tags: Vec<(String, String)>,
+    tree: Option<GameTree>,
+    // the last move of the line being read and the position after it
+    node: NodeId,
+    game_state: GameState,
+    // where each open variation branched off, to carry on from once it closes
+    variations: Vec<(NodeId, GameState)>,
+    // a comment ahead of the first move of a variation
+    pre_comment: Option<String>,
+    at_variation_start: bool,
+}
+
+impl GameTreeBuilder {
+    fn new() -> Self {
+        Self {
+            tags: vec![],
+            tree: None,
+            node: GameTree::ROOT,
+            game_state: GameState::new(),
+            variations: vec![],
+            pre_comment: None,
+            at_variation_start: false,
+        }
+    }
+    fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.tree.is_none()
+    }
+    fn has_moves(&self) -> bool {
+        self.tree
+            .as_ref()
+            .is_some_and(|tree| tree.next(GameTree::ROOT).is_some())
+    }
+    // the tree is only created once the tags are in, since the FEN tag sets its start
+    fn start(&mut self) -> Result<&mut GameTree, PgnTreeError> {
+        if self.tree.is_none() {
+            let start = match self.tags.iter().find(|(name, _)| name == FEN_TAG) {
+                Some((_, fen)) => {
+                    crate::notation::fen::deserialize(fen).map_err(PgnTreeError::InvalidFen)?
+                }
+                None => GameState::new(),
+            };
+            self.game_state = start.clone();
+            self.tree = Some(GameTree::new(start));
+        }
+        Ok(self.tree.as_mut().expect("the tree was just created"))
+    }
+    fn play(&mut self, token: &str) -> Result<(), PgnTreeError> {
+        let san = token.trim_end_matches(['!', '?']);
+        let nag = nag_from_suffix(&token[san.len()..]);
+        self.start()?;
+        let tree = self.tree.as_mut().expect("the tree was started");
+        let (node, game_state) = tree
+            .insert_san(self.node, &self.game_state, san)
+            .map_err(|err| PgnTreeError::InvalidMove(token.to_string(), err))?;
+        let annotations = tree.annotations_mut(node).expect("the node was just added");
+        annotations.nags.extend(nag);
+        if let Some(pre_comment) = self.pre_comment.take() {
+            annotations.pre_comment = Some(pre_comment);
+        }
+        self.node = node;
+        self.game_state = game_state;
+        self.at_variation_start = false;
+        Ok(())
+    }
+    fn comment(&mut self, comment: &str) -> Result<(), PgnTreeError> {
+        let node = self.node;
+        if !self.at_variation_start {
+            if let Some(annotations) = self.start()?.annotations_mut(node) {
+                annotations.add_pgn_comment(comment);
+            }
+            return Ok(());
+        }
+        let comment = comment.trim();
+        self.pre_comment = Some(match self.pre_comment.take() {
+            Some(existing) => format!("{existing} {comment}"),
+            None => comment.to_string(),
+        });
+        Ok(())
+    }
+    fn nag(&mut self, nag: u8) -> Result<(), PgnTreeError> {
+        let node = self.node;
+        if let Some(annotations) = self.start()?.annotations_mut(node) {
+            annotations.nags.push(nag);
+        }
+        Ok(())
+    }
+    // a variation replaces the last move played
+    fn open_variation(&mut self) -> Result<(), PgnTreeError> {
+        let node = self.node;
+        let tree = self.start()?;
+        let parent = tree
+            .parent(node)
+            .ok_or(PgnParsingError::UnexpectedChar('('))?;
+        let game_state = tree
+            .game_state(parent)
+            .expect("moves in the tree have been checked");
+        let line = std::mem::replace(&mut self.game_state, game_state);
+        self.variations.push((node, line));
+        self.node = parent;
+        self.at_variation_start = true;
+        Ok(())
+    }
+    fn close_variation(&mut self) -> Result<(), PgnTreeError> {
+        let (node, game_state) = self
+            .variations
+            .pop()
+            .ok_or(PgnParsingError::UnexpectedChar(')'))?;
+        self.node = node;
+        self.game_state = game_state;
+        self.pre_comment = None;
+        self.at_variation_start = false;
+        Ok(())
+    }
+    fn finish(&mut self, result: PgnResult) -> Result<PgnGameTree, PgnTreeError> {
+        if !self.variations.is_empty() {
+            return Err(PgnParsingError::Unterminated("variation").into());
+        }
+        self.start()?;
+        let builder = std::mem::replace(self, Self::new());
+        Ok(PgnGameTree {
+            tags: builder.tags,
+            tree: builder.tree.expect("the tree was started"),
+            result,
+        })
+    }
+}
+
+// like deserialize, but variations, comments and annotations are kept and every move is checked
+pub fn deserialize_game_trees(pgn: &str) -> Result<Vec<PgnGameTree>, PgnTreeError> {
+    let mut games = vec![];
+    let mut builder = GameTreeBuilder::new();
+    let mut chars = pgn.chars().peekable();
+    let mut at_line_start = true;
+
+    while let Some(&c) = chars.peek() {
+        if at_line_start && c == '%' {
+            read_until(&mut chars, '\n');
+            continue;
+        }
+        at_line_start = c == '\n';
+        chars.next();
+        match c {
+            _ if c.is_whitespace() => {}
+            '[' => {
+                if builder.has_moves() && builder.variations.is_empty() {
+                    games.push(builder.finish(PgnResult::Unknown)?);
+                }
+                let tag =
+                    read_until(&mut chars, ']').ok_or(PgnParsingError::Unterminated("tag"))?;
+                builder.tags.push(parse_tag(&tag)?);
+            }
+            '{' => {
+                let comment =
+                    read_until(&mut chars, '}').ok_or(PgnParsingError::Unterminated("comment"))?;
+                builder.comment(&comment)?;
+            }
+            ';' => {
+                let comment: String = chars.by_ref().take_while(|c| *c != '\n').collect();
+                builder.comment(&comment)?;
+                at_line_start = true;
+            }
+            '(' => builder.open_variation()?,
+            ')' => builder.close_variation()?,
+            ']' | '}' => return Err(PgnParsingError::UnexpectedChar(c).into()),
+            _ => {
+                let mut token = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || TOKEN_TERMINATORS.contains(&c) {
+                        break;
+                    }
+                    token.push(c);
+                    chars.next();
+                }
+                if let Some(result) = PgnResult::try_from_str(&token) {
+                    games.push(builder.finish(result)?);
+                } else if let Some(nag) = token.strip_prefix('$') {
+                    if let Ok(nag) = nag.parse() {
+                        builder.nag(nag)?;
+                    }
+                } else {
+                    let san = strip_move_number(&token);
+                    if !san.is_empty() {
+                        builder.play(san)?;
+                    }
+                }
+            }
+        }
+    }
+    if !builder.is_empty() {
+        games.push(builder.finish(PgnResult::Unknown)?);
+    }
+    Ok(games)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PGN: &str = r#"[Event "Casual Game"]
+[Site "Berlin GER"]
+[Date "1852.??.??"]
+[White "Adolf Anderssen"]
+[Black "Jean Dufresne"]
+[Result "1-0"]
+
+1.e4 e5 2.Nf3 Nc6 3.Bc4 Bc5 4.b4 Bxb4 5.c3 Ba5 6.d4 exd4 7.O-O
+d3 8.Qb3 Qf6 9.e5 Qg6 10.Re1 Nge7 11.Ba3 b5 12.Qxb5 Rb8 13.Qa4
+Bb6 14.Nbd2 Bb7 15.Ne4 Qf5 16.Bxd3 Qh5 17.Nf6+ gxf6 18.exf6
+Rg8 19.Rad1 Qxf3 20.Rxe7+ Nxe7 21.Qxd7+ Kxd7 22.Bf5+ Ke8
+23.Bd7+ Kf8 24.Bxe7# 1-0
+
+[Event "Second"]
+[Result "*"]
+
+% escaped line
+1. d4 {a comment (with parens)} d5 (1... Nf6 2. c4 (2. Nf3)) 2. c4 $1 ; rest of line
+e6 *
+"#;
+
+    #[test]
+    fn test_deserialize_games() {
+        let games = deserialize(PGN).expect("valid pgn");
+        assert_eq!(2, games.len());
+        let first = &games[0];
+        assert_eq!(Some("Adolf Anderssen"), first.tag("White"));
+        assert_eq!(PgnResult::WhiteWins, first.result);
+        assert_eq!(47, first.moves.len());
+        assert_eq!("Bxe7#", first.moves.last().expect("moves"));
+        let second = &games[1];
+        assert_eq!(vec!["d4", "d5", "c4", "e6"], second.moves);
+        assert_eq!(PgnResult::Unknown, second.result);
+    }
+
+    #[test]
+    fn test_replay_game() {
+        let games = deserialize(PGN).expect("valid pgn");
+        let plies = games[0].replay(None).expect("legal game");
+        assert_eq!(47, plies.len());
+        assert_eq!(10, games[0].replay(Some(10)).expect("legal game").len());
+    }
+
+    #[test]
+    fn test_deserialize_errors() {
+        assert_eq!(
+            Err(PgnParsingError::Unterminated("comment")),
+            deserialize("1. e4 { never closed")
+        );
+        assert_eq!(
+            Err(PgnParsingError::InvalidTag("Event".to_string())),
+            deserialize("[Event]")
+        );
+    }
+
+    #[test]
+    fn test_deserialize_game_trees() {
+        let games = deserialize_game_trees(PGN).expect("valid pgn");
+        assert_eq!(2, games.len());
+        assert_eq!(47, games[0].tree.mainline().len());
+        assert_eq!(
+            deserialize(PGN).expect("valid pgn"),
+            vec![games[0].to_pgn_game(), games[1].to_pgn_game()]
+        );
+        let tree = &games[1].tree;
+        let mainline = tree.mainline();
+        let d4 = tree.node(mainline[0]).expect("node");
+        assert_eq!(
+            Some("a comment (with parens)"),
+            d4.annotations.comment.as_deref()
+        );
+        let variation = tree.children(mainline[0])[1];
+        assert_eq!(
+            Some("Nf6"),
+            tree.node(variation).and_then(|node| node.san())
+        );
+        let c4 = tree.next(variation).expect("variation continues");
+        assert_eq!(
+            vec![Some("c4"), Some("Nf3")],
+            tree.children(variation)
+                .iter()
+                .map(|id| tree.node(*id).and_then(|node| node.san()))
+                .collect::<Vec<_>>()
+        );
+        assert!(!tree.is_mainline(c4));
+        let c4 = tree.node(mainline[2]).expect("node");
+        assert_eq!(vec![1], c4.annotations.nags);
+        assert_eq!(Some("rest of line"), c4.annotations.comment.as_deref());
+    }
+
+    #[test]
+    fn test_deserialize_game_tree_annotations() {
+        let pgn = "[FEN \"4k3/8/8/8/8/8/8/4K2R w K - 0 40\"]\n\n\
+            {Endgame} 40. O-O!? {[%eval 1.5] [%clk 0:00:30]} ({Or} 40. Rh8+?? $4) Kd7 *";
+        let games = deserialize_game_trees(pgn).expect("valid pgn");
+        let tree = &games[0].tree;
+        assert_eq!(
+            Some("Endgame"),
+            tree.node(GameTree::ROOT)
+                .and_then(|root| root.annotations.comment.as_deref())
+        );
+        let castle = tree.node(tree.mainline()[0]).expect("node");
+        assert_eq!(vec![5], castle.annotations.nags);
+        assert_eq!(
+            Some(crate::state::annotations::Evaluation::Centipawns(150)),
+            castle.annotations.eval
+        );
+        assert_eq!(
+            Some(std::time::Duration::from_secs(30)),
+            castle.annotations.clock
+        );
+        let check = tree.node(tree.children(GameTree::ROOT)[1]).expect("node");
+        assert_eq!(Some("Rh8+"), check.san());
+        assert_eq!(Some("Or"), check.annotations.pre_comment.as_deref());
+        assert_eq!(vec![4, 4], check.annotations.nags);
+    }
+
+    #[test]
+    fn test_deserialize_game_tree_errors() {
+        assert!(matches!(
+            deserialize_game_trees("(1. d4) 1. e4"),
+            Err(PgnTreeError::Parsing(PgnParsingError::UnexpectedChar('(')))
+        ));
+        assert!(matches!(
+            deserialize_game_trees("1. e4 (1. d4 d5"),
+            Err(PgnTreeError::Parsing(PgnParsingError::Unterminated(
+                "variation"
+            )))
+        ));
+        assert!(matches!(
+            deserialize_game_trees("1. e4 e5 (1... Nf3)"),
+            Err(PgnTreeError::InvalidMove(san, _)) if san == "Nf3"
+        ));
+        assert!(matches!(
+            deserialize_game_trees("[FEN \"nonsense\"] 1. e4"),
+            Err(PgnTreeError::InvalidFen(_))
+        ));
+    }
+}
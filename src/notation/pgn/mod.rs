@@ -0,0 +1,228 @@
+pub mod deserialize;
+pub mod serialize;
+
+pub use deserialize::{deserialize, deserialize_game_trees};
+pub use serialize::{serialize, serialize_game_tree};
+
+use crate::chess_move::chess_move::ChessMove;
+use crate::chess_move::chess_move_handler::default_chess_move_handler;
+use crate::chess_move::invalid_chess_move_error::InvalidChessMoveError;
+use crate::color::Color;
+use crate::eco::eco::classify_opening;
+use crate::notation::fen::deserialize::FenParsingError;
+use crate::notation::pgn::deserialize::PgnParsingError;
+use crate::notation::san::{deserialize_san, serialize_san, SanParsingError};
+use crate::state::game_state::GameState;
+use crate::state::game_status::GameStatus;
+use crate::state::game_tree::{GameTree, GameTreeError};
+use crate::state::history::replay_whole_game;
+use thiserror::Error;
+
+pub const FEN_TAG: &str = "FEN";
+pub const SETUP_TAG: &str = "SetUp";
+pub const ECO_TAG: &str = "ECO";
+pub const OPENING_TAG: &str = "Opening";
+pub const VARIATION_TAG: &str = "Variation";
+
+#[repr(transparent)]
+#[derive(Clone)]
+pub struct Pgn(String);
+
+#[derive(Error, Debug, Clone)]
+pub enum PgnReplayError {
+    #[error("Invalid FEN tag: {0}")]
+    InvalidFen(FenParsingError),
+    #[error("Invalid san at ply {0}: {1}")]
+    InvalidSan(usize, SanParsingError),
+    #[error("Invalid move at ply {0}: {1}")]
+    InvalidMove(usize, InvalidChessMoveError),
+}
+
+#[derive(Error, Debug, Clone)]
+pub enum PgnTreeError {
+    #[error("{0}")]
+    Parsing(#[from] PgnParsingError),
+    #[error("Invalid FEN tag: {0}")]
+    InvalidFen(FenParsingError),
+    #[error("Invalid move {0}: {1}")]
+    InvalidMove(String, GameTreeError),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PgnResult {
+    WhiteWins,
+    BlackWins,
+    Draw,
+    Unknown,
+}
+
+impl PgnResult {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::WhiteWins => "1-0",
+            Self::BlackWins => "0-1",
+            Self::Draw => "1/2-1/2",
+            Self::Unknown => "*",
+        }
+    }
+    pub const fn from_game_status(game_status: GameStatus) -> Self {
+        match game_status {
+            GameStatus::CheckMate(Color::White) => Self::BlackWins,
+            GameStatus::CheckMate(Color::Black) => Self::WhiteWins,
+            GameStatus::TimeForfeit(Color::White) => Self::BlackWins,
+            GameStatus::TimeForfeit(Color::Black) => Self::WhiteWins,
+            GameStatus::Stalemate | GameStatus::Draw => Self::Draw,
+            _ => Self::Unknown,
+        }
+    }
+    pub fn try_from_str(s: &str) -> Option<Self> {
+        Some(match s {
+            "1-0" => Self::WhiteWins,
+            "0-1" => Self::BlackWins,
+            "1/2-1/2" => Self::Draw,
+            "*" => Self::Unknown,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PgnGame {
+    pub tags: Vec<(String, String)>,
+    pub moves: Vec<String>,
+    pub result: PgnResult,
+}
+
+impl PgnGame {
+    pub const fn new() -> Self {
+        Self {
+            tags: Vec::new(),
+            moves: Vec::new(),
+            result: PgnResult::Unknown,
+        }
+    }
+    // falls back to a fen tag for the position itself when the moves leading to it aren't known
+    pub fn from_game_state(game_state: &GameState) -> Self {
+        let mut game = Self::new();
+        match replay_whole_game(game_state) {
+            Some((start, plies)) => {
+                let moves = plies
+                    .iter()
+                    .map(|(game_state, chess_move)| serialize_san(game_state, chess_move))
+                    .collect();
+                game.set_movetext(&start, moves);
+            }
+            None => game.set_movetext(game_state, vec![]),
+        }
+        game.result = PgnResult::from_game_status(game_state.game_status);
+        if let Some(eco) = classify_opening(game_state) {
+            game.set_tag(ECO_TAG, eco.code);
+            game.set_tag(OPENING_TAG, eco.name);
+            if let Some(variation) = eco.variation {
+                game.set_tag(VARIATION_TAG, variation);
+            }
+        }
+        game
+    }
+    // the san moves played from start, which gets the SetUp and FEN tags unless it's the
+    // standard starting position
+    pub fn set_movetext(&mut self, start: &GameState, moves: Vec<String>) {
+        let fen = crate::notation::fen::serialize(start);
+        self.tags
+            .retain(|(name, _)| name != SETUP_TAG && name != FEN_TAG);
+        if fen.get_str() != crate::notation::fen::FEN_STARTING_POS {
+            self.set_tag(SETUP_TAG, "1");
+            self.set_tag(FEN_TAG, fen.get_str());
+        }
+        self.moves = moves;
+    }
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag_name, _)| tag_name == name)
+            .map(|(_, value)| value.as_str())
+    }
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(tag_name, _)| tag_name == name) {
+            Some((_, existing)) => *existing = value.to_string(),
+            None => self.tags.push((name.to_string(), value.to_string())),
+        }
+    }
+    pub fn starting_game_state(&self) -> Result<GameState, FenParsingError> {
+        match self.tag(FEN_TAG) {
+            Some(fen) => crate::notation::fen::deserialize(fen),
+            None => Ok(GameState::new()),
+        }
+    }
+    // every position paired with the move played from it, up to max_ply moves
+    pub fn replay(
+        &self,
+        max_ply: Option<usize>,
+    ) -> Result<Vec<(GameState, ChessMove)>, PgnReplayError> {
+        let mut game_state = self
+            .starting_game_state()
+            .map_err(PgnReplayError::InvalidFen)?;
+        let max_ply = max_ply.unwrap_or(self.moves.len());
+        let mut plies = vec![];
+        for (ply, san) in self.moves.iter().take(max_ply).enumerate() {
+            let chess_move = deserialize_san(&game_state, san)
+                .map_err(|err| PgnReplayError::InvalidSan(ply, err))?;
+            let game_state_before = game_state.clone();
+            default_chess_move_handler(&mut game_state, &chess_move, None)
+                .map_err(|err| PgnReplayError::InvalidMove(ply, err))?;
+            plies.push((game_state_before, chess_move));
+        }
+        Ok(plies)
+    }
+    // the position after the last move
+    pub fn end_game_state(&self) -> Result<GameState, PgnReplayError> {
+        match self.replay(None)?.pop() {
+            Some((mut game_state, chess_move)) => {
+                default_chess_move_handler(&mut game_state, &chess_move, None)
+                    .map_err(|err| PgnReplayError::InvalidMove(self.moves.len() - 1, err))?;
+                Ok(game_state)
+            }
+            None => self
+                .starting_game_state()
+                .map_err(PgnReplayError::InvalidFen),
+        }
+    }
+}
+
+impl Default for PgnGame {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// a game with its variations, comments and annotations,
+// the tree starts from the position in the FEN tag if there is one
+#[derive(Clone, Debug)]
+pub struct PgnGameTree {
+    pub tags: Vec<(String, String)>,
+    pub tree: GameTree,
+    pub result: PgnResult,
+}
+
+impl PgnGameTree {
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag_name, _)| tag_name == name)
+            .map(|(_, value)| value.as_str())
+    }
+    // the mainline alone
+    pub fn to_pgn_game(&self) -> PgnGame {
+        PgnGame {
+            tags: self.tags.clone(),
+            moves: self
+                .tree
+                .mainline()
+                .into_iter()
+                .filter_map(|id| self.tree.node(id).and_then(|node| node.san()))
+                .map(String::from)
+                .collect(),
+            result: self.result,
+        }
+    }
+}
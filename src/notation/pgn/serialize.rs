@@ -0,0 +1,252 @@
+use crate::color::Color;
+use crate::notation::pgn::{PgnGame, PgnGameTree};
+use crate::state::game_tree::{GameTree, NodeId};
+
+const MAX_LINE_LENGTH: usize = 80;
+
+fn escape_tag_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn push_tags(pgn: &mut String, tags: &[(String, String)]) {
+    for (name, value) in tags.iter() {
+        pgn.push_str(&format!("[{name} \"{}\"]\n", escape_tag_value(value)));
+    }
+    if !tags.is_empty() {
+        pgn.push('\n');
+    }
+}
+
+fn push_movetext(pgn: &mut String, tokens: &[String]) {
+    let mut line_length = 0;
+    for token in tokens {
+        if line_length > 0 && line_length + 1 + token.len() > MAX_LINE_LENGTH {
+            pgn.push('\n');
+            line_length = 0;
+        } else if line_length > 0 {
+            pgn.push(' ');
+            line_length += 1;
+        }
+        line_length += token.len();
+        pgn.push_str(token);
+    }
+    pgn.push('\n');
+}
+
+pub fn serialize(game: &PgnGame) -> String {
+    let mut pgn = String::new();
+    push_tags(&mut pgn, &game.tags);
+
+    let (mut full_move_num, mut active_color) = match game.starting_game_state() {
+        Ok(game_state) => (game_state.move_counter.full_move, game_state.active_color),
+        Err(_) => (1, Color::White),
+    };
+    let mut tokens = vec![];
+    for (ix, san) in game.moves.iter().enumerate() {
+        match active_color {
+            Color::White => tokens.push(format!("{full_move_num}. {san}")),
+            Color::Black if ix == 0 => tokens.push(format!("{full_move_num}... {san}")),
+            Color::Black => tokens.push(san.to_string()),
+        }
+        if active_color == Color::Black {
+            full_move_num += 1;
+        }
+        active_color = active_color.as_inverse();
+    }
+    tokens.push(game.result.as_str().to_string());
+    push_movetext(&mut pgn, &tokens);
+    pgn
+}
+
+fn has_comment(tree: &GameTree, id: NodeId) -> bool {
+    tree.node(id)
+        .is_some_and(|node| node.annotations.pgn_comment().is_some())
+}
+
+// black's moves need a number at the start of a line and after a comment or variation
+fn push_move_tokens(tree: &GameTree, id: NodeId, tokens: &mut Vec<String>, show_number: bool) {
+    let Some(node) = tree.node(id) else {
+        return;
+    };
+    let start = tree.start();
+    let ply = node.ply() - 1 + (start.active_color == Color::Black) as usize;
+    let full_move_num = start.move_counter.full_move as usize + ply / 2;
+    let annotations = &node.annotations;
+    if let Some(pre_comment) = &annotations.pre_comment {
+        tokens.push(format!("{{{pre_comment}}}"));
+    }
+    let san = node.san().unwrap_or_default();
+    tokens.push(if ply.is_multiple_of(2) {
+        format!("{full_move_num}. {san}")
+    } else if show_number || annotations.pre_comment.is_some() {
+        format!("{full_move_num}... {san}")
+    } else {
+        san.to_string()
+    });
+    tokens.extend(annotations.nags.iter().map(|nag| format!("${nag}")));
+    if let Some(comment) = annotations.pgn_comment() {
+        tokens.push(format!("{{{comment}}}"));
+    }
+}
+
+// the line after from, with the variations of each move right after it
+fn push_line_tokens(tree: &GameTree, from: NodeId, tokens: &mut Vec<String>, show_number: bool) {
+    let mut node = from;
+    let mut show_number = show_number;
+    while let Some(&main) = tree.children(node).first() {
+        push_move_tokens(tree, main, tokens, show_number);
+        let variations = &tree.children(node)[1..];
+        for variation in variations {
+            // the parentheses stick to the first and last tokens inside them
+            let first = tokens.len();
+            push_move_tokens(tree, *variation, tokens, true);
+            push_line_tokens(tree, *variation, tokens, has_comment(tree, *variation));
+            tokens[first].insert(0, '(');
+            if let Some(last) = tokens.last_mut() {
+                last.push(')');
+            }
+        }
+        show_number = !variations.is_empty() || has_comment(tree, main);
+        node = main;
+    }
+}
+
+pub fn serialize_game_tree(game: &PgnGameTree) -> String {
+    let mut pgn = String::new();
+    push_tags(&mut pgn, &game.tags);
+    let mut tokens = vec![];
+    if let Some(comment) = game
+        .tree
+        .node(GameTree::ROOT)
+        .and_then(|root| root.annotations.pgn_comment())
+    {
+        tokens.push(format!("{{{comment}}}"));
+    }
+    push_line_tokens(&game.tree, GameTree::ROOT, &mut tokens, true);
+    tokens.push(game.result.as_str().to_string());
+    push_movetext(&mut pgn, &tokens);
+    pgn
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess_move::chess_move_handler::{
+        default_chess_move_handler, try_handle_chess_move,
+    };
+    use crate::notation::pgn::{deserialize, PgnResult, ECO_TAG, FEN_TAG, OPENING_TAG, SETUP_TAG};
+    use crate::notation::san::deserialize_san;
+    use rstest::rstest;
+
+    #[test]
+    fn test_serialize_round_trip() {
+        let mut game = PgnGame::new();
+        game.set_tag("Event", "Club \"Open\"");
+        game.moves = ["e4", "e5", "Nf3", "Nc6", "Bb5"].map(String::from).to_vec();
+        game.result = PgnResult::Draw;
+        let pgn = serialize(&game);
+        assert_eq!(
+            "[Event \"Club \\\"Open\\\"\"]\n\n1. e4 e5 2. Nf3 Nc6 3. Bb5 1/2-1/2\n",
+            pgn
+        );
+        assert_eq!(vec![game], deserialize(&pgn).expect("valid pgn"));
+    }
+
+    #[test]
+    fn test_serialize_from_black_to_move() {
+        let mut game = PgnGame::new();
+        game.set_tag(
+            FEN_TAG,
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+        );
+        game.moves = ["e5", "Nf3"].map(String::from).to_vec();
+        assert!(serialize(&game).ends_with("\n\n1... e5 2. Nf3 *\n"));
+    }
+
+    #[test]
+    fn test_serialize_from_game_state() {
+        let games = deserialize("1. e4 c5 2. Nf3 d6 3. d4 cxd4 4. Nxd4 Nf6 5. Nc3 a6 6. Be3")
+            .expect("valid pgn");
+        let plies = games[0].replay(None).expect("legal game");
+        let (last_game_state, last_move) = plies.last().expect("moves");
+        let game_state =
+            try_handle_chess_move(last_game_state, last_move, None).expect("legal move");
+        let game = PgnGame::from_game_state(&game_state);
+        assert_eq!(Some("B90"), game.tag(ECO_TAG));
+        assert_eq!(Some("Sicilian Defense"), game.tag(OPENING_TAG));
+        assert_eq!(None, game.tag(FEN_TAG));
+        assert_eq!(games[0].moves, game.moves);
+        let pgn = serialize(&game);
+        assert!(pgn.starts_with("[ECO \"B90\"]\n[Opening \"Sicilian Defense\"]\n[Variation \"Najdorf Variation\"]\n\n1. e4 c5"));
+    }
+
+    #[rstest]
+    #[case("4k3/8/8/8/8/8/8/4K2R w K - 0 40", &[])]
+    #[case("4k3/8/8/8/8/8/8/R3K3 w - - 0 1", &[])]
+    #[case("4k3/8/8/8/8/8/8/R3K3 w - - 0 1", &["Ra2"])]
+    #[case("4k3/8/8/8/8/8/8/R3K3 b - - 0 1", &["Kd7", "Ra7+"])]
+    // the moves are also legal from the standard starting position
+    #[case("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1", &["e4", "e5"])]
+    fn test_serialize_from_fen_game_state(#[case] fen: &str, #[case] moves: &[&str]) {
+        let mut game_state = crate::notation::fen::deserialize(fen).expect("bad fen string!");
+        for san in moves {
+            let chess_move = deserialize_san(&game_state, san).expect("bad san!");
+            default_chess_move_handler(&mut game_state, &chess_move, None).expect("bad move!");
+        }
+        let game = PgnGame::from_game_state(&game_state);
+        assert_eq!(Some("1"), game.tag(SETUP_TAG));
+        assert_eq!(Some(fen), game.tag(FEN_TAG));
+        assert_eq!(None, game.tag(ECO_TAG));
+        assert_eq!(moves, game.moves);
+        let pgn = serialize(&game);
+        assert!(pgn.starts_with(&format!("[SetUp \"1\"]\n[FEN \"{fen}\"]\n\n")));
+        let round_trip = deserialize(&pgn).expect("valid pgn");
+        assert_eq!(
+            crate::notation::fen::serialize(&game_state).get_str(),
+            crate::notation::fen::serialize(&round_trip[0].end_game_state().expect("legal game"))
+                .get_str()
+        );
+    }
+
+    #[test]
+    fn test_serialize_game_tree() {
+        let pgn = "[Event \"Annotated\"]\n\n{Intro} 1. e4 {[%eval 0.3] [%clk 0:05:00]} e5 $1 \
+            (1... c5! {Sicilian} 2. Nf3 (2. c3) d6) (1... e6) 2. Nf3 Nc6?! 3. Bb5 *";
+        let games = crate::notation::pgn::deserialize_game_trees(pgn).expect("valid pgn");
+        let serialized = serialize_game_tree(&games[0]);
+        assert_eq!(
+            "[Event \"Annotated\"]\n\n\
+            {Intro} 1. e4 {[%eval 0.30] [%clk 0:05:00]} 1... e5 $1 (1... c5 $1 {Sicilian}\n\
+            2. Nf3 (2. c3) 2... d6) (1... e6) 2. Nf3 Nc6 $6 3. Bb5 *\n",
+            serialized
+        );
+        let round_trip =
+            crate::notation::pgn::deserialize_game_trees(&serialized).expect("valid pgn");
+        assert_eq!(serialized, serialize_game_tree(&round_trip[0]));
+        assert_eq!(
+            "[Event \"Annotated\"]\n\n1. e4 e5 2. Nf3 Nc6 3. Bb5 *\n",
+            serialize(&round_trip[0].to_pgn_game())
+        );
+    }
+
+    #[test]
+    fn test_serialize_game_tree_from_black_to_move() {
+        let mut game = PgnGame::new();
+        game.set_tag(
+            FEN_TAG,
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+        );
+        let start = game.starting_game_state().expect("valid fen");
+        let mut tree = GameTree::new(start);
+        let e5 = tree.add_san(GameTree::ROOT, "e5").expect("legal move");
+        tree.add_san(e5, "Nf3").expect("legal move");
+        let c5 = tree.add_san(GameTree::ROOT, "c5").expect("legal move");
+        tree.add_san(c5, "Nc3").expect("legal move");
+        let game = PgnGameTree {
+            tags: game.tags,
+            tree,
+            result: PgnResult::Unknown,
+        };
+        assert!(serialize_game_tree(&game).ends_with("\n\n1... e5 (1... c5 2. Nc3) 2. Nf3 *\n"));
+    }
+}
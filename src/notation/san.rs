@@ -0,0 +1,181 @@
+use crate::board::board_position::BoardPosition;
+use crate::chess_move::chess_move::{ChessMove, ChessMoveType};
+use crate::chess_move::chess_move_handler::try_handle_chess_move;
+use crate::chess_move::chess_move_search::unchecked_chess_move_search;
+use crate::direction::castle_side::CastleSide;
+use crate::piece::piece::Piece;
+use crate::state::game_state::GameState;
+use crate::state::game_status::GameStatus;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum SanParsingError {
+    #[error("Invalid san: {0}")]
+    InvalidSan(String),
+    #[error("Illegal move for position: {0}")]
+    IllegalMove(String),
+    #[error("Ambiguous san: {0}")]
+    AmbiguousSan(String),
+}
+
+fn square_str(board_position: &BoardPosition) -> String {
+    board_position.to_string().to_ascii_lowercase()
+}
+
+pub fn legal_moves_with_status(game_state: &GameState) -> Vec<(ChessMove, GameStatus)> {
+    unchecked_chess_move_search(game_state, None)
+        .into_iter()
+        .filter_map(|chess_move| {
+            let new_game_state = try_handle_chess_move(game_state, &chess_move, None).ok()?;
+            Some((chess_move, new_game_state.game_status))
+        })
+        .collect()
+}
+
+fn san_without_suffix(chess_move: &ChessMove, legal_moves: &[(ChessMove, GameStatus)]) -> String {
+    match chess_move.move_type {
+        ChessMoveType::Castle(CastleSide::King) => return "O-O".to_string(),
+        ChessMoveType::Castle(CastleSide::Queen) => return "O-O-O".to_string(),
+        _ => {}
+    }
+    let piece = chess_move.piece.as_piece();
+    let is_capture = chess_move.captured_piece.is_some()
+        || matches!(chess_move.move_type, ChessMoveType::EnPassant(_));
+    let mut san = String::new();
+    if piece == Piece::Pawn {
+        if is_capture {
+            san.push(chess_move.from.file().as_char().to_ascii_lowercase());
+        }
+    } else {
+        san.push(piece.as_char());
+        let others = legal_moves
+            .iter()
+            .map(|(other, _)| other)
+            .filter(|other| {
+                other.piece == chess_move.piece
+                    && other.to == chess_move.to
+                    && other.from != chess_move.from
+            })
+            .collect::<Vec<_>>();
+        if !others.is_empty() {
+            let file_is_unique = others
+                .iter()
+                .all(|other| other.from.file() != chess_move.from.file());
+            let rank_is_unique = others
+                .iter()
+                .all(|other| other.from.rank() != chess_move.from.rank());
+            if file_is_unique {
+                san.push(chess_move.from.file().as_char().to_ascii_lowercase());
+            } else if rank_is_unique {
+                san.push(chess_move.from.rank().as_char());
+            } else {
+                san.push_str(&square_str(&chess_move.from));
+            }
+        }
+    }
+    if is_capture {
+        san.push('x');
+    }
+    san.push_str(&square_str(&chess_move.to));
+    if let ChessMoveType::Promotion(promotion_piece) = chess_move.move_type {
+        san.push('=');
+        san.push(promotion_piece.as_piece().as_char());
+    }
+    san
+}
+
+const fn status_suffix(game_status: GameStatus) -> &'static str {
+    match game_status {
+        GameStatus::CheckMate(_) => "#",
+        GameStatus::Check(_) => "+",
+        _ => "",
+    }
+}
+
+pub fn serialize_san(game_state: &GameState, chess_move: &ChessMove) -> String {
+    let legal_moves = legal_moves_with_status(game_state);
+    let game_status = legal_moves
+        .iter()
+        .find(|(legal_move, _)| legal_move == chess_move)
+        .map(|(_, game_status)| *game_status)
+        .unwrap_or(GameStatus::InProgress);
+    san_without_suffix(chess_move, &legal_moves) + status_suffix(game_status)
+}
+
+// accepts the common variations seen in the wild: 0-0, missing '=', annotations and check marks
+fn normalize_san(san: &str) -> String {
+    san.trim()
+        .trim_end_matches(['+', '#', '!', '?'])
+        .trim_end_matches("e.p.")
+        .trim()
+        .replace('0', "O")
+        .replace('=', "")
+}
+
+pub fn deserialize_san(game_state: &GameState, san: &str) -> Result<ChessMove, SanParsingError> {
+    let normalized = normalize_san(san);
+    if normalized.len() < 2 {
+        return Err(SanParsingError::InvalidSan(san.to_string()));
+    }
+    let legal_moves = legal_moves_with_status(game_state);
+    let mut matches = legal_moves.iter().filter(|(chess_move, _)| {
+        san_without_suffix(chess_move, &legal_moves).replace('=', "") == normalized
+    });
+    let Some((chess_move, _)) = matches.next() else {
+        return Err(SanParsingError::IllegalMove(san.to_string()));
+    };
+    if matches.any(|(other, _)| other != chess_move) {
+        return Err(SanParsingError::AmbiguousSan(san.to_string()));
+    }
+    Ok(chess_move.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::fen::{deserialize, FEN_STARTING_POS};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(FEN_STARTING_POS, "e4")]
+    #[case(FEN_STARTING_POS, "Nf3")]
+    #[case("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1", "O-O")]
+    #[case("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1", "O-O-O")]
+    #[case(
+        "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+        "exf6"
+    )]
+    #[case("1k6/8/8/8/8/8/4K3/R6R w - - 0 1", "Rad1")]
+    #[case("1k6/8/8/8/8/8/8/R3K2R w - - 0 1", "Rh7")]
+    #[case("2k5/8/8/8/8/R7/8/R3K3 w - - 0 1", "R1a2")]
+    #[case("k7/6P1/8/8/8/8/8/4K3 w - - 0 1", "g8=Q+")]
+    #[case("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1", "Ra8#")]
+    fn test_san_round_trip(#[case] fen: &'static str, #[case] san: &'static str) {
+        let game_state = deserialize(fen).expect("bad fen string!");
+        let chess_move = deserialize_san(&game_state, san).expect("valid san");
+        assert_eq!(san, serialize_san(&game_state, &chess_move));
+    }
+
+    #[rstest]
+    #[case("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1", "0-0", "O-O")]
+    #[case("k7/6P1/8/8/8/8/8/4K3 w - - 0 1", "g8Q", "g8=Q+")]
+    #[case(FEN_STARTING_POS, "e4!?", "e4")]
+    fn test_san_lenient_input(
+        #[case] fen: &'static str,
+        #[case] input: &'static str,
+        #[case] expected: &'static str,
+    ) {
+        let game_state = deserialize(fen).expect("bad fen string!");
+        let chess_move = deserialize_san(&game_state, input).expect("valid san");
+        assert_eq!(expected, serialize_san(&game_state, &chess_move));
+    }
+
+    #[rstest]
+    #[case(FEN_STARTING_POS, "e5")]
+    #[case(FEN_STARTING_POS, "Ke2")]
+    #[case(FEN_STARTING_POS, "x")]
+    fn test_san_invalid(#[case] fen: &'static str, #[case] san: &'static str) {
+        let game_state = deserialize(fen).expect("bad fen string!");
+        assert!(deserialize_san(&game_state, san).is_err());
+    }
+}
@@ -18,6 +18,7 @@ pub enum ChessPieceParseError {
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ChessPiece {
     WhiteKing,
     WhiteQueen,
@@ -42,6 +43,22 @@ impl ChessPiece {
             Color::Black => piece.as_char().to_ascii_lowercase(),
         }
     }
+    pub const fn as_unicode_char(&self) -> char {
+        match self {
+            Self::WhiteKing => '♔',
+            Self::WhiteQueen => '♕',
+            Self::WhiteRook => '♖',
+            Self::WhiteBishop => '♗',
+            Self::WhiteKnight => '♘',
+            Self::WhitePawn => '♙',
+            Self::BlackKing => '♚',
+            Self::BlackQueen => '♛',
+            Self::BlackRook => '♜',
+            Self::BlackBishop => '♝',
+            Self::BlackKnight => '♞',
+            Self::BlackPawn => '♟',
+        }
+    }
     pub const fn as_color(&self) -> Color {
         match self {
             Self::WhiteKing => Color::White,
@@ -1,6 +1,7 @@
 use crate::piece::piece::Piece;
 
 #[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PromotionPiece {
     Queen,
     Rook,
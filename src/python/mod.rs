@@ -0,0 +1,5 @@
+pub mod py_board;
+pub mod py_chess_move;
+pub mod py_errors;
+pub mod py_game_state;
+pub mod py_module;
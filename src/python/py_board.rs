@@ -0,0 +1,74 @@
+use crate::board::board::Board;
+use crate::board::board_diagram::render_board;
+use crate::board::board_position::BoardPosition;
+use crate::notation::fen::serialize;
+use crate::python::py_errors::ChessError;
+use crate::state::game_state::GameState;
+use pyo3::prelude::*;
+use std::collections::BTreeMap;
+
+#[pyclass(name = "Board", module = "rust_chess")]
+#[derive(Clone)]
+pub struct PyBoard {
+    pub board: Board,
+}
+
+fn parse_square(square: &str) -> PyResult<BoardPosition> {
+    BoardPosition::from_str(square).map_err(|err| ChessError::new_err(err.to_string()))
+}
+
+#[pymethods]
+impl PyBoard {
+    #[new]
+    pub fn new() -> Self {
+        Self {
+            board: Board::new(),
+        }
+    }
+    // fen letter of the piece, None for an empty square
+    pub fn piece_at(&self, square: &str) -> PyResult<Option<char>> {
+        let pos = parse_square(square)?;
+        Ok(self.board.get(pos).map(|chess_piece| chess_piece.as_char()))
+    }
+    pub fn pieces(&self) -> BTreeMap<String, char> {
+        self.board
+            .as_iter()
+            .filter_map(|(pos, maybe_piece)| {
+                maybe_piece.map(|chess_piece| {
+                    (pos.to_string().to_ascii_lowercase(), chess_piece.as_char())
+                })
+            })
+            .collect()
+    }
+    // the piece placement field of a fen
+    pub fn fen(&self) -> String {
+        let game_state = GameState {
+            board: self.board.clone(),
+            ..GameState::empty()
+        };
+        serialize(&game_state)
+            .get_str()
+            .split(' ')
+            .next()
+            .unwrap_or_default()
+            .to_string()
+    }
+    pub fn __getitem__(&self, square: &str) -> PyResult<Option<char>> {
+        self.piece_at(square)
+    }
+    pub fn __str__(&self) -> String {
+        render_board(&self.board, None)
+    }
+    pub fn __repr__(&self) -> String {
+        format!("Board('{}')", self.fen())
+    }
+    pub fn __eq__(&self, other: &Self) -> bool {
+        self.fen() == other.fen()
+    }
+}
+
+impl Default for PyBoard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
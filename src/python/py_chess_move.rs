@@ -0,0 +1,66 @@
+use crate::chess_move::chess_move::{ChessMove, ChessMoveType};
+use crate::notation::coordinate::serialize_coordinate;
+use crate::notation::san::serialize_san;
+use crate::state::game_state::GameState;
+use pyo3::prelude::*;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+#[pyclass(name = "ChessMove", module = "rust_chess", frozen)]
+#[derive(Clone)]
+pub struct PyChessMove {
+    pub chess_move: ChessMove,
+    // san only makes sense for the position the move was generated from
+    pub san: String,
+}
+
+impl PyChessMove {
+    pub fn from_game_state(game_state: &GameState, chess_move: ChessMove) -> Self {
+        Self {
+            san: serialize_san(game_state, &chess_move),
+            chess_move,
+        }
+    }
+}
+
+#[pymethods]
+impl PyChessMove {
+    pub fn uci(&self) -> String {
+        serialize_coordinate(&self.chess_move)
+    }
+    #[getter]
+    pub fn san(&self) -> String {
+        self.san.clone()
+    }
+    #[getter]
+    pub fn from_square(&self) -> String {
+        self.chess_move.from.to_string().to_ascii_lowercase()
+    }
+    #[getter]
+    pub fn to_square(&self) -> String {
+        self.chess_move.to.to_string().to_ascii_lowercase()
+    }
+    #[getter]
+    pub fn promotion(&self) -> Option<char> {
+        match self.chess_move.move_type {
+            ChessMoveType::Promotion(promotion_piece) => {
+                Some(promotion_piece.as_piece().as_char().to_ascii_lowercase())
+            }
+            _ => None,
+        }
+    }
+    pub fn __str__(&self) -> String {
+        self.uci()
+    }
+    pub fn __repr__(&self) -> String {
+        format!("<ChessMove {}>", self.uci())
+    }
+    pub fn __eq__(&self, other: &Self) -> bool {
+        self.chess_move == other.chess_move
+    }
+    pub fn __hash__(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.uci().hash(&mut hasher);
+        hasher.finish()
+    }
+}
@@ -0,0 +1,50 @@
+use crate::chess_move::invalid_chess_move_error::InvalidChessMoveError;
+use crate::notation::coordinate::CoordinateParsingError;
+use crate::notation::fen::deserialize::FenParsingError;
+use crate::notation::pgn::deserialize::PgnParsingError;
+use crate::notation::pgn::PgnReplayError;
+use crate::notation::san::SanParsingError;
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::PyErr;
+
+create_exception!(rust_chess, ChessError, PyException);
+create_exception!(rust_chess, FenError, ChessError);
+create_exception!(rust_chess, IllegalMoveError, ChessError);
+create_exception!(rust_chess, PgnError, ChessError);
+
+impl From<FenParsingError> for PyErr {
+    fn from(err: FenParsingError) -> Self {
+        FenError::new_err(err.to_string())
+    }
+}
+
+impl From<InvalidChessMoveError> for PyErr {
+    fn from(err: InvalidChessMoveError) -> Self {
+        IllegalMoveError::new_err(err.to_string())
+    }
+}
+
+impl From<SanParsingError> for PyErr {
+    fn from(err: SanParsingError) -> Self {
+        IllegalMoveError::new_err(err.to_string())
+    }
+}
+
+impl From<CoordinateParsingError> for PyErr {
+    fn from(err: CoordinateParsingError) -> Self {
+        IllegalMoveError::new_err(err.to_string())
+    }
+}
+
+impl From<PgnParsingError> for PyErr {
+    fn from(err: PgnParsingError) -> Self {
+        PgnError::new_err(err.to_string())
+    }
+}
+
+impl From<PgnReplayError> for PyErr {
+    fn from(err: PgnReplayError) -> Self {
+        PgnError::new_err(err.to_string())
+    }
+}
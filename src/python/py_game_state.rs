@@ -0,0 +1,178 @@
+use crate::chess_move::chess_move_handler::default_chess_move_handler;
+use crate::chess_move::invalid_chess_move_error::InvalidChessMoveError;
+use crate::notation::coordinate::{deserialize_coordinate, CoordinateParsingError};
+use crate::notation::fen::{deserialize, serialize};
+use crate::notation::pgn::{PgnGame, PgnResult};
+use crate::notation::san::{deserialize_san, legal_moves_with_status};
+use crate::python::py_board::PyBoard;
+use crate::python::py_chess_move::PyChessMove;
+use crate::python::py_errors::PgnError;
+use crate::state::game_state::GameState;
+use crate::state::game_status::GameStatus;
+use pyo3::prelude::*;
+use std::vec::IntoIter;
+
+#[derive(FromPyObject)]
+pub enum MoveArg {
+    Move(PyChessMove),
+    // san or uci
+    Notation(String),
+}
+
+#[pyclass(name = "GameState", module = "rust_chess")]
+#[derive(Clone)]
+pub struct PyGameState {
+    pub game_state: GameState,
+}
+
+#[pyclass(module = "rust_chess")]
+pub struct LegalMoveIterator {
+    moves: IntoIter<PyChessMove>,
+}
+
+#[pymethods]
+impl LegalMoveIterator {
+    pub fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+    pub fn __next__(mut slf: PyRefMut<'_, Self>) -> Option<PyChessMove> {
+        slf.moves.next()
+    }
+}
+
+impl PyGameState {
+    fn legal_moves_vec(&self) -> Vec<PyChessMove> {
+        legal_moves_with_status(&self.game_state)
+            .into_iter()
+            .map(|(chess_move, _)| PyChessMove::from_game_state(&self.game_state, chess_move))
+            .collect()
+    }
+}
+
+#[pymethods]
+impl PyGameState {
+    #[new]
+    #[pyo3(signature = (fen=None))]
+    pub fn new(fen: Option<&str>) -> PyResult<Self> {
+        let game_state = match fen {
+            Some(fen) => deserialize(fen)?,
+            None => GameState::new(),
+        };
+        Ok(Self { game_state })
+    }
+    // the position at the end of the first game in the pgn
+    #[staticmethod]
+    pub fn from_pgn(pgn: &str) -> PyResult<Self> {
+        let game = crate::notation::pgn::deserialize(pgn)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| PgnError::new_err("No games in pgn"))?;
+        Ok(Self {
+            game_state: game.end_game_state()?,
+        })
+    }
+    pub fn fen(&self) -> String {
+        serialize(&self.game_state).get_str().to_string()
+    }
+    pub fn pgn(&self) -> String {
+        crate::notation::pgn::serialize(&PgnGame::from_game_state(&self.game_state))
+    }
+    #[getter]
+    pub fn legal_moves(&self) -> Vec<PyChessMove> {
+        self.legal_moves_vec()
+    }
+    #[getter]
+    pub fn board(&self) -> PyBoard {
+        PyBoard {
+            board: self.game_state.board.clone(),
+        }
+    }
+    #[getter]
+    pub fn active_color(&self) -> String {
+        format!("{:?}", self.game_state.active_color).to_lowercase()
+    }
+    // one of new, in_progress, check, checkmate, stalemate, draw or time_forfeit
+    #[getter]
+    pub fn status(&self) -> &'static str {
+        match self.game_state.game_status {
+            GameStatus::New => "new",
+            GameStatus::InProgress => "in_progress",
+            GameStatus::Check(_) => "check",
+            GameStatus::CheckMate(_) => "checkmate",
+            GameStatus::Stalemate => "stalemate",
+            GameStatus::TimeForfeit(_) => "time_forfeit",
+            GameStatus::Draw => "draw",
+        }
+    }
+    // 1-0, 0-1, 1/2-1/2 or * like a pgn result tag
+    #[getter]
+    pub fn result(&self) -> &'static str {
+        PgnResult::from_game_status(self.game_state.game_status).as_str()
+    }
+    pub fn is_check(&self) -> bool {
+        self.game_state.game_status.is_check_or_mate()
+    }
+    pub fn is_game_over(&self) -> bool {
+        self.game_state.game_status.is_game_over()
+    }
+    // plays a ChessMove, san or uci and returns the move that was played
+    pub fn push(&mut self, chess_move: MoveArg) -> PyResult<PyChessMove> {
+        let chess_move = match chess_move {
+            // the move handler trusts the move it's given, and this one may be from another position
+            MoveArg::Move(py_chess_move) => {
+                let chess_move = py_chess_move.chess_move;
+                let is_legal = legal_moves_with_status(&self.game_state)
+                    .iter()
+                    .any(|(legal, _)| *legal == chess_move);
+                if !is_legal {
+                    return Err(
+                        InvalidChessMoveError::InvalidMove(chess_move.from, chess_move.to).into(),
+                    );
+                }
+                chess_move
+            }
+            // anything that doesn't look like uci gets the san error
+            MoveArg::Notation(s) => match deserialize_coordinate(&self.game_state, &s) {
+                Ok(chess_move) => chess_move,
+                Err(CoordinateParsingError::InvalidCoordinate(_)) => {
+                    deserialize_san(&self.game_state, &s)?
+                }
+                Err(err) => return Err(err.into()),
+            },
+        };
+        let played = PyChessMove::from_game_state(&self.game_state, chess_move.clone());
+        default_chess_move_handler(&mut self.game_state, &chess_move, None)?;
+        Ok(played)
+    }
+    pub fn copy(&self) -> Self {
+        self.clone()
+    }
+    pub fn __copy__(&self) -> Self {
+        self.clone()
+    }
+    pub fn __iter__(&self) -> LegalMoveIterator {
+        LegalMoveIterator {
+            moves: self.legal_moves_vec().into_iter(),
+        }
+    }
+    pub fn __len__(&self) -> usize {
+        legal_moves_with_status(&self.game_state).len()
+    }
+    pub fn __contains__(&self, chess_move: MoveArg) -> bool {
+        let legal_moves = self.legal_moves_vec();
+        match chess_move {
+            MoveArg::Move(py_chess_move) => legal_moves
+                .iter()
+                .any(|legal| legal.chess_move == py_chess_move.chess_move),
+            MoveArg::Notation(s) => legal_moves
+                .iter()
+                .any(|legal| legal.san == s || legal.uci() == s),
+        }
+    }
+    pub fn __str__(&self) -> String {
+        self.fen()
+    }
+    pub fn __repr__(&self) -> String {
+        format!("GameState('{}')", self.fen())
+    }
+}
@@ -0,0 +1,120 @@
+use crate::notation::fen::FEN_STARTING_POS;
+use crate::python::py_board::PyBoard;
+use crate::python::py_chess_move::PyChessMove;
+use crate::python::py_errors::{ChessError, FenError, IllegalMoveError, PgnError};
+use crate::python::py_game_state::{LegalMoveIterator, PyGameState};
+use crate::state::evaluate_game_state;
+use pyo3::prelude::*;
+
+pub const DEFAULT_SEARCH_DEPTH: u8 = 3;
+
+#[pyfunction]
+#[pyo3(signature = (game_state, depth=DEFAULT_SEARCH_DEPTH))]
+pub fn find_best_move(
+    py: Python<'_>,
+    game_state: &PyGameState,
+    depth: u8,
+) -> PyResult<PyChessMove> {
+    let game_state = &game_state.game_state;
+    // searching doesn't touch python objects, so let other python threads run meanwhile
+    let chess_move = py
+        .allow_threads(|| evaluate_game_state::find_best_move(game_state, depth))
+        .map_err(ChessError::new_err)?;
+    Ok(PyChessMove::from_game_state(game_state, chess_move))
+}
+
+#[pymodule]
+pub fn rust_chess(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add_class::<PyGameState>()?;
+    m.add_class::<PyChessMove>()?;
+    m.add_class::<PyBoard>()?;
+    m.add_class::<LegalMoveIterator>()?;
+    m.add_function(wrap_pyfunction!(find_best_move, m)?)?;
+    m.add("STARTING_FEN", FEN_STARTING_POS)?;
+    m.add("ChessError", py.get_type::<ChessError>())?;
+    m.add("FenError", py.get_type::<FenError>())?;
+    m.add("IllegalMoveError", py.get_type::<IllegalMoveError>())?;
+    m.add("PgnError", py.get_type::<PgnError>())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::ffi::c_str;
+
+    fn run(script: &std::ffi::CStr) {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "rust_chess").unwrap();
+            rust_chess(&module).unwrap();
+            let locals = pyo3::types::PyDict::new(py);
+            locals.set_item("rust_chess", module).unwrap();
+            py.run(script, None, Some(&locals))
+                .inspect_err(|err| err.print(py))
+                .expect("python script failed");
+        });
+    }
+
+    #[test]
+    fn test_game_state() {
+        run(c_str!(
+            r#"
+game = rust_chess.GameState()
+assert str(game) == rust_chess.STARTING_FEN
+assert len(game) == 20
+assert "e4" in game and "e2e4" in game and "e5" not in game
+ucis = [m.uci() for m in game]
+assert "g1f3" in ucis and len(ucis) == 20
+played = game.push("e4")
+assert (played.san, played.from_square, played.to_square) == ("e4", "e2", "e4")
+game.push("e7e5")
+game.push(next(m for m in game.legal_moves if m.san == "Nf3"))
+assert game.active_color == "black"
+assert game.status == "in_progress"
+assert game.board["f3"] == "N" and game.board.piece_at("e2") is None
+assert "1. e4 e5 2. Nf3" in game.pgn()
+copy = game.copy()
+copy.push("Nc6")
+assert copy.fen() != game.fen()
+"#
+        ));
+    }
+
+    #[test]
+    fn test_errors_and_search() {
+        run(c_str!(
+            r#"
+try:
+    rust_chess.GameState("8/8 w")
+    raise AssertionError("expected a FenError")
+except rust_chess.FenError:
+    pass
+game = rust_chess.GameState()
+try:
+    game.push("e5")
+    raise AssertionError("expected an IllegalMoveError")
+except rust_chess.ChessError as err:
+    assert isinstance(err, rust_chess.IllegalMoveError)
+try:
+    rust_chess.GameState.from_pgn("1. e4 e5 2. Ke3")
+    raise AssertionError("expected a PgnError")
+except rust_chess.PgnError:
+    pass
+game = rust_chess.GameState.from_pgn("1. e4 e5 2. Nf3 *")
+assert game.active_color == "black"
+mate = rust_chess.GameState("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1")
+best = rust_chess.find_best_move(mate, 2)
+assert repr(best) == "<ChessMove a1a8>" and best.san == "Ra8#"
+mate.push(best)
+assert mate.is_game_over() and mate.result == "1-0"
+try:
+    game.push(best)
+    raise AssertionError("expected an IllegalMoveError")
+except rust_chess.IllegalMoveError:
+    pass
+"#
+        ));
+    }
+}
@@ -0,0 +1,654 @@
+use crate::chess_move::chess_move::ChessMove;
+use crate::chess_move::chess_move_handler::try_handle_chess_move_and_apply;
+use crate::chess_move::chess_move_search::unchecked_chess_move_search;
+use crate::color::Color;
+use crate::piece::piece::Piece;
+use crate::search::move_ordering::{is_tactical, move_to_front, order_moves};
+use crate::search::search_options::SearchOptions;
+use crate::search::skill_level::eval_noise;
+use crate::search::transposition_table::{
+    Bound, PackedMove, TranspositionTable, TranspositionTableHit,
+};
+use crate::search::zobrist::zobrist_hash;
+use crate::state::evaluate_game_state::evaluate_game_state;
+use crate::state::game_state::GameState;
+use crate::state::game_status::GameStatus;
+use crate::tablebase::tablebase::{Tablebase, TablebaseValue};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+pub const INFINITY: i32 = i32::MAX;
+// being mated scores -(MATE_SCORE - ply), so faster mates score higher and slower losses less low
+pub const MATE_SCORE: i32 = INFINITY - 1;
+// the ply always fits in a u8
+const MATE_SCORE_RANGE: i32 = u8::MAX as i32;
+// below the score of a mate found on the board, shorter tablebase mates score higher
+pub const TABLEBASE_WIN_SCORE: i32 = INFINITY / 2;
+// both the ply and the tablebase distance fit in a u8
+const TABLEBASE_SCORE_RANGE: i32 = 2 * u8::MAX as i32;
+
+const NULL_MOVE_MIN_DEPTH: u8 = 3;
+const NULL_MOVE_REDUCTION: u8 = 2;
+const NULL_MOVE_VERIFICATION_DEPTH: u8 = 5;
+const LMR_MIN_DEPTH: u8 = 3;
+const LMR_FULL_DEPTH_MOVES: usize = 3;
+const LMR_DEEP_REDUCTION_MOVES: usize = 6;
+const RAZORING_MAX_DEPTH: u8 = 2;
+// scores are doubled in evaluate_game_state, so a pawn is worth 2
+const FUTILITY_MARGIN: i32 = Piece::Knight.as_score() * 2;
+const RAZORING_MARGINS: [i32; RAZORING_MAX_DEPTH as usize] =
+    [Piece::Rook.as_score() * 2, Piece::Queen.as_score() * 2];
+
+#[derive(Clone, Debug)]
+pub struct SearchResult {
+    pub best_move: Option<ChessMove>,
+    pub score: i32,
+    pub depth: u8,
+    pub nodes: u64,
+}
+
+impl SearchResult {
+    pub const fn empty() -> Self {
+        Self {
+            best_move: None,
+            score: -INFINITY,
+            depth: 0,
+            nodes: 0,
+        }
+    }
+}
+
+pub struct SearchContext {
+    pub options: SearchOptions,
+    pub maximizing_color: Color,
+    pub root_depth: u8,
+    pub nodes: u64,
+    pub transposition_table: Arc<TranspositionTable>,
+    pub stop: Arc<AtomicBool>,
+    pub node_limit: Option<u64>,
+    pub best_so_far: Option<Arc<Mutex<SearchResult>>>,
+    pub tablebase: Option<Arc<Tablebase>>,
+    // node and depth limits are held back while this is set, they only count from ponder_hit
+    pub pondering: Option<Arc<AtomicBool>>,
+}
+
+impl SearchContext {
+    pub fn new(
+        maximizing_color: Color,
+        options: SearchOptions,
+        transposition_table: Arc<TranspositionTable>,
+        stop: Arc<AtomicBool>,
+    ) -> Self {
+        Self {
+            options,
+            maximizing_color,
+            root_depth: 0,
+            nodes: 0,
+            transposition_table,
+            stop,
+            node_limit: None,
+            best_so_far: None,
+            tablebase: None,
+            pondering: None,
+        }
+    }
+    pub fn is_pondering(&self) -> bool {
+        self.pondering
+            .as_ref()
+            .is_some_and(|pondering| pondering.load(Ordering::Relaxed))
+    }
+    pub fn is_stopped(&self) -> bool {
+        self.stop.load(Ordering::Relaxed)
+            || !self.is_pondering()
+                && self
+                    .node_limit
+                    .is_some_and(|node_limit| self.nodes >= node_limit)
+    }
+}
+
+// evaluate_game_state isn't symmetrical between colors, so always score from the root color
+// and flip the sign for the side to move
+fn evaluate_for_side_to_move(game_state: &GameState, context: &SearchContext) -> i32 {
+    let mut score =
+        evaluate_game_state(game_state, context.maximizing_color).clamp(-INFINITY, INFINITY);
+    if let Some(skill_level) = context.options.skill_level {
+        score = score
+            .saturating_add(eval_noise(
+                game_state,
+                skill_level,
+                context.options.random_seed,
+            ))
+            .clamp(-INFINITY, INFINITY);
+    }
+    if game_state.active_color == context.maximizing_color {
+        score
+    } else {
+        -score
+    }
+}
+
+pub const fn tablebase_score(value: TablebaseValue, ply: u8) -> i32 {
+    match value {
+        TablebaseValue::Win(plies) => TABLEBASE_WIN_SCORE - ply as i32 - plies as i32,
+        TablebaseValue::Draw => 0,
+        TablebaseValue::Loss(plies) => -(TABLEBASE_WIN_SCORE - ply as i32 - plies as i32),
+    }
+}
+
+pub const fn mated_score(ply: u8) -> i32 {
+    -(MATE_SCORE - ply as i32)
+}
+
+pub const fn is_mate_score(score: i32) -> bool {
+    let score = score.abs();
+    score >= MATE_SCORE - MATE_SCORE_RANGE && score <= MATE_SCORE
+}
+
+pub const fn is_tablebase_score(score: i32) -> bool {
+    let score = score.abs();
+    score >= TABLEBASE_WIN_SCORE - TABLEBASE_SCORE_RANGE && score <= TABLEBASE_WIN_SCORE
+}
+
+// mate and tablebase scores count plies from the root, the transposition table keeps them
+// counted from the node so a hit at another ply still gets the right distance
+const fn score_to_transposition_table(score: i32, ply: u8) -> i32 {
+    if is_mate_score(score) || is_tablebase_score(score) {
+        score + score.signum() * ply as i32
+    } else {
+        score
+    }
+}
+
+const fn score_from_transposition_table(score: i32, ply: u8) -> i32 {
+    if is_mate_score(score) || is_tablebase_score(score) {
+        score - score.signum() * ply as i32
+    } else {
+        score
+    }
+}
+
+fn has_non_pawn_material(game_state: &GameState, color: Color) -> bool {
+    game_state.board.as_iter().any(|(_, maybe_piece)| {
+        maybe_piece.is_some_and(|piece| {
+            piece.as_color() == color && !matches!(piece.as_piece(), Piece::Pawn | Piece::King)
+        })
+    })
+}
+
+fn make_null_move(game_state: &GameState) -> GameState {
+    let mut null_move_game_state = game_state.clone();
+    null_move_game_state.active_color = game_state.active_color.as_inverse();
+    null_move_game_state.en_passant_target_pos = None;
+    null_move_game_state.game_status = GameStatus::InProgress;
+    null_move_game_state.move_counter.half_move += 1;
+    null_move_game_state
+}
+
+pub fn negamax(
+    game_state: &GameState,
+    depth: u8,
+    mut alpha: i32,
+    beta: i32,
+    ply: u8,
+    allow_null_move: bool,
+    context: &mut SearchContext,
+) -> i32 {
+    if context.is_stopped() {
+        return 0;
+    }
+    context.nodes += 1;
+    if let GameStatus::CheckMate(_) = game_state.game_status {
+        return mated_score(ply);
+    }
+    if game_state.game_status.is_game_over() {
+        return evaluate_for_side_to_move(game_state, context);
+    }
+    if let Some(value) = context
+        .tablebase
+        .as_ref()
+        .and_then(|tablebase| tablebase.probe(game_state))
+    {
+        return tablebase_score(value, ply);
+    }
+    if depth == 0 {
+        return evaluate_for_side_to_move(game_state, context);
+    }
+    // no line from here can beat a mate that was already found closer to the root
+    alpha = alpha.max(mated_score(ply));
+    let beta = beta.min(-mated_score(ply.saturating_add(1)));
+    if alpha >= beta {
+        return alpha;
+    }
+
+    let key = zobrist_hash(game_state);
+    let original_alpha = alpha;
+    let mut hash_move = None;
+    if let Some(hit) = context.transposition_table.probe(key) {
+        hash_move = hit.best_move;
+        if hit.depth >= depth {
+            let score = score_from_transposition_table(hit.score, ply);
+            match hit.bound {
+                Bound::Exact => return score,
+                Bound::Lower if score >= beta => return score,
+                Bound::Upper if score <= alpha => return score,
+                _ => {}
+            }
+        }
+    }
+
+    let options = context.options;
+    let mut depth = depth;
+    let in_check = game_state.game_status.is_check();
+    let is_pv_node = beta.saturating_sub(alpha) > 1;
+    let static_eval = evaluate_for_side_to_move(game_state, context);
+
+    if options.razoring
+        && !is_pv_node
+        && !in_check
+        && depth <= RAZORING_MAX_DEPTH
+        && static_eval.saturating_add(RAZORING_MARGINS[depth as usize - 1]) <= alpha
+    {
+        if depth == 1 {
+            return static_eval;
+        }
+        depth -= 1;
+    }
+
+    if options.null_move_pruning
+        && allow_null_move
+        && !is_pv_node
+        && !in_check
+        && depth >= NULL_MOVE_MIN_DEPTH
+        && static_eval >= beta
+        // zugzwang guard: passing is only safe when there is something other than pawns to move
+        && has_non_pawn_material(game_state, game_state.active_color)
+    {
+        let null_move_game_state = make_null_move(game_state);
+        let reduced_depth = depth - 1 - NULL_MOVE_REDUCTION;
+        let score = -negamax(
+            &null_move_game_state,
+            reduced_depth,
+            -beta,
+            (-beta).saturating_add(1),
+            ply + 1,
+            false,
+            context,
+        );
+        if score >= beta {
+            if depth < NULL_MOVE_VERIFICATION_DEPTH {
+                return beta;
+            }
+            // zugzwang guard: deep cutoffs are verified with a reduced search that doesn't pass
+            let verified_score = negamax(
+                game_state,
+                depth - NULL_MOVE_REDUCTION,
+                beta.saturating_sub(1),
+                beta,
+                ply,
+                false,
+                context,
+            );
+            if verified_score >= beta {
+                return beta;
+            }
+        }
+    }
+
+    let skip_quiet_moves = options.futility_pruning
+        && !is_pv_node
+        && !in_check
+        && depth == 1
+        && static_eval.saturating_add(FUTILITY_MARGIN) <= alpha;
+
+    let mut moves = unchecked_chess_move_search(game_state, None);
+    order_moves(&mut moves);
+    if let Some(hash_move) = hash_move {
+        move_to_front(&mut moves, hash_move);
+    }
+
+    let mut best_score = -INFINITY;
+    let mut best_move = None;
+    let mut moves_searched: usize = 0;
+    for move_ in moves {
+        let mut new_game_state = game_state.clone();
+        if try_handle_chess_move_and_apply(&mut new_game_state, &move_, None).is_err() {
+            continue;
+        }
+        let gives_check = new_game_state.game_status.is_check_or_mate();
+        let is_quiet = !is_tactical(&move_) && !gives_check;
+
+        if skip_quiet_moves && moves_searched > 0 && is_quiet {
+            continue;
+        }
+
+        let extension = u8::from(
+            options.check_extensions
+                && gives_check
+                && ply.saturating_add(depth) < context.root_depth.saturating_mul(2),
+        );
+        let new_depth = depth - 1 + extension;
+
+        let score = if moves_searched == 0 {
+            -negamax(
+                &new_game_state,
+                new_depth,
+                -beta,
+                -alpha,
+                ply + 1,
+                true,
+                context,
+            )
+        } else {
+            let reduction = if options.late_move_reductions
+                && depth >= LMR_MIN_DEPTH
+                && moves_searched >= LMR_FULL_DEPTH_MOVES
+                && !in_check
+                && is_quiet
+            {
+                if moves_searched >= LMR_DEEP_REDUCTION_MOVES {
+                    2
+                } else {
+                    1
+                }
+            } else {
+                0
+            };
+            let null_window_beta = -alpha;
+            let null_window_alpha = (-alpha).saturating_sub(1);
+            let mut score = -negamax(
+                &new_game_state,
+                new_depth.saturating_sub(reduction),
+                null_window_alpha,
+                null_window_beta,
+                ply + 1,
+                true,
+                context,
+            );
+            if score > alpha && reduction > 0 {
+                score = -negamax(
+                    &new_game_state,
+                    new_depth,
+                    null_window_alpha,
+                    null_window_beta,
+                    ply + 1,
+                    true,
+                    context,
+                );
+            }
+            if score > alpha && score < beta {
+                score = -negamax(
+                    &new_game_state,
+                    new_depth,
+                    -beta,
+                    -alpha,
+                    ply + 1,
+                    true,
+                    context,
+                );
+            }
+            score
+        };
+        moves_searched += 1;
+        if context.is_stopped() {
+            return 0;
+        }
+
+        if score > best_score {
+            best_score = score;
+            best_move = Some(PackedMove::from_move(&move_));
+        }
+        alpha = alpha.max(score);
+        if alpha >= beta {
+            break;
+        }
+    }
+
+    if moves_searched == 0 {
+        // only reachable after a null move, the move handler flags real mates / stalemates
+        return static_eval;
+    }
+
+    let bound = if best_score <= original_alpha {
+        Bound::Upper
+    } else if best_score >= beta {
+        Bound::Lower
+    } else {
+        Bound::Exact
+    };
+    context.transposition_table.store(
+        key,
+        TranspositionTableHit {
+            depth,
+            score: score_to_transposition_table(best_score, ply),
+            bound,
+            best_move,
+        },
+    );
+
+    best_score
+}
+
+pub fn search_root_at_depth(
+    game_state: &GameState,
+    depth: u8,
+    previous_best_move: Option<PackedMove>,
+    excluded_moves: &[ChessMove],
+    context: &mut SearchContext,
+) -> (SearchResult, bool) {
+    context.root_depth = depth;
+    let mut result = SearchResult {
+        depth,
+        ..SearchResult::empty()
+    };
+    let mut alpha = -INFINITY;
+
+    let mut moves = unchecked_chess_move_search(game_state, None);
+    order_moves(&mut moves);
+    if let Some(previous_best_move) = previous_best_move {
+        move_to_front(&mut moves, previous_best_move);
+    }
+    for move_ in moves {
+        if excluded_moves.contains(&move_) {
+            continue;
+        }
+        let mut new_game_state = game_state.clone();
+        if try_handle_chess_move_and_apply(&mut new_game_state, &move_, None).is_err() {
+            continue;
+        }
+        let score = -negamax(
+            &new_game_state,
+            depth - 1,
+            -INFINITY,
+            -alpha,
+            1,
+            true,
+            context,
+        );
+        if context.is_stopped() {
+            // the interrupted score can't be trusted, keep what was completed
+            result.best_move = result.best_move.or(Some(move_));
+            return (result, false);
+        }
+        if score > result.score {
+            result.score = score;
+            result.best_move = Some(move_);
+        } else {
+            // force to pick at least one legal chess_move
+            result.best_move = result.best_move.or(Some(move_));
+        }
+        alpha = alpha.max(score);
+    }
+
+    (result, true)
+}
+
+pub fn iterative_deepening(
+    game_state: &GameState,
+    max_depth: u8,
+    context: &mut SearchContext,
+) -> SearchResult {
+    let mut result = SearchResult::empty();
+    for depth in 1..=u8::MAX {
+        if depth > max_depth.max(1) && !context.is_pondering() {
+            break;
+        }
+        let previous_best_move = result.best_move.as_ref().map(PackedMove::from_move);
+        let (iteration_result, completed) =
+            search_root_at_depth(game_state, depth, previous_best_move, &[], context);
+        if completed || result.best_move.is_none() {
+            result = iteration_result;
+            if let Some(best_so_far) = context.best_so_far.as_ref() {
+                *best_so_far.lock().expect("best_so_far lock poisoned") = SearchResult {
+                    nodes: context.nodes,
+                    ..result.clone()
+                };
+            }
+        }
+        if !completed {
+            break;
+        }
+    }
+    result.nodes = context.nodes;
+    result
+}
+
+pub fn search_root(game_state: &GameState, depth: u8, options: SearchOptions) -> SearchResult {
+    let mut context = SearchContext::new(
+        game_state.active_color,
+        options,
+        Arc::new(TranspositionTable::new(options.hash_size_mb)),
+        Arc::new(AtomicBool::new(false)),
+    );
+    iterative_deepening(game_state, depth, &mut context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::board_position::BoardPosition;
+    use crate::board::position::*;
+    use crate::notation::fen::deserialize;
+    use rstest::rstest;
+
+    const NULL_MOVE_ONLY: SearchOptions = SearchOptions {
+        null_move_pruning: true,
+        ..SearchOptions::new()
+    };
+    const LMR_ONLY: SearchOptions = SearchOptions {
+        late_move_reductions: true,
+        ..SearchOptions::new()
+    };
+    const FUTILITY_ONLY: SearchOptions = SearchOptions {
+        futility_pruning: true,
+        ..SearchOptions::new()
+    };
+    const RAZORING_ONLY: SearchOptions = SearchOptions {
+        razoring: true,
+        ..SearchOptions::new()
+    };
+    const CHECK_EXTENSIONS_ONLY: SearchOptions = SearchOptions {
+        check_extensions: true,
+        ..SearchOptions::new()
+    };
+
+    #[rstest]
+    #[case(SearchOptions::new())]
+    #[case(NULL_MOVE_ONLY)]
+    #[case(LMR_ONLY)]
+    #[case(FUTILITY_ONLY)]
+    #[case(RAZORING_ONLY)]
+    #[case(CHECK_EXTENSIONS_ONLY)]
+    #[case(SearchOptions::selective())]
+    fn test_search_root_finds_mate_in_one(#[case] options: SearchOptions) {
+        let game_state =
+            deserialize("6k1/5ppp/8/8/8/8/5PPP/3Q2K1 w - - 0 1").expect("bad fen string!");
+        let result = search_root(&game_state, 2, options);
+        let best_move = result.best_move.expect("expected a move");
+        assert_eq!((D1, D8), (best_move.from, best_move.to));
+    }
+
+    #[rstest]
+    #[case("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1", A1, A8)]
+    #[case("r5k1/5ppp/8/8/8/8/5PPP/6K1 b - - 0 1", A8, A1)]
+    fn test_search_root_selective_finds_back_rank_mate(
+        #[case] fen_str: &'static str,
+        #[case] expected_from: BoardPosition,
+        #[case] expected_to: BoardPosition,
+    ) {
+        let game_state = deserialize(fen_str).expect("bad fen string!");
+        let best_move = search_root(&game_state, 3, SearchOptions::selective())
+            .best_move
+            .expect("expected a move");
+        assert_eq!((expected_from, expected_to), (best_move.from, best_move.to));
+    }
+
+    #[rstest]
+    #[case(SearchOptions::new())]
+    #[case(SearchOptions::selective())]
+    fn test_search_prefers_the_fastest_mate(#[case] options: SearchOptions) {
+        // slower mates are also found at depth 3, only the distance separates them
+        let game_state =
+            deserialize("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1").expect("bad fen string!");
+        let result = search_root(&game_state, 3, options);
+        let best_move = result.best_move.expect("expected a move");
+        assert_eq!((A1, A8), (best_move.from, best_move.to));
+        assert_eq!(MATE_SCORE - 1, result.score);
+    }
+
+    #[test]
+    fn test_search_scores_being_mated_by_distance() {
+        let game_state = deserialize("8/8/8/8/8/1k6/7r/K7 w - - 0 1").expect("bad fen string!");
+        let result = search_root(&game_state, 3, SearchOptions::new());
+        assert_eq!(mated_score(2), result.score);
+    }
+
+    #[test]
+    fn test_selective_search_visits_fewer_nodes() {
+        // a quiet position, mate distance pruning settles forced mates before selectivity matters
+        let game_state =
+            deserialize("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3")
+                .expect("bad fen string!");
+        let full = search_root(&game_state, 3, SearchOptions::new());
+        let selective = search_root(&game_state, 3, SearchOptions::selective());
+        assert!(
+            selective.nodes < full.nodes,
+            "selective: {} full: {}",
+            selective.nodes,
+            full.nodes
+        );
+    }
+
+    #[rstest]
+    #[case(TablebaseValue::Win(7))]
+    #[case(TablebaseValue::Loss(12))]
+    #[case(TablebaseValue::Draw)]
+    fn test_tablebase_score_transposition_table_round_trip(#[case] value: TablebaseValue) {
+        let stored = score_to_transposition_table(tablebase_score(value, 3), 3);
+        assert_eq!(
+            tablebase_score(value, 9),
+            score_from_transposition_table(stored, 9)
+        );
+        assert_eq!(
+            tablebase_score(value, 0),
+            score_from_transposition_table(stored, 0)
+        );
+    }
+
+    #[test]
+    fn test_mate_score_transposition_table_round_trip() {
+        let stored = score_to_transposition_table(mated_score(6), 2);
+        assert_eq!(mated_score(4), stored);
+        assert_eq!(mated_score(9), score_from_transposition_table(stored, 5));
+        let stored = score_to_transposition_table(-mated_score(5), 4);
+        assert_eq!(-mated_score(1), stored);
+    }
+
+    #[rstest]
+    #[case(INFINITY)]
+    #[case(-INFINITY)]
+    #[case(250)]
+    fn test_non_tablebase_scores_stored_unchanged(#[case] score: i32) {
+        assert_eq!(score, score_to_transposition_table(score, 5));
+        assert_eq!(score, score_from_transposition_table(score, 5));
+    }
+}
@@ -0,0 +1,191 @@
+use crate::chess_move::chess_move::ChessMove;
+use crate::notation::epd::Epd;
+use crate::notation::san::SanParsingError;
+use crate::search::search_handle::SearchHandle;
+use crate::search::search_limits::SearchLimits;
+use crate::search::search_options::SearchOptions;
+
+const DEFAULT_SUITE_DEPTH: u8 = 4;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct EpdSuiteOptions {
+    pub limits: SearchLimits,
+    pub search_options: SearchOptions,
+}
+
+impl EpdSuiteOptions {
+    pub const fn new() -> Self {
+        Self {
+            limits: SearchLimits {
+                depth: Some(DEFAULT_SUITE_DEPTH),
+                ..SearchLimits::new()
+            },
+            search_options: SearchOptions::selective(),
+        }
+    }
+}
+
+impl Default for EpdSuiteOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EpdPositionResult {
+    pub id: Option<String>,
+    pub best_move: Option<ChessMove>,
+    pub solved: bool,
+    pub points: u32,
+    pub max_points: u32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EpdSuiteReport {
+    pub results: Vec<EpdPositionResult>,
+}
+
+impl EpdSuiteReport {
+    pub fn solved(&self) -> impl Iterator<Item = &EpdPositionResult> {
+        self.results.iter().filter(|result| result.solved)
+    }
+    pub fn unsolved(&self) -> impl Iterator<Item = &EpdPositionResult> {
+        self.results.iter().filter(|result| !result.solved)
+    }
+    pub fn points(&self) -> u32 {
+        self.results.iter().map(|result| result.points).sum()
+    }
+    pub fn max_points(&self) -> u32 {
+        self.results.iter().map(|result| result.max_points).sum()
+    }
+}
+
+struct Expectation {
+    best_moves: Vec<ChessMove>,
+    avoid_moves: Vec<ChessMove>,
+    move_points: Vec<(ChessMove, u32)>,
+}
+
+impl Expectation {
+    fn from_epd(epd: &Epd) -> Result<Self, SanParsingError> {
+        Ok(Self {
+            best_moves: epd.best_moves()?,
+            avoid_moves: epd.avoid_moves()?,
+            move_points: epd.move_points(),
+        })
+    }
+    fn is_solved_by(&self, chess_move: &ChessMove) -> bool {
+        // without bm or am only the top scoring move counts as solved
+        if self.best_moves.is_empty() && self.avoid_moves.is_empty() {
+            return !self.move_points.is_empty()
+                && self.points_for(chess_move) == self.max_points();
+        }
+        (self.best_moves.is_empty() || self.best_moves.contains(chess_move))
+            && !self.avoid_moves.contains(chess_move)
+    }
+    fn points_for(&self, chess_move: &ChessMove) -> u32 {
+        self.move_points
+            .iter()
+            .find(|(scored_move, _)| scored_move == chess_move)
+            .map(|(_, points)| *points)
+            .unwrap_or_default()
+    }
+    fn max_points(&self) -> u32 {
+        self.move_points
+            .iter()
+            .map(|(_, points)| *points)
+            .max()
+            .unwrap_or(1)
+    }
+    fn result(&self, id: Option<String>, best_move: Option<ChessMove>) -> EpdPositionResult {
+        let solved = best_move
+            .as_ref()
+            .is_some_and(|chess_move| self.is_solved_by(chess_move));
+        // suites without partial credit score one point per solved position
+        let points = match &best_move {
+            Some(chess_move) if !self.move_points.is_empty() => self.points_for(chess_move),
+            _ => solved as u32,
+        };
+        EpdPositionResult {
+            id,
+            best_move,
+            solved,
+            points,
+            max_points: self.max_points(),
+        }
+    }
+}
+
+pub fn run_epd_suite(
+    epds: &[Epd],
+    options: Option<EpdSuiteOptions>,
+) -> Result<EpdSuiteReport, SanParsingError> {
+    run_epd_suite_with(epds, options, |_| {})
+}
+
+// on_result is called after every position so long runs can report progress
+pub fn run_epd_suite_with(
+    epds: &[Epd],
+    options: Option<EpdSuiteOptions>,
+    mut on_result: impl FnMut(&EpdPositionResult),
+) -> Result<EpdSuiteReport, SanParsingError> {
+    let options = options.unwrap_or_default();
+    // a typo in the suite should fail before any time is spent searching
+    let expectations = epds
+        .iter()
+        .map(Expectation::from_epd)
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut report = EpdSuiteReport::default();
+    for (epd, expectation) in epds.iter().zip(expectations) {
+        let search_result =
+            SearchHandle::start(&epd.game_state, options.limits, options.search_options).join();
+        let result = expectation.result(epd.id().map(str::to_string), search_result.best_move);
+        on_result(&result);
+        report.results.push(result);
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::epd::{deserialize_epd, deserialize_epd_suite};
+
+    const MATE_SUITE: &str = "6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - bm Ra8#; id \"mate.1\";
+6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - am Ra8#; id \"avoid.mate\";
+6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - c0 \"Ra8#=10, Ra7=3\"; id \"points\";";
+
+    #[test]
+    fn test_run_epd_suite() {
+        let epds = deserialize_epd_suite(MATE_SUITE).expect("bad epd suite!");
+        let mut progress = vec![];
+        let report = run_epd_suite_with(&epds, None, |result| progress.push(result.id.clone()))
+            .expect("bad expectations!");
+        assert_eq!(
+            vec![Some("mate.1".to_string())],
+            report
+                .solved()
+                .take(1)
+                .map(|result| result.id.clone())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![Some("avoid.mate".to_string())],
+            report
+                .unsolved()
+                .map(|result| result.id.clone())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(2, report.solved().count());
+        assert_eq!(11, report.points());
+        assert_eq!(12, report.max_points());
+        assert_eq!(3, progress.len());
+    }
+
+    #[test]
+    fn test_run_epd_suite_rejects_illegal_best_move() {
+        let epd =
+            deserialize_epd("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - bm Qa8#;").expect("bad epd string!");
+        assert!(run_epd_suite(&[epd], None).is_err());
+    }
+}
@@ -0,0 +1,148 @@
+use crate::search::alpha_beta::{iterative_deepening, SearchContext, SearchResult};
+use crate::search::search_limits::SearchLimits;
+use crate::search::search_options::SearchOptions;
+use crate::search::transposition_table::TranspositionTable;
+use crate::state::game_state::GameState;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+pub fn lazy_smp_search(game_state: &GameState, depth: u8, options: SearchOptions) -> SearchResult {
+    let context = SearchContext::new(
+        game_state.active_color,
+        options,
+        Arc::new(TranspositionTable::new(options.hash_size_mb)),
+        Arc::new(AtomicBool::new(false)),
+    );
+    lazy_smp_search_with(game_state, depth, context)
+}
+
+// helpers share the main context's options, transposition table and tablebase, but not its limits
+pub fn lazy_smp_search_with(
+    game_state: &GameState,
+    depth: u8,
+    mut main_context: SearchContext,
+) -> SearchResult {
+    let options = main_context.options;
+    if options.threads <= 1 {
+        return iterative_deepening(game_state, depth, &mut main_context);
+    }
+
+    // helpers only exist to fill the shared table, they are stopped as soon as the main thread is done
+    let helpers_stop = Arc::new(AtomicBool::new(false));
+    thread::scope(|scope| {
+        let helpers = (1..options.threads)
+            .map(|thread_id| {
+                let mut helper_context = SearchContext::new(
+                    main_context.maximizing_color,
+                    options,
+                    main_context.transposition_table.clone(),
+                    helpers_stop.clone(),
+                );
+                helper_context.tablebase = main_context.tablebase.clone();
+                helper_context.pondering = main_context.pondering.clone();
+                // odd helpers search one ply deeper so threads don't all walk the same tree in lockstep
+                let helper_depth = depth.saturating_add((thread_id % 2) as u8);
+                scope.spawn(move || {
+                    iterative_deepening(game_state, helper_depth, &mut helper_context).nodes
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut result = iterative_deepening(game_state, depth, &mut main_context);
+        helpers_stop.store(true, Ordering::Relaxed);
+        for helper in helpers {
+            result.nodes += helper.join().expect("lazy smp helper thread panicked");
+        }
+        result
+    })
+}
+
+// searches on the calling thread for targets without threads, so move_time can't be honoured
+pub fn single_threaded_search(
+    game_state: &GameState,
+    limits: SearchLimits,
+    options: SearchOptions,
+) -> SearchResult {
+    let options = SearchOptions {
+        threads: 1,
+        ..options
+    };
+    let mut context = SearchContext::new(
+        game_state.active_color,
+        options,
+        Arc::new(TranspositionTable::new(options.hash_size_mb)),
+        Arc::new(AtomicBool::new(false)),
+    );
+    context.node_limit = limits.nodes;
+    iterative_deepening(game_state, limits.max_depth(), &mut context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::position::*;
+    use crate::notation::fen::deserialize;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(SearchOptions::new())]
+    #[case(SearchOptions::selective())]
+    fn test_single_thread_is_deterministic(#[case] options: SearchOptions) {
+        let game_state = deserialize("8/1P2R3/k7/8/1Q6/8/8/7K w - - 0 1").expect("bad fen string!");
+        let first = lazy_smp_search(&game_state, 3, options);
+        let second = lazy_smp_search(&game_state, 3, options);
+        assert_eq!(first.best_move, second.best_move);
+        assert_eq!(first.score, second.score);
+        assert_eq!(first.nodes, second.nodes);
+    }
+
+    #[rstest]
+    #[case(2)]
+    #[case(4)]
+    fn test_multi_thread_finds_mate(#[case] threads: usize) {
+        let game_state =
+            deserialize("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1").expect("bad fen string!");
+        let options = SearchOptions {
+            threads,
+            ..SearchOptions::selective()
+        };
+        let result = lazy_smp_search(&game_state, 3, options);
+        let best_move = result.best_move.expect("expected a move");
+        assert_eq!((A1, A8), (best_move.from, best_move.to));
+    }
+
+    #[test]
+    fn test_stop_signal_ends_search() {
+        let game_state =
+            deserialize("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1").expect("bad fen string!");
+        let options = SearchOptions {
+            threads: 4,
+            ..SearchOptions::new()
+        };
+        let context = SearchContext::new(
+            game_state.active_color,
+            options,
+            Arc::new(TranspositionTable::new(1)),
+            Arc::new(AtomicBool::new(true)),
+        );
+        let result = lazy_smp_search_with(&game_state, u8::MAX, context);
+        // a search that is stopped before it starts still returns a legal move
+        assert!(result.best_move.is_some());
+    }
+
+    #[test]
+    fn test_single_threaded_search_node_limit() {
+        let game_state =
+            deserialize("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1").expect("bad fen string!");
+        let limits = SearchLimits {
+            nodes: Some(2_000),
+            ..SearchLimits::new()
+        };
+        let result = single_threaded_search(&game_state, limits, SearchOptions::selective());
+        let best_move = result.best_move.expect("expected a move");
+        assert_eq!((A1, A8), (best_move.from, best_move.to));
+        // the limit is checked between nodes, so it can be overshot by a few
+        assert!(result.nodes < 2_100, "searched {} nodes", result.nodes);
+    }
+}
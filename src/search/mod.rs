@@ -0,0 +1,11 @@
+pub mod alpha_beta;
+pub mod epd_suite;
+pub mod lazy_smp;
+pub mod move_ordering;
+pub mod multi_pv;
+pub mod search_handle;
+pub mod search_limits;
+pub mod search_options;
+pub mod skill_level;
+pub mod transposition_table;
+pub mod zobrist;
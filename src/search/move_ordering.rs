@@ -0,0 +1,65 @@
+use crate::chess_move::chess_move::{ChessMove, ChessMoveType};
+use crate::search::transposition_table::PackedMove;
+use std::cmp::Reverse;
+
+const CAPTURE_BONUS: i32 = 100;
+const CAPTURE_SCORE_MULTIPLIER: i32 = 10;
+const PROMOTION_BONUS: i32 = 1000;
+
+pub const fn is_tactical(chess_move: &ChessMove) -> bool {
+    chess_move.captured_piece.is_some()
+        || matches!(chess_move.move_type, ChessMoveType::Promotion(_))
+}
+
+pub const fn move_order_score(chess_move: &ChessMove) -> i32 {
+    let mut score = 0;
+    // most valuable victim / least valuable attacker
+    if let Some(captured_piece) = chess_move.captured_piece {
+        score += CAPTURE_BONUS
+            + captured_piece.as_piece().as_score() * CAPTURE_SCORE_MULTIPLIER
+            - chess_move.piece.as_piece().as_score();
+    }
+    if let ChessMoveType::Promotion(promotion_piece) = chess_move.move_type {
+        score += PROMOTION_BONUS + promotion_piece.as_piece().as_score();
+    }
+    score
+}
+
+pub fn order_moves(moves: &mut [ChessMove]) {
+    // stable so equally scored moves keep their generation order
+    moves.sort_by_key(|chess_move| Reverse(move_order_score(chess_move)));
+}
+
+pub fn move_to_front(moves: &mut Vec<ChessMove>, packed_move: PackedMove) {
+    if let Some(ix) = moves.iter().position(|chess_move| packed_move.matches(chess_move)) {
+        let chess_move = moves.remove(ix);
+        moves.insert(0, chess_move);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::position::*;
+    use crate::piece::chess_piece::ChessPiece;
+    use crate::piece::promotion_piece::PromotionPiece;
+
+    #[test]
+    fn test_order_moves() {
+        let quiet = ChessMove::create_normal(ChessPiece::WhiteQueen, D1, D2);
+        let queen_takes_pawn =
+            ChessMove::create_normal_capture(ChessPiece::WhiteQueen, D1, D7, ChessPiece::BlackPawn);
+        let pawn_takes_queen =
+            ChessMove::create_normal_capture(ChessPiece::WhitePawn, E4, D5, ChessPiece::BlackQueen);
+        let promotion =
+            ChessMove::create_promotion(ChessPiece::WhitePawn, A7, A8, PromotionPiece::Queen);
+        let mut moves = vec![
+            quiet.clone(),
+            queen_takes_pawn.clone(),
+            pawn_takes_queen.clone(),
+            promotion.clone(),
+        ];
+        order_moves(&mut moves);
+        assert_eq!(vec![promotion, pawn_takes_queen, queen_takes_pawn, quiet], moves);
+    }
+}
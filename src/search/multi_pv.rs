@@ -0,0 +1,216 @@
+use crate::chess_move::chess_move::ChessMove;
+use crate::chess_move::chess_move_handler::try_handle_chess_move_and_apply;
+use crate::chess_move::chess_move_search::unchecked_chess_move_search;
+use crate::search::alpha_beta::{search_root_at_depth, SearchContext};
+use crate::search::search_handle::spawn_timer;
+use crate::search::search_limits::SearchLimits;
+use crate::search::search_options::SearchOptions;
+use crate::search::transposition_table::{PackedMove, TranspositionTable};
+use crate::search::zobrist::zobrist_hash;
+use crate::state::game_state::GameState;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+// nothing can stop an analysis from outside, so one without limits stops at this depth
+pub const DEFAULT_ANALYSIS_DEPTH: u8 = 4;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnalysisLine {
+    pub principal_variation: Vec<ChessMove>,
+    pub score: i32,
+    pub depth: u8,
+}
+
+impl AnalysisLine {
+    pub fn best_move(&self) -> Option<&ChessMove> {
+        self.principal_variation.first()
+    }
+}
+
+pub fn analyse(game_state: &GameState, limits: SearchLimits, multipv: usize) -> Vec<AnalysisLine> {
+    analyse_with_options(game_state, limits, multipv, None)
+}
+
+pub fn analyse_with_options(
+    game_state: &GameState,
+    limits: SearchLimits,
+    multipv: usize,
+    options: Option<SearchOptions>,
+) -> Vec<AnalysisLine> {
+    let options = options.unwrap_or_default();
+    let limits = if limits.is_unlimited() {
+        SearchLimits {
+            depth: Some(DEFAULT_ANALYSIS_DEPTH),
+            ..limits
+        }
+    } else {
+        limits
+    };
+    let stop = Arc::new(AtomicBool::new(false));
+    let done = Arc::new(AtomicBool::new(false));
+    let mut context = SearchContext::new(
+        game_state.active_color,
+        options,
+        Arc::new(TranspositionTable::new(options.hash_size_mb)),
+        stop.clone(),
+    );
+    context.node_limit = limits.nodes;
+    if let Some(move_time) = limits.move_time {
+        spawn_timer(
+            move_time,
+            stop,
+            Arc::new(AtomicBool::new(false)),
+            done.clone(),
+        );
+    }
+
+    let mut lines: Vec<AnalysisLine> = vec![];
+    for depth in 1..=limits.max_depth().max(1) {
+        let mut depth_lines: Vec<AnalysisLine> = vec![];
+        let mut excluded_moves: Vec<ChessMove> = vec![];
+        let mut completed = true;
+        while depth_lines.len() < multipv {
+            // order each line by the previous iteration's move at the same rank
+            let previous_best_move = lines
+                .get(depth_lines.len())
+                .and_then(AnalysisLine::best_move)
+                .map(PackedMove::from_move);
+            let (result, line_completed) = search_root_at_depth(
+                game_state,
+                depth,
+                previous_best_move,
+                &excluded_moves,
+                &mut context,
+            );
+            let Some(best_move) = result.best_move else {
+                break;
+            };
+            if !line_completed {
+                completed = false;
+                break;
+            }
+            excluded_moves.push(best_move.clone());
+            depth_lines.push(AnalysisLine {
+                principal_variation: principal_variation(game_state, best_move, depth, &context),
+                score: result.score,
+                depth,
+            });
+        }
+        if completed || lines.is_empty() {
+            lines = depth_lines;
+        }
+        if !completed {
+            break;
+        }
+    }
+    done.store(true, Ordering::Relaxed);
+    lines
+}
+
+// follows hash moves out of the transposition table, the root move is already known
+fn principal_variation(
+    game_state: &GameState,
+    root_move: ChessMove,
+    depth: u8,
+    context: &SearchContext,
+) -> Vec<ChessMove> {
+    let mut game_state = game_state.clone();
+    let mut seen_positions = vec![zobrist_hash(&game_state)];
+    let mut next_move = Some(root_move);
+    let mut principal_variation = vec![];
+    while let Some(chess_move) = next_move.take() {
+        if try_handle_chess_move_and_apply(&mut game_state, &chess_move, None).is_err() {
+            break;
+        }
+        principal_variation.push(chess_move);
+        let key = zobrist_hash(&game_state);
+        if principal_variation.len() >= depth as usize || seen_positions.contains(&key) {
+            break;
+        }
+        seen_positions.push(key);
+        let Some(hash_move) = context
+            .transposition_table
+            .probe(key)
+            .and_then(|hit| hit.best_move)
+        else {
+            break;
+        };
+        next_move = unchecked_chess_move_search(&game_state, None)
+            .into_iter()
+            .find(|chess_move| hash_move.matches(chess_move));
+    }
+    principal_variation
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::position::*;
+    use crate::notation::fen::{deserialize, FEN_STARTING_POS};
+    use crate::search::lazy_smp::lazy_smp_search;
+    use rstest::rstest;
+
+    const DEPTH_3: SearchLimits = SearchLimits {
+        depth: Some(3),
+        ..SearchLimits::new()
+    };
+
+    #[rstest]
+    #[case(1)]
+    #[case(3)]
+    #[case(5)]
+    fn test_analyse_ranks_lines(#[case] multipv: usize) {
+        let game_state = deserialize(FEN_STARTING_POS).expect("bad fen string!");
+        let lines = analyse(&game_state, DEPTH_3, multipv);
+        assert_eq!(multipv, lines.len());
+        for window in lines.windows(2) {
+            assert!(window[0].score >= window[1].score);
+            assert_ne!(window[0].best_move(), window[1].best_move());
+        }
+        for line in lines.iter() {
+            assert_eq!(3, line.depth);
+            assert!(!line.principal_variation.is_empty());
+            assert!(line.principal_variation.len() <= 3);
+        }
+    }
+
+    #[test]
+    fn test_first_line_matches_best_move() {
+        let game_state =
+            deserialize("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1").expect("bad fen string!");
+        let lines = analyse(&game_state, DEPTH_3, 3);
+        let expected = lazy_smp_search(&game_state, 3, SearchOptions::new());
+        assert_eq!(expected.best_move.as_ref(), lines[0].best_move());
+        assert_eq!(expected.score, lines[0].score);
+        let best_move = lines[0].best_move().expect("expected a move");
+        assert_eq!((A1, A8), (best_move.from, best_move.to));
+        // the mate is only found on the first line
+        assert!(lines[0].score > lines[1].score);
+    }
+
+    #[test]
+    fn test_scores_from_side_to_move() {
+        // black is a queen up and to move
+        let game_state =
+            deserialize("6k1/5ppp/8/8/8/3q4/5PPP/6K1 b - - 0 1").expect("bad fen string!");
+        let lines = analyse(&game_state, DEPTH_3, 2);
+        assert!(lines.iter().all(|line| line.score > 0));
+    }
+
+    #[test]
+    fn test_multipv_larger_than_legal_moves() {
+        // the white king is in check and can only step off the first rank
+        let game_state = deserialize("k7/8/8/8/8/8/8/r6K w - - 0 1").expect("bad fen string!");
+        let lines = analyse(&game_state, DEPTH_3, 10);
+        assert_eq!(2, lines.len());
+    }
+
+    #[test]
+    fn test_unlimited_analysis_uses_default_depth() {
+        let game_state = deserialize("k7/8/8/8/8/8/8/r6K w - - 0 1").expect("bad fen string!");
+        let lines = analyse(&game_state, SearchLimits::new(), 2);
+        assert!(lines
+            .iter()
+            .all(|line| line.depth == DEFAULT_ANALYSIS_DEPTH));
+    }
+}
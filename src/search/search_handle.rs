@@ -0,0 +1,275 @@
+use crate::chess_move::chess_move::ChessMove;
+use crate::chess_move::chess_move_handler::try_handle_chess_move_and_apply;
+use crate::chess_move::chess_move_search::unchecked_chess_move_search;
+use crate::chess_move::invalid_chess_move_error::InvalidChessMoveError;
+use crate::search::alpha_beta::{SearchContext, SearchResult};
+use crate::search::lazy_smp::lazy_smp_search_with;
+use crate::search::search_limits::SearchLimits;
+use crate::search::search_options::SearchOptions;
+use crate::search::transposition_table::TranspositionTable;
+use crate::state::game_state::GameState;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+const TIMER_POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+pub struct SearchHandle {
+    stop: Arc<AtomicBool>,
+    pondering: Arc<AtomicBool>,
+    done: Arc<AtomicBool>,
+    best_so_far: Arc<Mutex<SearchResult>>,
+    limits: SearchLimits,
+    search_thread: Option<JoinHandle<SearchResult>>,
+}
+
+impl SearchHandle {
+    pub fn start(game_state: &GameState, limits: SearchLimits, options: SearchOptions) -> Self {
+        Self::spawn(game_state.clone(), limits, options, false)
+    }
+    // searches the position after the expected reply, no limit applies until ponder_hit
+    pub fn ponder(
+        game_state: &GameState,
+        expected_reply: &ChessMove,
+        limits: SearchLimits,
+        options: SearchOptions,
+    ) -> Result<Self, InvalidChessMoveError> {
+        // the move handler trusts the move it's given, so make sure it can actually be played
+        if !unchecked_chess_move_search(game_state, None).contains(expected_reply) {
+            return Err(InvalidChessMoveError::InvalidMove(
+                expected_reply.from,
+                expected_reply.to,
+            ));
+        }
+        let mut ponder_game_state = game_state.clone();
+        try_handle_chess_move_and_apply(&mut ponder_game_state, expected_reply, None)?;
+        Ok(Self::spawn(ponder_game_state, limits, options, true))
+    }
+    fn spawn(
+        game_state: GameState,
+        limits: SearchLimits,
+        options: SearchOptions,
+        pondering: bool,
+    ) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let pondering = Arc::new(AtomicBool::new(pondering));
+        let done = Arc::new(AtomicBool::new(false));
+        let best_so_far = Arc::new(Mutex::new(SearchResult::empty()));
+
+        let mut context = SearchContext::new(
+            game_state.active_color,
+            options,
+            Arc::new(TranspositionTable::new(options.hash_size_mb)),
+            stop.clone(),
+        );
+        // node limits are per search, a ponder search that turns into a real one keeps its count
+        context.node_limit = limits.nodes;
+        context.best_so_far = Some(best_so_far.clone());
+        context.pondering = Some(pondering.clone());
+
+        if let Some(move_time) = limits.move_time {
+            spawn_timer(move_time, stop.clone(), pondering.clone(), done.clone());
+        }
+        let search_thread = {
+            let done = done.clone();
+            thread::spawn(move || {
+                let result = lazy_smp_search_with(&game_state, limits.max_depth(), context);
+                done.store(true, Ordering::Relaxed);
+                result
+            })
+        };
+
+        Self {
+            stop,
+            pondering,
+            done,
+            best_so_far,
+            limits,
+            search_thread: Some(search_thread),
+        }
+    }
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+    pub fn ponder_hit(&self) {
+        self.pondering.store(false, Ordering::Relaxed);
+        // pondering may already have gone past the depth limit, the completed depth is enough
+        if self.best_so_far().depth >= self.limits.max_depth() {
+            self.stop();
+        }
+    }
+    pub fn is_pondering(&self) -> bool {
+        self.pondering.load(Ordering::Relaxed)
+    }
+    pub fn is_done(&self) -> bool {
+        self.done.load(Ordering::Relaxed)
+    }
+    pub fn best_so_far(&self) -> SearchResult {
+        self.best_so_far
+            .lock()
+            .expect("best_so_far lock poisoned")
+            .clone()
+    }
+    pub fn join(mut self) -> SearchResult {
+        self.search_thread
+            .take()
+            .expect("search thread already joined")
+            .join()
+            .expect("search thread panicked")
+    }
+}
+
+impl Drop for SearchHandle {
+    // a dropped handle shouldn't leave a search running in the background
+    fn drop(&mut self) {
+        if self.search_thread.is_some() {
+            self.stop();
+        }
+    }
+}
+
+pub fn spawn_timer(
+    move_time: Duration,
+    stop: Arc<AtomicBool>,
+    pondering: Arc<AtomicBool>,
+    done: Arc<AtomicBool>,
+) {
+    thread::spawn(move || {
+        let mut started = None;
+        while !done.load(Ordering::Relaxed) && !stop.load(Ordering::Relaxed) {
+            if !pondering.load(Ordering::Relaxed) {
+                let started = *started.get_or_insert_with(Instant::now);
+                if started.elapsed() >= move_time {
+                    stop.store(true, Ordering::Relaxed);
+                    break;
+                }
+            }
+            thread::sleep(TIMER_POLL_INTERVAL);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::position::*;
+    use crate::notation::fen::{deserialize, FEN_STARTING_POS};
+    use crate::piece::chess_piece::ChessPiece;
+    use crate::search::lazy_smp::lazy_smp_search;
+    use rstest::rstest;
+
+    fn wait_for_first_result(search_handle: &SearchHandle) {
+        while search_handle.best_so_far().best_move.is_none() {
+            thread::sleep(TIMER_POLL_INTERVAL);
+        }
+    }
+
+    #[test]
+    fn test_stop_infinite_search() {
+        let game_state = deserialize(FEN_STARTING_POS).expect("bad fen string!");
+        let search_handle =
+            SearchHandle::start(&game_state, SearchLimits::new(), SearchOptions::selective());
+        wait_for_first_result(&search_handle);
+        assert!(!search_handle.is_done());
+        search_handle.stop();
+        let result = search_handle.join();
+        assert!(result.best_move.is_some());
+        assert!(result.depth >= 1);
+    }
+
+    #[test]
+    fn test_depth_limit_matches_blocking_search() {
+        let game_state =
+            deserialize("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1").expect("bad fen string!");
+        let limits = SearchLimits {
+            depth: Some(3),
+            ..SearchLimits::new()
+        };
+        let search_handle = SearchHandle::start(&game_state, limits, SearchOptions::new());
+        let result = search_handle.join();
+        let expected = lazy_smp_search(&game_state, 3, SearchOptions::new());
+        assert_eq!(expected.best_move, result.best_move);
+        assert_eq!(expected.score, result.score);
+    }
+
+    #[test]
+    fn test_move_time_limit() {
+        let game_state = deserialize(FEN_STARTING_POS).expect("bad fen string!");
+        let limits = SearchLimits {
+            move_time: Some(Duration::from_millis(50)),
+            ..SearchLimits::new()
+        };
+        let search_handle = SearchHandle::start(&game_state, limits, SearchOptions::new());
+        let result = search_handle.join();
+        assert!(result.best_move.is_some());
+    }
+
+    #[test]
+    fn test_node_limit() {
+        let game_state = deserialize(FEN_STARTING_POS).expect("bad fen string!");
+        let limits = SearchLimits {
+            nodes: Some(500),
+            ..SearchLimits::new()
+        };
+        let result = SearchHandle::start(&game_state, limits, SearchOptions::new()).join();
+        assert!(result.best_move.is_some());
+        assert!(result.nodes <= 501);
+    }
+
+    #[test]
+    fn test_ponder_hit_starts_clock() {
+        let game_state = deserialize(FEN_STARTING_POS).expect("bad fen string!");
+        let expected_reply = ChessMove::create_normal(ChessPiece::WhitePawn, E2, E4);
+        let limits = SearchLimits {
+            move_time: Some(Duration::from_millis(20)),
+            ..SearchLimits::new()
+        };
+        let search_handle =
+            SearchHandle::ponder(&game_state, &expected_reply, limits, SearchOptions::new())
+                .expect("expected reply should be legal");
+        assert!(search_handle.is_pondering());
+        thread::sleep(Duration::from_millis(60));
+        assert!(!search_handle.is_done());
+        search_handle.ponder_hit();
+        assert!(!search_handle.is_pondering());
+        let result = search_handle.join();
+        // the ponder search is for the opponent of the side that was to move
+        let best_move = result.best_move.expect("expected a move");
+        assert_eq!(
+            game_state.active_color.as_inverse(),
+            best_move.piece.as_color()
+        );
+    }
+
+    #[rstest]
+    #[case(SearchLimits { nodes: Some(500), ..SearchLimits::new() })]
+    #[case(SearchLimits { depth: Some(1), ..SearchLimits::new() })]
+    fn test_ponder_holds_back_limits(#[case] limits: SearchLimits) {
+        let game_state = deserialize(FEN_STARTING_POS).expect("bad fen string!");
+        let expected_reply = ChessMove::create_normal(ChessPiece::WhitePawn, E2, E4);
+        let search_handle =
+            SearchHandle::ponder(&game_state, &expected_reply, limits, SearchOptions::new())
+                .expect("expected reply should be legal");
+        wait_for_first_result(&search_handle);
+        thread::sleep(Duration::from_millis(60));
+        assert!(!search_handle.is_done());
+        search_handle.ponder_hit();
+        let result = search_handle.join();
+        assert!(result.best_move.is_some());
+        assert!(result.depth >= 1);
+    }
+
+    #[test]
+    fn test_ponder_illegal_reply() {
+        let game_state = deserialize(FEN_STARTING_POS).expect("bad fen string!");
+        let expected_reply = ChessMove::create_normal(ChessPiece::WhitePawn, E2, E5);
+        assert!(SearchHandle::ponder(
+            &game_state,
+            &expected_reply,
+            SearchLimits::new(),
+            SearchOptions::new()
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,34 @@
+use std::time::Duration;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SearchLimits {
+    pub depth: Option<u8>,
+    pub nodes: Option<u64>,
+    pub move_time: Option<Duration>,
+}
+
+impl SearchLimits {
+    // no limits means the search runs until it is stopped
+    pub const fn new() -> Self {
+        Self {
+            depth: None,
+            nodes: None,
+            move_time: None,
+        }
+    }
+    pub const fn is_unlimited(&self) -> bool {
+        self.depth.is_none() && self.nodes.is_none() && self.move_time.is_none()
+    }
+    pub const fn max_depth(&self) -> u8 {
+        match self.depth {
+            Some(depth) => depth,
+            None => u8::MAX,
+        }
+    }
+}
+
+impl Default for SearchLimits {
+    fn default() -> Self {
+        Self::new()
+    }
+}
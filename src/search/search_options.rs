@@ -0,0 +1,55 @@
+use crate::book::polyglot_book::{BookSelection, PolyglotBook};
+use crate::search::skill_level::SkillLevel;
+use crate::search::transposition_table::DEFAULT_HASH_SIZE_MB;
+
+const DEFAULT_RANDOM_SEED: u64 = 0x5EED;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SearchOptions {
+    pub null_move_pruning: bool,
+    pub late_move_reductions: bool,
+    pub futility_pruning: bool,
+    pub razoring: bool,
+    pub check_extensions: bool,
+    pub threads: usize,
+    pub hash_size_mb: usize,
+    pub skill_level: Option<SkillLevel>,
+    pub random_seed: u64,
+    pub book_selection: BookSelection,
+    // consulted before searching, books are loaded once and kept for the whole run
+    pub book: Option<&'static PolyglotBook>,
+}
+
+impl SearchOptions {
+    pub const fn new() -> Self {
+        Self {
+            null_move_pruning: false,
+            late_move_reductions: false,
+            futility_pruning: false,
+            razoring: false,
+            check_extensions: false,
+            threads: 1,
+            hash_size_mb: DEFAULT_HASH_SIZE_MB,
+            skill_level: None,
+            random_seed: DEFAULT_RANDOM_SEED,
+            book_selection: BookSelection::Weighted,
+            book: None,
+        }
+    }
+    pub const fn selective() -> Self {
+        Self {
+            null_move_pruning: true,
+            late_move_reductions: true,
+            futility_pruning: true,
+            razoring: true,
+            check_extensions: true,
+            ..Self::new()
+        }
+    }
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
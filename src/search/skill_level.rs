@@ -0,0 +1,202 @@
+use crate::chess_move::chess_move::ChessMove;
+use crate::search::multi_pv::{analyse_with_options, AnalysisLine};
+use crate::search::search_limits::SearchLimits;
+use crate::search::search_options::SearchOptions;
+use crate::search::zobrist::zobrist_hash;
+use crate::state::game_state::GameState;
+use crate::utils::random::{split_mix_64, Random};
+
+const MIN_ELO: u16 = 1000;
+const ELO_PER_LEVEL: u16 = 75;
+const WEAK_MULTIPV: usize = 4;
+const BASE_NODE_LIMIT: u64 = 200;
+
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+pub struct SkillLevel(u8);
+
+impl SkillLevel {
+    pub const MIN: Self = Self(0);
+    pub const MAX: Self = Self(20);
+
+    pub const fn new(level: u8) -> Self {
+        if level > Self::MAX.0 {
+            Self::MAX
+        } else {
+            Self(level)
+        }
+    }
+    pub const fn from_elo(elo: u16) -> Self {
+        Self::new((elo.saturating_sub(MIN_ELO) / ELO_PER_LEVEL) as u8)
+    }
+    pub const fn as_elo(&self) -> u16 {
+        MIN_ELO + self.0 as u16 * ELO_PER_LEVEL
+    }
+    pub const fn as_u8(&self) -> u8 {
+        self.0
+    }
+    const fn weakness(&self) -> u8 {
+        Self::MAX.0 - self.0
+    }
+    pub const fn is_max(&self) -> bool {
+        self.0 == Self::MAX.0
+    }
+    pub const fn max_depth(&self) -> u8 {
+        1 + self.0 / 4
+    }
+    pub const fn node_limit(&self) -> Option<u64> {
+        if self.is_max() {
+            None
+        } else {
+            Some(BASE_NODE_LIMIT << (self.0 / 2))
+        }
+    }
+    // scores are doubled in evaluate_game_state, so a pawn is worth 2
+    pub const fn eval_noise(&self) -> i32 {
+        self.weakness() as i32 / 4
+    }
+    const fn score_tolerance(&self) -> i32 {
+        self.weakness() as i32 / 2
+    }
+    const fn multipv(&self) -> usize {
+        if self.is_max() {
+            1
+        } else {
+            WEAK_MULTIPV
+        }
+    }
+}
+
+impl Default for SkillLevel {
+    fn default() -> Self {
+        Self::MAX
+    }
+}
+
+// same noise for the same position, so transpositions agree with each other
+pub fn eval_noise(game_state: &GameState, skill_level: SkillLevel, seed: u64) -> i32 {
+    let amplitude = skill_level.eval_noise();
+    if amplitude == 0 {
+        return 0;
+    }
+    let (_, value) = split_mix_64(zobrist_hash(game_state) ^ seed);
+    (value % (amplitude as u64 * 2 + 1)) as i32 - amplitude
+}
+
+pub fn find_skill_limited_move(
+    game_state: &GameState,
+    depth: u8,
+    skill_level: SkillLevel,
+    options: SearchOptions,
+) -> Option<ChessMove> {
+    let options = SearchOptions {
+        skill_level: Some(skill_level),
+        ..options
+    };
+    let limits = SearchLimits {
+        depth: Some(depth.min(skill_level.max_depth())),
+        nodes: skill_level.node_limit(),
+        ..SearchLimits::new()
+    };
+    let lines = analyse_with_options(game_state, limits, skill_level.multipv(), Some(options));
+    let mut random = Random::new(options.random_seed ^ zobrist_hash(game_state));
+    pick_line(&lines, skill_level, &mut random)
+        .and_then(AnalysisLine::best_move)
+        .cloned()
+}
+
+// weighted towards the best line, anything outside the tolerance is never picked
+fn pick_line<'a>(
+    lines: &'a [AnalysisLine],
+    skill_level: SkillLevel,
+    random: &mut Random,
+) -> Option<&'a AnalysisLine> {
+    let best_score = lines.first()?.score;
+    let weights = lines
+        .iter()
+        .map(|line| {
+            let gap = best_score.saturating_sub(line.score);
+            (skill_level.score_tolerance() - gap).max(-1) as u64 + 1
+        })
+        .collect::<Vec<_>>();
+    let mut pick = random.next_below(weights.iter().sum());
+    for (line, weight) in lines.iter().zip(weights) {
+        if pick < weight {
+            return Some(line);
+        }
+        pick -= weight;
+    }
+    lines.first()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::position::*;
+    use crate::notation::fen::{deserialize, FEN_STARTING_POS};
+    use crate::search::lazy_smp::lazy_smp_search;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0, SkillLevel::MIN)]
+    #[case(1000, SkillLevel::MIN)]
+    #[case(1750, SkillLevel::new(10))]
+    #[case(2500, SkillLevel::MAX)]
+    #[case(u16::MAX, SkillLevel::MAX)]
+    fn test_from_elo(#[case] elo: u16, #[case] expected: SkillLevel) {
+        assert_eq!(expected, SkillLevel::from_elo(elo));
+    }
+
+    #[test]
+    fn test_max_skill_plays_best_move() {
+        let game_state =
+            deserialize("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1").expect("bad fen string!");
+        let options = SearchOptions::new();
+        let expected = lazy_smp_search(&game_state, 3, options).best_move;
+        let actual = find_skill_limited_move(&game_state, 3, SkillLevel::MAX, options);
+        assert_eq!(expected, actual);
+        let best_move = actual.expect("expected a move");
+        assert_eq!((A1, A8), (best_move.from, best_move.to));
+    }
+
+    #[test]
+    fn test_same_seed_is_reproducible() {
+        let game_state = deserialize(FEN_STARTING_POS).expect("bad fen string!");
+        let options = SearchOptions {
+            random_seed: 7,
+            ..SearchOptions::new()
+        };
+        let first = find_skill_limited_move(&game_state, 3, SkillLevel::MIN, options);
+        let second = find_skill_limited_move(&game_state, 3, SkillLevel::MIN, options);
+        assert!(first.is_some());
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_low_skill_varies_with_seed() {
+        let game_state = deserialize(FEN_STARTING_POS).expect("bad fen string!");
+        let moves = (0..8)
+            .map(|random_seed| {
+                let options = SearchOptions {
+                    random_seed,
+                    ..SearchOptions::new()
+                };
+                find_skill_limited_move(&game_state, 3, SkillLevel::MIN, options)
+            })
+            .collect::<Vec<_>>();
+        assert!(moves.iter().any(|chess_move| *chess_move != moves[0]));
+    }
+
+    #[rstest]
+    #[case(SkillLevel::MIN)]
+    #[case(SkillLevel::new(10))]
+    fn test_eval_noise_within_amplitude(#[case] skill_level: SkillLevel) {
+        let game_state = deserialize(FEN_STARTING_POS).expect("bad fen string!");
+        let amplitude = skill_level.eval_noise();
+        assert!(amplitude > 0);
+        for seed in 0..32 {
+            let noise = eval_noise(&game_state, skill_level, seed);
+            assert!((-amplitude..=amplitude).contains(&noise));
+        }
+        assert_eq!(0, eval_noise(&game_state, SkillLevel::MAX, 0));
+    }
+}
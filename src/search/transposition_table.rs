@@ -0,0 +1,201 @@
+use crate::board::board_position::BoardPosition;
+use crate::chess_move::chess_move::{ChessMove, ChessMoveType};
+use crate::piece::promotion_piece::PromotionPiece;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+pub const DEFAULT_HASH_SIZE_MB: usize = 4;
+const BYTES_PER_MB: usize = 1024 * 1024;
+const ENTRY_SIZE: usize = std::mem::size_of::<TranspositionTableEntry>();
+const NO_MOVE: u16 = u16::MAX;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Bound {
+    Exact,
+    Lower,
+    Upper,
+}
+
+impl Bound {
+    const fn as_bits(&self) -> u64 {
+        match self {
+            Self::Exact => 0,
+            Self::Lower => 1,
+            Self::Upper => 2,
+        }
+    }
+    const fn from_bits(bits: u64) -> Self {
+        match bits {
+            1 => Self::Lower,
+            2 => Self::Upper,
+            _ => Self::Exact,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PackedMove(u16);
+
+impl PackedMove {
+    pub const fn from_move(chess_move: &ChessMove) -> Self {
+        let promotion = match chess_move.move_type {
+            ChessMoveType::Promotion(PromotionPiece::Queen) => 1,
+            ChessMoveType::Promotion(PromotionPiece::Rook) => 2,
+            ChessMoveType::Promotion(PromotionPiece::Bishop) => 3,
+            ChessMoveType::Promotion(PromotionPiece::Knight) => 4,
+            _ => 0,
+        };
+        Self(
+            chess_move.from.as_pos_index() as u16
+                | (chess_move.to.as_pos_index() as u16) << 6
+                | promotion << 12,
+        )
+    }
+    pub fn matches(&self, chess_move: &ChessMove) -> bool {
+        *self == Self::from_move(chess_move)
+    }
+    pub fn from(&self) -> Option<BoardPosition> {
+        BoardPosition::from_pos_index((self.0 & 0x3F) as usize)
+    }
+    pub fn to(&self) -> Option<BoardPosition> {
+        BoardPosition::from_pos_index((self.0 >> 6 & 0x3F) as usize)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TranspositionTableHit {
+    pub depth: u8,
+    pub score: i32,
+    pub bound: Bound,
+    pub best_move: Option<PackedMove>,
+}
+
+impl TranspositionTableHit {
+    // score: 32 bits | depth: 8 bits | bound: 2 bits | move: 16 bits
+    const fn pack(&self) -> u64 {
+        let best_move = match self.best_move {
+            Some(PackedMove(packed)) => packed,
+            None => NO_MOVE,
+        };
+        (self.score as u32 as u64)
+            | (self.depth as u64) << 32
+            | self.bound.as_bits() << 40
+            | (best_move as u64) << 42
+    }
+    const fn unpack(data: u64) -> Self {
+        let best_move = (data >> 42 & 0xFFFF) as u16;
+        Self {
+            score: data as u32 as i32,
+            depth: (data >> 32 & 0xFF) as u8,
+            bound: Bound::from_bits(data >> 40 & 0b11),
+            best_move: if best_move == NO_MOVE {
+                None
+            } else {
+                Some(PackedMove(best_move))
+            },
+        }
+    }
+}
+
+#[derive(Default)]
+struct TranspositionTableEntry {
+    // stored as key ^ data so torn writes from other threads are detected on probe
+    checked_key: AtomicU64,
+    data: AtomicU64,
+}
+
+// lock-free so it can be shared between lazy smp threads without synchronization
+pub struct TranspositionTable {
+    entries: Vec<TranspositionTableEntry>,
+}
+
+impl TranspositionTable {
+    pub fn new(size_mb: usize) -> Self {
+        let entry_count = (size_mb.max(1) * BYTES_PER_MB / ENTRY_SIZE).max(1);
+        Self {
+            entries: (0..entry_count)
+                .map(|_| TranspositionTableEntry::default())
+                .collect(),
+        }
+    }
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+    fn entry(&self, key: u64) -> &TranspositionTableEntry {
+        &self.entries[(key % self.entries.len() as u64) as usize]
+    }
+    pub fn probe(&self, key: u64) -> Option<TranspositionTableHit> {
+        let entry = self.entry(key);
+        let checked_key = entry.checked_key.load(Ordering::Relaxed);
+        let data = entry.data.load(Ordering::Relaxed);
+        if checked_key ^ data != key || (checked_key == 0 && data == 0) {
+            return None;
+        }
+        Some(TranspositionTableHit::unpack(data))
+    }
+    pub fn store(&self, key: u64, hit: TranspositionTableHit) {
+        let entry = self.entry(key);
+        if let Some(existing) = self.probe(key) {
+            // keep deeper results for the same position
+            if existing.depth > hit.depth && hit.bound != Bound::Exact {
+                return;
+            }
+        }
+        let data = hit.pack();
+        entry.checked_key.store(key ^ data, Ordering::Relaxed);
+        entry.data.store(data, Ordering::Relaxed);
+    }
+    pub fn clear(&self) {
+        for entry in self.entries.iter() {
+            entry.checked_key.store(0, Ordering::Relaxed);
+            entry.data.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::position::*;
+    use crate::piece::chess_piece::ChessPiece;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(TranspositionTableHit { depth: 3, score: -42, bound: Bound::Upper, best_move: None })]
+    #[case(TranspositionTableHit {
+        depth: 255,
+        score: i32::MAX,
+        bound: Bound::Lower,
+        best_move: Some(PackedMove::from_move(&ChessMove::create_promotion(
+            ChessPiece::WhitePawn,
+            H7,
+            H8,
+            PromotionPiece::Knight,
+        ))),
+    })]
+    #[case(TranspositionTableHit { depth: 0, score: -i32::MAX, bound: Bound::Exact, best_move: None })]
+    fn test_store_and_probe(#[case] hit: TranspositionTableHit) {
+        let transposition_table = TranspositionTable::new(1);
+        let key = 0xDEAD_BEEF_u64;
+        assert_eq!(None, transposition_table.probe(key));
+        transposition_table.store(key, hit);
+        assert_eq!(Some(hit), transposition_table.probe(key));
+        // same slot, different key
+        let colliding_key = key + transposition_table.len() as u64;
+        assert_eq!(None, transposition_table.probe(colliding_key));
+        transposition_table.clear();
+        assert_eq!(None, transposition_table.probe(key));
+    }
+
+    #[test]
+    fn test_packed_move() {
+        let chess_move = ChessMove::create_normal(ChessPiece::WhiteKnight, G1, F3);
+        let packed_move = PackedMove::from_move(&chess_move);
+        assert!(packed_move.matches(&chess_move));
+        assert_eq!(Some(G1), packed_move.from());
+        assert_eq!(Some(F3), packed_move.to());
+        assert!(!packed_move.matches(&ChessMove::create_normal(ChessPiece::WhiteKnight, G1, H3)));
+    }
+}
@@ -0,0 +1,121 @@
+use crate::color::Color;
+use crate::direction::castle_side::CastleSide;
+use crate::piece::chess_piece::ChessPiece;
+use crate::state::game_state::GameState;
+use crate::utils::random::split_mix_64;
+
+const PIECE_KEYS: usize = 12 * 64;
+const BLACK_TO_MOVE_KEY: usize = PIECE_KEYS;
+const CASTLE_RIGHTS_KEYS: usize = BLACK_TO_MOVE_KEY + 1;
+const EN_PASSANT_FILE_KEYS: usize = CASTLE_RIGHTS_KEYS + 4;
+const KEY_COUNT: usize = EN_PASSANT_FILE_KEYS + 8;
+const SEED: u64 = 0x2545_F491_4F6C_DD1D;
+
+const fn generate_keys() -> [u64; KEY_COUNT] {
+    let mut keys = [0; KEY_COUNT];
+    let mut state = SEED;
+    let mut ix = 0;
+    while ix < KEY_COUNT {
+        let (next_state, key) = split_mix_64(state);
+        state = next_state;
+        keys[ix] = key;
+        ix += 1;
+    }
+    keys
+}
+
+static KEYS: [u64; KEY_COUNT] = generate_keys();
+
+const fn piece_index(chess_piece: ChessPiece) -> usize {
+    match chess_piece {
+        ChessPiece::WhitePawn => 0,
+        ChessPiece::WhiteKnight => 1,
+        ChessPiece::WhiteBishop => 2,
+        ChessPiece::WhiteRook => 3,
+        ChessPiece::WhiteQueen => 4,
+        ChessPiece::WhiteKing => 5,
+        ChessPiece::BlackPawn => 6,
+        ChessPiece::BlackKnight => 7,
+        ChessPiece::BlackBishop => 8,
+        ChessPiece::BlackRook => 9,
+        ChessPiece::BlackQueen => 10,
+        ChessPiece::BlackKing => 11,
+    }
+}
+
+const fn castle_rights_offset(color: Color) -> usize {
+    match color {
+        Color::White => 0,
+        Color::Black => 2,
+    }
+}
+
+pub fn zobrist_hash(game_state: &GameState) -> u64 {
+    let mut hash = 0;
+    for (pos, maybe_piece) in game_state.board.as_iter() {
+        let Some(piece) = maybe_piece else { continue };
+        hash ^= KEYS[piece_index(piece) * 64 + pos.as_pos_index()];
+    }
+    if game_state.active_color == Color::Black {
+        hash ^= KEYS[BLACK_TO_MOVE_KEY];
+    }
+    for color in [Color::White, Color::Black] {
+        let Some(castle_rights) = game_state.castle_rights.for_color(color) else {
+            continue;
+        };
+        let offset = CASTLE_RIGHTS_KEYS + castle_rights_offset(color);
+        if castle_rights.has(CastleSide::King) {
+            hash ^= KEYS[offset];
+        }
+        if castle_rights.has(CastleSide::Queen) {
+            hash ^= KEYS[offset + 1];
+        }
+    }
+    if let Some(en_passant_target_pos) = game_state.en_passant_target_pos {
+        hash ^= KEYS[EN_PASSANT_FILE_KEYS + en_passant_target_pos.file().as_zero_based_index()];
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::fen::{deserialize, FEN_STARTING_POS};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(FEN_STARTING_POS, FEN_STARTING_POS, true)]
+    // move counters don't change the position
+    #[case(
+        FEN_STARTING_POS,
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 5 20",
+        true
+    )]
+    #[case(
+        FEN_STARTING_POS,
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1",
+        false
+    )]
+    #[case(
+        FEN_STARTING_POS,
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w Kkq - 0 1",
+        false
+    )]
+    #[case(
+        "rnbqkbnr/ppp1pppp/8/3pP3/8/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 1",
+        "rnbqkbnr/ppp1pppp/8/3pP3/8/8/PPPP1PPP/RNBQKBNR w KQkq - 0 1",
+        false
+    )]
+    fn test_zobrist_hash(
+        #[case] fen_a: &'static str,
+        #[case] fen_b: &'static str,
+        #[case] expected_equal: bool,
+    ) {
+        let game_state_a = deserialize(fen_a).expect("bad fen string!");
+        let game_state_b = deserialize(fen_b).expect("bad fen string!");
+        assert_eq!(
+            expected_equal,
+            zobrist_hash(&game_state_a) == zobrist_hash(&game_state_b)
+        );
+    }
+}
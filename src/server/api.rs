@@ -0,0 +1,371 @@
+use crate::chess_move::chess_move::ChessMove;
+use crate::notation::coordinate::{deserialize_coordinate, serialize_coordinate};
+use crate::notation::fen::deserialize::FenParsingError;
+use crate::notation::fen::deserialize_strict;
+use crate::notation::san::{deserialize_san, legal_moves_with_status, serialize_san};
+use crate::server::session::{GameView, SessionId, SessionStore};
+use crate::state::evaluate_game_state::find_best_move_with_options;
+use crate::state::game_state::GameState;
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, MutexGuard};
+use thiserror::Error;
+
+pub const DEFAULT_BOT_DEPTH: u8 = 3;
+pub const MAX_BOT_DEPTH: u8 = 6;
+
+#[derive(Error, Debug, Clone)]
+pub enum ApiError {
+    #[error("Not found: {0}")]
+    NotFound(String),
+    #[error("Game not found: {0}")]
+    GameNotFound(SessionId),
+    #[error("Invalid request body: {0}")]
+    InvalidBody(String),
+    #[error("Invalid fen: {0}")]
+    InvalidFen(FenParsingError),
+    #[error("Illegal move: {0}")]
+    IllegalMove(String),
+    #[error("Invalid bot depth, expected 1..={MAX_BOT_DEPTH}, received: {0}")]
+    InvalidDepth(u8),
+    #[error("Game is over: {0}")]
+    GameOver(SessionId),
+    #[error("Game changed while the bot was thinking: {0}")]
+    GameChanged(SessionId),
+}
+
+impl ApiError {
+    pub const fn status_code(&self) -> u16 {
+        match self {
+            Self::NotFound(_) | Self::GameNotFound(_) => 404,
+            Self::GameOver(_) | Self::GameChanged(_) => 409,
+            Self::InvalidBody(_) | Self::InvalidFen(_) | Self::IllegalMove(_) => 400,
+            Self::InvalidDepth(_) => 400,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ApiResponse {
+    pub status_code: u16,
+    pub body: String,
+}
+
+#[derive(Deserialize, Default)]
+struct CreateGameRequest {
+    fen: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct MoveRequest {
+    // san or coordinate notation
+    #[serde(rename = "move")]
+    chess_move: String,
+}
+
+#[derive(Deserialize, Default)]
+struct BotMoveRequest {
+    depth: Option<u8>,
+}
+
+#[derive(Serialize)]
+struct LegalMove {
+    san: String,
+    coordinate: String,
+}
+
+#[derive(Serialize)]
+struct MoveResponse {
+    san: String,
+    game: GameView,
+}
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+fn parse_body<'a, T: Deserialize<'a> + Default>(body: &'a str) -> Result<T, ApiError> {
+    if body.trim().is_empty() {
+        return Ok(T::default());
+    }
+    serde_json::from_str(body).map_err(|err| ApiError::InvalidBody(err.to_string()))
+}
+
+fn to_json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value).expect("api responses always serialize")
+}
+
+fn parse_move(game_state: &GameState, s: &str) -> Result<ChessMove, ApiError> {
+    deserialize_san(game_state, s)
+        .or_else(|_| deserialize_coordinate(game_state, s))
+        .map_err(|_| ApiError::IllegalMove(s.to_string()))
+}
+
+fn parse_session_id(s: &str) -> Result<SessionId, ApiError> {
+    s.parse()
+        .map_err(|_| ApiError::NotFound(format!("/games/{s}")))
+}
+
+// the json pushed to websocket subscribers after every change
+pub fn game_update(store: &SessionStore, id: SessionId) -> Option<String> {
+    store.get(id).map(|session| to_json(&session.view(id)))
+}
+
+fn notify(store: &mut SessionStore, id: SessionId) {
+    if let Some(update) = game_update(store, id) {
+        if let Some(session) = store.get_mut(id) {
+            session.notify(&update);
+        }
+    }
+}
+
+fn play_move(
+    store: &mut SessionStore,
+    id: SessionId,
+    chess_move: impl FnOnce(&GameState) -> Result<ChessMove, ApiError>,
+) -> Result<String, ApiError> {
+    let session = store.get_mut(id).ok_or(ApiError::GameNotFound(id))?;
+    if session.game_state.game_status.is_game_over() {
+        return Err(ApiError::GameOver(id));
+    }
+    let chess_move = chess_move(&session.game_state)?;
+    let san = session
+        .play(&chess_move)
+        .map_err(|err| ApiError::IllegalMove(err.to_string()))?;
+    let response = to_json(&MoveResponse {
+        san,
+        game: session.view(id),
+    });
+    notify(store, id);
+    Ok(response)
+}
+
+fn lock(store: &Mutex<SessionStore>) -> MutexGuard<'_, SessionStore> {
+    store.lock().expect("session store lock poisoned")
+}
+
+// the move is only played if nothing else was played since the search started
+fn play_searched_move(
+    store: &mut SessionStore,
+    id: SessionId,
+    searched_at_ply: usize,
+    chess_move: ChessMove,
+) -> Result<String, ApiError> {
+    let session = store.get(id).ok_or(ApiError::GameNotFound(id))?;
+    if session.moves.len() != searched_at_ply {
+        return Err(ApiError::GameChanged(id));
+    }
+    play_move(store, id, |_| Ok(chess_move))
+}
+
+// the store is only locked to read the position and to play the move, so a deep search doesn't
+// hold up every other session and websocket
+fn bot_move(store: &Mutex<SessionStore>, id: SessionId, body: &str) -> Result<String, ApiError> {
+    let request = parse_body::<BotMoveRequest>(body)?;
+    let depth = request.depth.unwrap_or(DEFAULT_BOT_DEPTH);
+    if !(1..=MAX_BOT_DEPTH).contains(&depth) {
+        return Err(ApiError::InvalidDepth(depth));
+    }
+    let (game_state, searched_at_ply, bot_options) = {
+        let store = lock(store);
+        let session = store.get(id).ok_or(ApiError::GameNotFound(id))?;
+        if session.game_state.game_status.is_game_over() {
+            return Err(ApiError::GameOver(id));
+        }
+        (
+            session.game_state.clone(),
+            session.moves.len(),
+            store.bot_options(),
+        )
+    };
+    let chess_move = find_best_move_with_options(&game_state, depth, Some(bot_options))
+        .map_err(|err| ApiError::IllegalMove(err.into()))?;
+    play_searched_move(&mut lock(store), id, searched_at_ply, chess_move)
+}
+
+fn route(
+    store: &Mutex<SessionStore>,
+    method: &str,
+    path: &str,
+    body: &str,
+) -> Result<(u16, String), ApiError> {
+    let segments = path
+        .split('?')
+        .next()
+        .unwrap_or_default()
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>();
+    let not_found = || ApiError::NotFound(format!("{method} {path}"));
+    if let ("POST", ["games", id, "bot"]) = (method, segments.as_slice()) {
+        return Ok((200, bot_move(store, parse_session_id(id)?, body)?));
+    }
+    let mut store = lock(store);
+    let store = &mut *store;
+    let (id, rest) = match segments.as_slice() {
+        ["games"] if method == "POST" => {
+            let request = parse_body::<CreateGameRequest>(body)?;
+            let game_state = match request.fen {
+                Some(fen) => deserialize_strict(&fen).map_err(ApiError::InvalidFen)?,
+                None => GameState::new(),
+            };
+            let id = store.create(game_state);
+            let view = store.get(id).expect("session was just created").view(id);
+            return Ok((201, to_json(&view)));
+        }
+        ["games", id, rest @ ..] => (parse_session_id(id)?, rest),
+        _ => return Err(not_found()),
+    };
+    let session = store.get(id).ok_or(ApiError::GameNotFound(id))?;
+    let body = match (method, rest) {
+        ("GET", []) => to_json(&session.view(id)),
+        ("DELETE", []) => {
+            store.remove(id);
+            return Ok((204, String::new()));
+        }
+        ("GET", ["fen"]) => to_json(&serde_json::json!({ "fen": session.view(id).fen })),
+        ("GET", ["pgn"]) => to_json(&serde_json::json!({ "pgn": session.pgn() })),
+        ("GET", ["moves"]) => {
+            let game_state = &session.game_state;
+            let moves = legal_moves_with_status(game_state)
+                .iter()
+                .map(|(chess_move, _)| LegalMove {
+                    san: serialize_san(game_state, chess_move),
+                    coordinate: serialize_coordinate(chess_move),
+                })
+                .collect::<Vec<_>>();
+            to_json(&serde_json::json!({ "moves": moves }))
+        }
+        ("POST", ["moves"]) => {
+            let request = serde_json::from_str::<MoveRequest>(body)
+                .map_err(|err| ApiError::InvalidBody(err.to_string()))?;
+            play_move(store, id, |game_state| {
+                parse_move(game_state, &request.chess_move)
+            })?
+        }
+        _ => return Err(not_found()),
+    };
+    Ok((200, body))
+}
+
+pub fn handle_request(
+    store: &Mutex<SessionStore>,
+    method: &str,
+    path: &str,
+    body: &str,
+) -> ApiResponse {
+    match route(store, method, path, body) {
+        Ok((status_code, body)) => ApiResponse { status_code, body },
+        Err(err) => ApiResponse {
+            status_code: err.status_code(),
+            body: to_json(&ErrorResponse {
+                error: err.to_string(),
+            }),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use serde_json::Value;
+
+    fn request(store: &Mutex<SessionStore>, method: &str, path: &str, body: &str) -> (u16, Value) {
+        let response = handle_request(store, method, path, body);
+        let value = serde_json::from_str(&response.body).unwrap_or(Value::Null);
+        (response.status_code, value)
+    }
+
+    #[test]
+    fn test_game_session_flow() {
+        let store = Mutex::new(SessionStore::new());
+        let (status_code, game) = request(&store, "POST", "/games", "");
+        assert_eq!(201, status_code);
+        assert_eq!(1, game["id"]);
+        let (_, moves) = request(&store, "GET", "/games/1/moves", "");
+        assert_eq!(20, moves["moves"].as_array().unwrap().len());
+        let (status_code, played) =
+            request(&store, "POST", "/games/1/moves", r#"{"move": "e2e4"}"#);
+        assert_eq!(200, status_code);
+        assert_eq!("e4", played["san"]);
+        let (status_code, played) = request(&store, "POST", "/games/1/bot", r#"{"depth": 1}"#);
+        assert_eq!(200, status_code);
+        assert_eq!("White", played["game"]["active_color"]);
+        let (_, pgn) = request(&store, "GET", "/games/1/pgn", "");
+        assert!(pgn["pgn"].as_str().unwrap().starts_with("1. e4 "));
+        let (status_code, _) = request(&store, "DELETE", "/games/1", "");
+        assert_eq!(204, status_code);
+        assert!(lock(&store).is_empty());
+    }
+
+    #[test]
+    fn test_create_game_from_fen() {
+        let store = Mutex::new(SessionStore::new());
+        let fen = "6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1";
+        let (_, game) = request(&store, "POST", "/games", &format!(r#"{{"fen": "{fen}"}}"#));
+        assert_eq!(fen, game["fen"]);
+        let (_, played) = request(&store, "POST", "/games/1/moves", r#"{"move": "Ra8#"}"#);
+        assert_eq!(
+            serde_json::json!({ "CheckMate": "Black" }),
+            played["game"]["status"]
+        );
+        let (status_code, _) = request(&store, "POST", "/games/1/bot", "");
+        assert_eq!(409, status_code);
+        let (_, pgn) = request(&store, "GET", "/games/1/pgn", "");
+        assert!(pgn["pgn"]
+            .as_str()
+            .unwrap()
+            .contains(&format!("[SetUp \"1\"]\n[FEN \"{fen}\"]")));
+    }
+
+    #[rstest]
+    #[case("GET", "/games/7", "", 404)]
+    #[case("GET", "/nowhere", "", 404)]
+    #[case("POST", "/games", r#"{"fen": "8/8 w"}"#, 400)]
+    #[case("POST", "/games", "not json", 400)]
+    #[case("POST", "/games/1/moves", r#"{"move": "e5"}"#, 400)]
+    #[case("POST", "/games/1/bot", r#"{"depth": 0}"#, 400)]
+    fn test_errors(
+        #[case] method: &str,
+        #[case] path: &str,
+        #[case] body: &str,
+        #[case] expected: u16,
+    ) {
+        let store = Mutex::new(SessionStore::new());
+        lock(&store).create(GameState::new());
+        let (status_code, error) = request(&store, method, path, body);
+        assert_eq!(expected, status_code);
+        assert!(error["error"].is_string());
+    }
+
+    #[test]
+    fn test_updates_are_pushed_to_subscribers() {
+        let store = Mutex::new(SessionStore::new());
+        let id = lock(&store).create(GameState::new());
+        let (sender, receiver) = std::sync::mpsc::channel();
+        lock(&store).get_mut(id).unwrap().subscribe(sender);
+        request(&store, "POST", "/games/1/moves", r#"{"move": "d4"}"#);
+        let update: Value = serde_json::from_str(&receiver.try_recv().unwrap()).unwrap();
+        assert_eq!(serde_json::json!(["d4"]), update["moves"]);
+    }
+
+    #[test]
+    fn test_stale_bot_move_is_rejected() {
+        let mut store = SessionStore::new();
+        let id = store.create(GameState::new());
+        let game_state = GameState::new();
+        let chess_move = find_best_move_with_options(&game_state, 1, None).expect("a move");
+        let d4 = deserialize_san(&game_state, "d4").expect("legal move");
+        play_move(&mut store, id, |_| Ok(d4)).expect("legal move");
+        assert!(matches!(
+            play_searched_move(&mut store, id, 0, chess_move.clone()),
+            Err(ApiError::GameChanged(_))
+        ));
+        store.remove(id);
+        assert!(matches!(
+            play_searched_move(&mut store, id, 1, chess_move),
+            Err(ApiError::GameNotFound(_))
+        ));
+    }
+}
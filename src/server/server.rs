@@ -0,0 +1,222 @@
+use crate::search::search_options::SearchOptions;
+use crate::server::api::{game_update, handle_request};
+use crate::server::session::{SessionId, SessionStore};
+use std::net::SocketAddr;
+use std::sync::mpsc::channel;
+use std::sync::{Arc, Mutex};
+use thiserror::Error;
+use tiny_http::{Header, Request, Response, Server};
+use tungstenite::handshake::derive_accept_key;
+use tungstenite::protocol::Role;
+use tungstenite::{Message, WebSocket};
+
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:8080";
+const WEBSOCKET_PROTOCOL: &str = "websocket";
+
+#[derive(Error, Debug)]
+pub enum ServerError {
+    #[error("Failed to bind {0}: {1}")]
+    Bind(String, String),
+}
+
+pub struct ApiServer {
+    server: Arc<Server>,
+    store: Arc<Mutex<SessionStore>>,
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name.as_bytes(), value.as_bytes()).expect("valid header")
+}
+
+fn request_header<'a>(request: &'a Request, name: &str) -> Option<&'a str> {
+    request
+        .headers()
+        .iter()
+        .find(|header| header.field.as_str().as_str().eq_ignore_ascii_case(name))
+        .map(|header| header.value.as_str())
+}
+
+// GET /games/{id}/ws
+fn websocket_session_id(request: &Request) -> Option<SessionId> {
+    let is_upgrade = request_header(request, "Upgrade")
+        .is_some_and(|value| value.eq_ignore_ascii_case(WEBSOCKET_PROTOCOL));
+    let segments = request
+        .url()
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>();
+    match segments.as_slice() {
+        ["games", id, "ws"] if is_upgrade => id.parse().ok(),
+        _ => None,
+    }
+}
+
+fn respond_json(request: Request, status_code: u16, body: String) {
+    let response = Response::from_string(body)
+        .with_status_code(status_code)
+        .with_header(header("Content-Type", "application/json"));
+    // the client hanging up early isn't the server's problem
+    let _ = request.respond(response);
+}
+
+fn handle_api_request(store: &Mutex<SessionStore>, mut request: Request) {
+    let mut body = String::new();
+    if request.as_reader().read_to_string(&mut body).is_err() {
+        respond_json(
+            request,
+            400,
+            r#"{"error":"Invalid request body"}"#.to_string(),
+        );
+        return;
+    }
+    let method = request.method().as_str().to_string();
+    let response = handle_request(store, &method, request.url(), &body);
+    respond_json(request, response.status_code, response.body);
+}
+
+// sends the current state straight away, then every update until either side hangs up
+fn handle_websocket(store: &Mutex<SessionStore>, request: Request, id: SessionId) {
+    let Some(key) = request_header(&request, "Sec-WebSocket-Key") else {
+        respond_json(
+            request,
+            400,
+            r#"{"error":"Missing Sec-WebSocket-Key"}"#.to_string(),
+        );
+        return;
+    };
+    let accept_key = derive_accept_key(key.as_bytes());
+    let (sender, receiver) = channel();
+    {
+        let mut store = store.lock().expect("session store lock poisoned");
+        let Some(update) = game_update(&store, id) else {
+            drop(store);
+            respond_json(
+                request,
+                404,
+                format!(r#"{{"error":"Game not found: {id}"}}"#),
+            );
+            return;
+        };
+        let session = store.get_mut(id).expect("session exists");
+        let _ = sender.send(update);
+        session.subscribe(sender);
+    }
+    let response = Response::empty(101).with_header(header("Sec-WebSocket-Accept", &accept_key));
+    let stream = request.upgrade(WEBSOCKET_PROTOCOL, response);
+    let mut websocket = WebSocket::from_raw_socket(stream, Role::Server, None);
+    for update in receiver {
+        if websocket.send(Message::Text(update)).is_err() {
+            break;
+        }
+    }
+    // the session was deleted, so the sender side went away
+    let _ = websocket.close(None);
+}
+
+impl ApiServer {
+    pub fn bind(address: &str, bot_options: Option<SearchOptions>) -> Result<Self, ServerError> {
+        let server = Server::http(address)
+            .map_err(|err| ServerError::Bind(address.to_string(), err.to_string()))?;
+        Ok(Self {
+            server: Arc::new(server),
+            store: Arc::new(Mutex::new(SessionStore::with_bot_options(
+                bot_options.unwrap_or_default(),
+            ))),
+        })
+    }
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.server.server_addr().to_ip()
+    }
+    // blocks until unblock is called, every request gets its own thread so bot moves don't stall websockets
+    pub fn run(&self) {
+        for request in self.server.incoming_requests() {
+            let store = Arc::clone(&self.store);
+            std::thread::spawn(move || match websocket_session_id(&request) {
+                Some(id) => handle_websocket(&store, request, id),
+                None => handle_api_request(&store, request),
+            });
+        }
+    }
+    pub fn unblock(&self) {
+        self.server.unblock();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+    use std::thread::JoinHandle;
+
+    fn start() -> (Arc<ApiServer>, SocketAddr, JoinHandle<()>) {
+        let server = Arc::new(ApiServer::bind("127.0.0.1:0", None).expect("failed to bind"));
+        let addr = server.local_addr().expect("bound to an ip");
+        let runner = Arc::clone(&server);
+        let handle = std::thread::spawn(move || runner.run());
+        (server, addr, handle)
+    }
+
+    fn http(addr: SocketAddr, method: &str, path: &str, body: &str) -> (u16, Value) {
+        let mut stream = TcpStream::connect(addr).expect("failed to connect");
+        write!(
+            stream,
+            "{method} {path} HTTP/1.1\r\nHost: {addr}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )
+        .expect("failed to send request");
+        let mut response = String::new();
+        stream
+            .read_to_string(&mut response)
+            .expect("failed to read response");
+        let status_code = response[9..12].parse().expect("bad status line");
+        let (_, body) = response.split_once("\r\n\r\n").expect("bad response");
+        (
+            status_code,
+            serde_json::from_str(body).unwrap_or(Value::Null),
+        )
+    }
+
+    fn next_update(websocket: &mut WebSocket<impl Read + Write>) -> Value {
+        match websocket.read().expect("failed to read update") {
+            Message::Text(text) => serde_json::from_str(&text).expect("bad update json"),
+            message => panic!("unexpected message: {message:?}"),
+        }
+    }
+
+    #[test]
+    fn test_local_client() {
+        let (server, addr, handle) = start();
+        let (status_code, game) = http(addr, "POST", "/games", "{}");
+        assert_eq!(201, status_code);
+        let id = game["id"].as_u64().unwrap();
+        let (mut websocket, _) = tungstenite::connect(format!("ws://{addr}/games/{id}/ws"))
+            .expect("failed to connect websocket");
+        assert_eq!(serde_json::json!([]), next_update(&mut websocket)["moves"]);
+
+        let (status_code, played) = http(
+            addr,
+            "POST",
+            &format!("/games/{id}/moves"),
+            r#"{"move":"e4"}"#,
+        );
+        assert_eq!(200, status_code);
+        assert_eq!("e4", played["san"]);
+        assert_eq!(
+            serde_json::json!(["e4"]),
+            next_update(&mut websocket)["moves"]
+        );
+
+        let (_, fen) = http(addr, "GET", &format!("/games/{id}/fen"), "");
+        assert_eq!(
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+            fen["fen"]
+        );
+        let (status_code, _) = http(addr, "GET", "/games/99/ws", "");
+        assert_eq!(404, status_code);
+
+        server.unblock();
+        handle.join().expect("server thread panicked");
+    }
+}
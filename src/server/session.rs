@@ -0,0 +1,115 @@
+use crate::chess_move::chess_move::ChessMove;
+use crate::chess_move::chess_move_handler::default_chess_move_handler;
+use crate::chess_move::invalid_chess_move_error::InvalidChessMoveError;
+use crate::color::Color;
+use crate::notation::fen::serialize;
+use crate::notation::pgn::{PgnGame, PgnResult};
+use crate::notation::san::serialize_san;
+use crate::search::search_options::SearchOptions;
+use crate::state::game_state::GameState;
+use crate::state::game_status::GameStatus;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::mpsc::Sender;
+
+pub type SessionId = u64;
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct GameView {
+    pub id: SessionId,
+    pub fen: String,
+    pub active_color: Color,
+    pub status: GameStatus,
+    pub moves: Vec<String>,
+}
+
+pub struct GameSession {
+    pub start: GameState,
+    pub game_state: GameState,
+    // san of every move played since the session started
+    pub moves: Vec<String>,
+    subscribers: Vec<Sender<String>>,
+}
+
+impl GameSession {
+    pub fn new(game_state: GameState) -> Self {
+        Self {
+            start: game_state.clone(),
+            game_state,
+            moves: vec![],
+            subscribers: vec![],
+        }
+    }
+    pub fn play(&mut self, chess_move: &ChessMove) -> Result<String, InvalidChessMoveError> {
+        let san = serialize_san(&self.game_state, chess_move);
+        default_chess_move_handler(&mut self.game_state, chess_move, None)?;
+        self.moves.push(san.clone());
+        Ok(san)
+    }
+    pub fn view(&self, id: SessionId) -> GameView {
+        GameView {
+            id,
+            fen: serialize(&self.game_state).get_str().to_string(),
+            active_color: self.game_state.active_color,
+            status: self.game_state.game_status,
+            moves: self.moves.clone(),
+        }
+    }
+    pub fn pgn(&self) -> String {
+        let mut game = PgnGame::new();
+        game.set_movetext(&self.start, self.moves.clone());
+        game.result = PgnResult::from_game_status(self.game_state.game_status);
+        crate::notation::pgn::serialize(&game)
+    }
+    pub fn subscribe(&mut self, subscriber: Sender<String>) {
+        self.subscribers.push(subscriber);
+    }
+    // subscribers that hung up are dropped on the next update
+    pub fn notify(&mut self, message: &str) {
+        self.subscribers
+            .retain(|subscriber| subscriber.send(message.to_string()).is_ok());
+    }
+}
+
+#[derive(Default)]
+pub struct SessionStore {
+    sessions: HashMap<SessionId, GameSession>,
+    next_id: SessionId,
+    bot_options: SearchOptions,
+}
+
+impl SessionStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn with_bot_options(bot_options: SearchOptions) -> Self {
+        Self {
+            bot_options,
+            ..Self::default()
+        }
+    }
+    pub const fn bot_options(&self) -> SearchOptions {
+        self.bot_options
+    }
+    pub fn create(&mut self, game_state: GameState) -> SessionId {
+        self.next_id += 1;
+        self.sessions
+            .insert(self.next_id, GameSession::new(game_state));
+        self.next_id
+    }
+    pub fn get(&self, id: SessionId) -> Option<&GameSession> {
+        self.sessions.get(&id)
+    }
+    pub fn get_mut(&mut self, id: SessionId) -> Option<&mut GameSession> {
+        self.sessions.get_mut(&id)
+    }
+    pub fn remove(&mut self, id: SessionId) -> Option<GameSession> {
+        self.sessions.remove(&id)
+    }
+    pub fn len(&self) -> usize {
+        self.sessions.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty()
+    }
+}
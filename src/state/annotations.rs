@@ -0,0 +1,205 @@
+use std::time::Duration;
+
+// from white's point of view
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Evaluation {
+    Centipawns(i32),
+    // moves until mate, negative when black mates
+    Mate(i32),
+}
+
+impl Evaluation {
+    // the pgn %eval format, pawns with two decimals or #n for a mate
+    pub fn as_pgn(&self) -> String {
+        match self {
+            Self::Centipawns(centipawns) => {
+                let sign = if *centipawns < 0 { "-" } else { "" };
+                let centipawns = centipawns.unsigned_abs();
+                format!("{sign}{}.{:02}", centipawns / 100, centipawns % 100)
+            }
+            Self::Mate(moves) => format!("#{moves}"),
+        }
+    }
+    pub fn try_from_pgn(s: &str) -> Option<Self> {
+        let s = s.trim();
+        if let Some(moves) = s.strip_prefix('#') {
+            return moves.parse().ok().map(Self::Mate);
+        }
+        let pawns: f64 = s.parse().ok()?;
+        pawns
+            .is_finite()
+            .then(|| Self::Centipawns((pawns * 100.0).round() as i32))
+    }
+}
+
+// the pgn %clk format, h:mm:ss with optional fractions of a second
+pub fn format_clock(clock: Duration) -> String {
+    let secs = clock.as_secs();
+    let mut s = format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60);
+    if clock.subsec_millis() > 0 {
+        let millis = format!("{:03}", clock.subsec_millis());
+        s.push('.');
+        s.push_str(millis.trim_end_matches('0'));
+    }
+    s
+}
+
+pub fn parse_clock(s: &str) -> Option<Duration> {
+    let mut parts = s.trim().rsplit(':');
+    let secs: f64 = parts.next()?.parse().ok()?;
+    let mut total = secs;
+    for (scale, part) in [60.0, 3600.0].into_iter().zip(parts.by_ref()) {
+        total += part.parse::<u64>().ok()? as f64 * scale;
+    }
+    if parts.next().is_some() {
+        return None;
+    }
+    (secs.is_finite() && secs >= 0.0).then(|| Duration::from_secs_f64(total))
+}
+
+// move suffixes and the numeric annotation glyphs they stand for
+pub fn nag_from_suffix(suffix: &str) -> Option<u8> {
+    Some(match suffix {
+        "!" => 1,
+        "?" => 2,
+        "!!" => 3,
+        "??" => 4,
+        "!?" => 5,
+        "?!" => 6,
+        _ => return None,
+    })
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Annotations {
+    // shown before the move, usually at the start of a variation
+    pub pre_comment: Option<String>,
+    pub comment: Option<String>,
+    pub nags: Vec<u8>,
+    // time left after the move
+    pub clock: Option<Duration>,
+    pub eval: Option<Evaluation>,
+}
+
+impl Annotations {
+    pub const fn new() -> Self {
+        Self {
+            pre_comment: None,
+            comment: None,
+            nags: Vec::new(),
+            clock: None,
+            eval: None,
+        }
+    }
+    pub fn is_empty(&self) -> bool {
+        *self == Self::new()
+    }
+    // pulls the [%clk] and [%eval] commands out of a pgn comment, anything else is kept as text
+    pub fn add_pgn_comment(&mut self, comment: &str) {
+        let mut text = String::new();
+        let mut rest = comment;
+        while let Some(start) = rest.find("[%") {
+            let Some(len) = rest[start..].find(']') else {
+                break;
+            };
+            let command = &rest[start + 2..start + len];
+            let (name, value) = command
+                .split_once(char::is_whitespace)
+                .unwrap_or((command, ""));
+            let parsed = match name {
+                "clk" => parse_clock(value).map(|clock| self.clock = Some(clock)),
+                "eval" => Evaluation::try_from_pgn(value).map(|eval| self.eval = Some(eval)),
+                _ => None,
+            };
+            text.push_str(&rest[..start]);
+            if parsed.is_none() {
+                text.push_str(&rest[start..=start + len]);
+            }
+            rest = &rest[start + len + 1..];
+        }
+        text.push_str(rest);
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if text.is_empty() {
+            return;
+        }
+        self.comment = Some(match self.comment.take() {
+            Some(existing) => format!("{existing} {text}"),
+            None => text,
+        });
+    }
+    // the comment to write after the move, with the commands ahead of the text
+    pub fn pgn_comment(&self) -> Option<String> {
+        let mut parts = vec![];
+        if let Some(eval) = self.eval {
+            parts.push(format!("[%eval {}]", eval.as_pgn()));
+        }
+        if let Some(clock) = self.clock {
+            parts.push(format!("[%clk {}]", format_clock(clock)));
+        }
+        parts.extend(self.comment.clone());
+        (!parts.is_empty()).then(|| parts.join(" "))
+    }
+}
+
+impl Default for Annotations {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("0.17", Evaluation::Centipawns(17))]
+    #[case("-1.50", Evaluation::Centipawns(-150))]
+    #[case("-0.05", Evaluation::Centipawns(-5))]
+    #[case("#3", Evaluation::Mate(3))]
+    #[case("#-2", Evaluation::Mate(-2))]
+    fn test_evaluation(#[case] s: &str, #[case] expected: Evaluation) {
+        assert_eq!(Some(expected), Evaluation::try_from_pgn(s));
+        assert_eq!(s, expected.as_pgn());
+    }
+
+    #[rstest]
+    #[case("0:00:09", Duration::from_secs(9))]
+    #[case("1:05:12", Duration::from_secs(3912))]
+    #[case("0:00:09.5", Duration::from_millis(9500))]
+    fn test_clock(#[case] s: &str, #[case] expected: Duration) {
+        assert_eq!(Some(expected), parse_clock(s));
+        assert_eq!(s, format_clock(expected));
+    }
+
+    #[rstest]
+    #[case("1:00:00:09")]
+    #[case("0:0a:09")]
+    #[case("-1")]
+    fn test_invalid_clock(#[case] s: &str) {
+        assert_eq!(None, parse_clock(s));
+    }
+
+    #[test]
+    fn test_add_pgn_comment() {
+        let mut annotations = Annotations::new();
+        annotations.add_pgn_comment(" [%eval -0.31] [%clk 0:02:58]  a bit passive [%csl Gd4] ");
+        assert_eq!(Some(Evaluation::Centipawns(-31)), annotations.eval);
+        assert_eq!(Some(Duration::from_secs(178)), annotations.clock);
+        assert_eq!(
+            Some("a bit passive [%csl Gd4]"),
+            annotations.comment.as_deref()
+        );
+        assert_eq!(
+            Some("[%eval -0.31] [%clk 0:02:58] a bit passive [%csl Gd4]".to_string()),
+            annotations.pgn_comment()
+        );
+        annotations.add_pgn_comment("[%clk never]");
+        assert_eq!(
+            Some("a bit passive [%csl Gd4] [%clk never]"),
+            annotations.comment.as_deref()
+        );
+        assert!(Annotations::new().is_empty());
+        assert_eq!(None, Annotations::new().pgn_comment());
+    }
+}
@@ -1,81 +1,139 @@
-use crate::color::Color;
+use crate::board::board_file::BoardFile;
 use crate::direction::castle_side::CastleSide;
 use thiserror::Error;
 
 #[derive(Error, Debug, Clone)]
 pub enum CastleRightsStringParseError {
-    #[error("Invalid castle rights string, expects to be any combination of K | q | Q | q | KQ | kq, received: {0}")]
+    #[error("Invalid castle rights string, expects to be - or any combination of K | Q | k | q | A-H | a-h, received: {0}")]
     InvalidCastleRightsString(String),
+    #[error("Castle rights rook file {0} is on the same file as the king")]
+    RookFileOnKingFile(char),
 }
 
-#[derive(Clone, Copy, Debug)]
-pub enum CastleRights {
-    KingSideOnly,
-    QueenSideOnly,
-    Both,
+// the rook files are kept so chess960 rights can say which rook castles
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CastleRights {
+    king_side: Option<BoardFile>,
+    queen_side: Option<BoardFile>,
 }
 
 impl CastleRights {
+    pub const fn standard_rook_file(castle_side: CastleSide) -> BoardFile {
+        match castle_side {
+            CastleSide::King => BoardFile::H,
+            CastleSide::Queen => BoardFile::A,
+        }
+    }
+    pub const fn both() -> Self {
+        Self {
+            king_side: Some(Self::standard_rook_file(CastleSide::King)),
+            queen_side: Some(Self::standard_rook_file(CastleSide::Queen)),
+        }
+    }
     pub const fn from_castle_side(castle_side: CastleSide) -> Self {
+        Self::from_rook_file(castle_side, Self::standard_rook_file(castle_side))
+    }
+    pub const fn from_rook_file(castle_side: CastleSide, rook_file: BoardFile) -> Self {
         match castle_side {
-            CastleSide::King => CastleRights::KingSideOnly,
-            CastleSide::Queen => CastleRights::QueenSideOnly,
+            CastleSide::King => Self {
+                king_side: Some(rook_file),
+                queen_side: None,
+            },
+            CastleSide::Queen => Self {
+                king_side: None,
+                queen_side: Some(rook_file),
+            },
         }
     }
-    pub const fn has(&self, check_castle_rights: CastleRights) -> bool {
-        match (self, check_castle_rights) {
-            (Self::Both, _) => true,
-            (Self::KingSideOnly, Self::KingSideOnly) => true,
-            (Self::QueenSideOnly, Self::QueenSideOnly) => true,
-            (_, _) => false,
+    pub const fn rook_file(&self, castle_side: CastleSide) -> Option<BoardFile> {
+        match castle_side {
+            CastleSide::King => self.king_side,
+            CastleSide::Queen => self.queen_side,
         }
     }
-    pub const fn without(&self, without_castle_rights: CastleRights) -> Option<Self> {
-        Some(match (without_castle_rights, self) {
-            (Self::KingSideOnly, Self::Both | Self::QueenSideOnly) => CastleRights::QueenSideOnly,
-            (Self::QueenSideOnly, Self::Both | Self::KingSideOnly) => CastleRights::KingSideOnly,
-            (Self::KingSideOnly, Self::KingSideOnly) => return None,
-            (Self::QueenSideOnly, Self::QueenSideOnly) => return None,
-            (Self::Both, _) => return None,
-        })
+    pub const fn has(&self, castle_side: CastleSide) -> bool {
+        self.rook_file(castle_side).is_some()
+    }
+    pub fn castle_side_for_rook_file(&self, rook_file: BoardFile) -> Option<CastleSide> {
+        [CastleSide::King, CastleSide::Queen]
+            .into_iter()
+            .find(|&castle_side| self.rook_file(castle_side) == Some(rook_file))
+    }
+    pub const fn without(&self, castle_side: CastleSide) -> Option<Self> {
+        let castle_rights = match castle_side {
+            CastleSide::King => Self {
+                king_side: None,
+                ..*self
+            },
+            CastleSide::Queen => Self {
+                queen_side: None,
+                ..*self
+            },
+        };
+        match castle_rights {
+            Self {
+                king_side: None,
+                queen_side: None,
+            } => None,
+            castle_rights => Some(castle_rights),
+        }
     }
-    pub const fn with(&self, with_castle_rights: CastleRights) -> Self {
-        match (self, with_castle_rights) {
-            (Self::KingSideOnly, Self::QueenSideOnly)
-            | (Self::QueenSideOnly, Self::KingSideOnly) => CastleRights::Both,
-            (Self::Both, _) | (_, Self::Both) => CastleRights::Both,
-            (_, right) => right,
+    pub const fn with(&self, castle_side: CastleSide, rook_file: BoardFile) -> Self {
+        match castle_side {
+            CastleSide::King => Self {
+                king_side: Some(rook_file),
+                ..*self
+            },
+            CastleSide::Queen => Self {
+                queen_side: Some(rook_file),
+                ..*self
+            },
         }
     }
     pub const fn with_option(
         source: Option<CastleRights>,
-        with_castle_rights: CastleRights,
+        castle_side: CastleSide,
+        rook_file: BoardFile,
     ) -> Option<Self> {
         Some(match source {
-            None => with_castle_rights,
-            Some(cr) => cr.with(with_castle_rights),
+            None => Self::from_rook_file(castle_side, rook_file),
+            Some(cr) => cr.with(castle_side, rook_file),
         })
     }
-    pub const fn as_str(&self, color: Color) -> &'static str {
-        match color {
-            Color::White => match self {
-                Self::KingSideOnly => "K",
-                Self::QueenSideOnly => "Q",
-                Self::Both => "KQ",
-            },
-            Color::Black => match self {
-                Self::KingSideOnly => "k",
-                Self::QueenSideOnly => "q",
-                Self::Both => "kq",
-            },
-        }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_without() {
+        let castle_rights = CastleRights::both();
+        assert_eq!(
+            Some(CastleRights::from_castle_side(CastleSide::Queen)),
+            castle_rights.without(CastleSide::King)
+        );
+        assert_eq!(
+            None,
+            castle_rights
+                .without(CastleSide::King)
+                .and_then(|castle_rights| castle_rights.without(CastleSide::Queen))
+        );
     }
-    pub fn from_str(s: &str) -> Result<Self, CastleRightsStringParseError> {
-        Ok(match s {
-            "K" | "k" => Self::KingSideOnly,
-            "Q" | "q" => Self::QueenSideOnly,
-            "KQ" | "kq" => Self::Both,
-            _ => return Err(CastleRightsStringParseError::InvalidCastleRightsString(s.to_string()))
-        })
+
+    #[test]
+    fn test_castle_side_for_rook_file() {
+        let castle_rights = CastleRights::from_rook_file(CastleSide::King, BoardFile::G)
+            .with(CastleSide::Queen, BoardFile::B);
+        assert_eq!(
+            Some(CastleSide::King),
+            castle_rights.castle_side_for_rook_file(BoardFile::G)
+        );
+        assert_eq!(
+            Some(CastleSide::Queen),
+            castle_rights.castle_side_for_rook_file(BoardFile::B)
+        );
+        assert_eq!(None, castle_rights.castle_side_for_rook_file(BoardFile::H));
     }
 }
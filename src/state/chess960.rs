@@ -0,0 +1,207 @@
+use crate::board::board::Board;
+use crate::board::board_file::BoardFile;
+use crate::board::board_position::BoardPosition;
+use crate::color::Color;
+use crate::notation::fen::deserialize;
+use crate::piece::piece::Piece;
+use crate::state::game_state::GameState;
+use thiserror::Error;
+
+pub const CHESS960_POSITION_COUNT: u16 = 960;
+// RNBQKBNR in scharnagl numbering
+pub const CHESS960_STANDARD_INDEX: u16 = 518;
+
+// placements of the two knights on the five squares left after bishops and queen
+const KNIGHT_PLACEMENTS: [(usize, usize); 10] = [
+    (0, 1),
+    (0, 2),
+    (0, 3),
+    (0, 4),
+    (1, 2),
+    (1, 3),
+    (1, 4),
+    (2, 3),
+    (2, 4),
+    (3, 4),
+];
+
+#[derive(Error, Debug, Copy, Clone, PartialEq)]
+pub enum Chess960Error {
+    #[error("Invalid chess960 index, expected 0..960, received: {0}")]
+    InvalidIndex(u16),
+}
+
+fn place_on_nth_empty(back_rank: &mut [Option<Piece>; 8], n: usize, piece: Piece) {
+    let file_ix = back_rank
+        .iter()
+        .enumerate()
+        .filter(|(_, square)| square.is_none())
+        .nth(n)
+        .map(|(file_ix, _)| file_ix)
+        .expect("not enough empty squares on the back rank");
+    back_rank[file_ix] = Some(piece);
+}
+
+// scharnagl numbering, files a through h
+pub fn chess960_back_rank(index: u16) -> Result<[Piece; 8], Chess960Error> {
+    if index >= CHESS960_POSITION_COUNT {
+        return Err(Chess960Error::InvalidIndex(index));
+    }
+    let mut back_rank: [Option<Piece>; 8] = [None; 8];
+    let mut n = index as usize;
+    // light squared bishop on b, d, f or h, then the dark one on a, c, e or g
+    back_rank[(n % 4) * 2 + 1] = Some(Piece::Bishop);
+    n /= 4;
+    back_rank[(n % 4) * 2] = Some(Piece::Bishop);
+    n /= 4;
+    place_on_nth_empty(&mut back_rank, n % 6, Piece::Queen);
+    n /= 6;
+    let (first_knight, second_knight) = KNIGHT_PLACEMENTS[n];
+    // placing the first knight shifts the empty squares the second one counts over
+    place_on_nth_empty(&mut back_rank, second_knight, Piece::Knight);
+    place_on_nth_empty(&mut back_rank, first_knight, Piece::Knight);
+    // the king always ends up between the rooks
+    for piece in [Piece::Rook, Piece::King, Piece::Rook] {
+        place_on_nth_empty(&mut back_rank, 0, piece);
+    }
+    Ok(back_rank.map(|square| square.expect("back rank is full")))
+}
+
+pub fn chess960_fen(index: u16) -> Result<String, Chess960Error> {
+    let back_rank = chess960_back_rank(index)?
+        .iter()
+        .map(Piece::as_char)
+        .collect::<String>();
+    // x-fen KQkq always refers to the outer rooks, which are the only rooks at the start
+    Ok(format!(
+        "{}/pppppppp/8/8/8/8/PPPPPPPP/{back_rank} w KQkq - 0 1",
+        back_rank.to_ascii_lowercase()
+    ))
+}
+
+pub fn chess960_game_state(index: u16) -> Result<GameState, Chess960Error> {
+    let fen = chess960_fen(index)?;
+    Ok(deserialize(&fen).expect("chess960 fen should always be valid"))
+}
+
+// only the white back rank is compared, pieces elsewhere are ignored
+pub fn chess960_index(board: &Board) -> Option<u16> {
+    (0..CHESS960_POSITION_COUNT).find(|&index| {
+        let Ok(back_rank) = chess960_back_rank(index) else {
+            return false;
+        };
+        back_rank.iter().enumerate().all(|(file_ix, &piece)| {
+            let file = BoardFile::from_zero_based_index(file_ix).expect("file out of range");
+            *board.get(BoardPosition(file, Color::White.back_rank()))
+                == Some(piece.as_chess_piece(Color::White))
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess_move::perft::perft;
+    use crate::notation::fen::{
+        deserialize_strict, serialize, serialize_with_castling_notation, FEN_STARTING_POS,
+    };
+    use crate::state::color_castle_rights::CastlingNotation;
+    use rstest::rstest;
+    use std::collections::HashSet;
+
+    #[rstest]
+    #[case(0, "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w HFhf - 0 1")]
+    #[case(
+        CHESS960_STANDARD_INDEX,
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w HAha - 0 1"
+    )]
+    #[case(959, "rkrnnqbb/pppppppp/8/8/8/8/PPPPPPPP/RKRNNQBB w CAca - 0 1")]
+    fn test_chess960_game_state(#[case] index: u16, #[case] expected: &str) {
+        let game_state = chess960_game_state(index).expect("bad chess960 index!");
+        assert_eq!(
+            expected,
+            serialize_with_castling_notation(&game_state, CastlingNotation::Shredder).get_str()
+        );
+        assert_eq!(Some(index), chess960_index(&game_state.board));
+    }
+
+    #[test]
+    fn test_standard_index_is_standard_chess() {
+        let game_state = chess960_game_state(CHESS960_STANDARD_INDEX).expect("bad chess960 index!");
+        assert_eq!(FEN_STARTING_POS, serialize(&game_state).get_str());
+    }
+
+    #[test]
+    fn test_chess960_fens_pass_strict_parsing() {
+        for index in 0..CHESS960_POSITION_COUNT {
+            let fen = chess960_fen(index).expect("bad chess960 index!");
+            let game_state = deserialize_strict(&fen)
+                .unwrap_or_else(|err| panic!("chess960 position {index} is invalid: {err}"));
+            let shredder_fen =
+                serialize_with_castling_notation(&game_state, CastlingNotation::Shredder);
+            assert!(
+                deserialize_strict(shredder_fen.get_str()).is_ok(),
+                "{shredder_fen:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_chess960_back_ranks_are_unique_and_valid() {
+        let back_ranks = (0..CHESS960_POSITION_COUNT)
+            .map(|index| chess960_back_rank(index).expect("bad chess960 index!"))
+            .collect::<Vec<_>>();
+        for back_rank in back_ranks.iter() {
+            let files_of = |piece: Piece| {
+                back_rank
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &other)| other == piece)
+                    .map(|(file_ix, _)| file_ix)
+                    .collect::<Vec<_>>()
+            };
+            let bishops = files_of(Piece::Bishop);
+            let rooks = files_of(Piece::Rook);
+            let king = files_of(Piece::King)[0];
+            assert_ne!(bishops[0] % 2, bishops[1] % 2);
+            assert!(rooks[0] < king && king < rooks[1]);
+        }
+        let unique = back_ranks
+            .iter()
+            .map(|back_rank| back_rank.map(|piece| piece.as_char()))
+            .collect::<HashSet<_>>();
+        assert_eq!(CHESS960_POSITION_COUNT as usize, unique.len());
+        assert_eq!(
+            Some(Chess960Error::InvalidIndex(CHESS960_POSITION_COUNT)),
+            chess960_back_rank(CHESS960_POSITION_COUNT).err()
+        );
+    }
+
+    #[rstest]
+    #[case(
+        "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+        3,
+        12189
+    )]
+    #[case(
+        "2nnrbkr/p1qppppp/8/1ppb4/6PP/3PP3/PPP2P2/BQNNRBKR w HEhe - 1 9",
+        2,
+        807
+    )]
+    #[case("b1q1rrkb/pppppppp/3nn3/8/P7/1PPP4/4PPPP/BQNNRKRB w GE - 1 9", 2, 479)]
+    #[case(
+        "qbbnnrkr/2pp2pp/p7/1p2pp2/8/P3PP2/1PPP1KPP/QBBNNR1R w hf - 0 9",
+        2,
+        593
+    )]
+    #[case(
+        "1nbbnrkr/p1p1ppp1/3p4/1p3P1p/3Pq2P/8/PPP1P1P1/QNBBNRKR w HFhf - 0 9",
+        2,
+        1120
+    )]
+    #[case("rk5r/8/8/8/8/8/8/RK5R w HAha - 0 1", 3, 11099)]
+    fn test_chess960_perft(#[case] fen: &str, #[case] depth: u8, #[case] expected: u64) {
+        let game_state = deserialize(fen).expect("bad fen string!");
+        assert_eq!(expected, perft(&game_state, depth));
+    }
+}
@@ -1,12 +1,82 @@
+use crate::board::board::Board;
+use crate::board::board_file::BoardFile;
+use crate::board::board_position::BoardPosition;
 use crate::color::Color;
+use crate::direction::castle_side::CastleSide;
+use crate::piece::chess_piece::ChessPiece;
+use crate::piece::piece::Piece;
 use crate::state::castle_rights::{CastleRights, CastleRightsStringParseError};
 
-#[derive(Clone, Debug)]
+const NO_CASTLE_RIGHTS: &str = "-";
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum CastlingNotation {
+    // KQkq, falling back to the rook file when an inner rook castles
+    #[default]
+    XFen,
+    // always the rook file, e.g. HAha
+    Shredder,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ColorCastleRights {
     pub white: Option<CastleRights>,
     pub black: Option<CastleRights>,
 }
 
+const fn castle_side_files(castle_side: CastleSide) -> [BoardFile; 8] {
+    use BoardFile::*;
+    // ordered from the edge of the board towards the king
+    match castle_side {
+        CastleSide::King => [H, G, F, E, D, C, B, A],
+        CastleSide::Queen => [A, B, C, D, E, F, G, H],
+    }
+}
+
+fn king_file(board: Option<&Board>, color: Color) -> BoardFile {
+    let king = ChessPiece::from(color, Piece::King);
+    board
+        .and_then(|board| {
+            castle_side_files(CastleSide::Queen)
+                .into_iter()
+                .find(|&file| *board.get(BoardPosition(file, color.back_rank())) == Some(king))
+        })
+        .unwrap_or(BoardFile::E)
+}
+
+// the rook closest to the edge of the board on the given side of the king
+fn outermost_rook_file(
+    board: Option<&Board>,
+    color: Color,
+    castle_side: CastleSide,
+) -> Option<BoardFile> {
+    let board = board?;
+    let rook = ChessPiece::from(color, Piece::Rook);
+    let king_file = king_file(Some(board), color);
+    castle_side_files(castle_side)
+        .into_iter()
+        .take_while(|&file| file != king_file)
+        .find(|&file| *board.get(BoardPosition(file, color.back_rank())) == Some(rook))
+}
+
+fn castle_side_for_file(
+    board: Option<&Board>,
+    color: Color,
+    rook_file: BoardFile,
+    c: char,
+) -> Result<CastleSide, CastleRightsStringParseError> {
+    let king_file = king_file(board, color);
+    if rook_file == king_file {
+        return Err(CastleRightsStringParseError::RookFileOnKingFile(c));
+    }
+    Ok(if rook_file > king_file {
+        CastleSide::King
+    } else {
+        CastleSide::Queen
+    })
+}
+
 impl ColorCastleRights {
     pub const fn empty() -> Self {
         Self {
@@ -16,8 +86,8 @@ impl ColorCastleRights {
     }
     pub const fn new() -> Self {
         Self {
-            white: Some(CastleRights::Both),
-            black: Some(CastleRights::Both),
+            white: Some(CastleRights::both()),
+            black: Some(CastleRights::both()),
         }
     }
     pub const fn for_color(&self, color: Color) -> Option<CastleRights> {
@@ -32,55 +102,162 @@ impl ColorCastleRights {
             Color::Black => &mut self.black,
         }
     }
-    const fn to_tuple(&self) -> (&Option<CastleRights>, &Option<CastleRights>) {
-        (&self.white, &self.black)
+
+    pub fn to_fen_string(&self, board: &Board, notation: CastlingNotation) -> String {
+        let mut s = String::new();
+        for color in [Color::White, Color::Black] {
+            let Some(castle_rights) = self.for_color(color) else {
+                continue;
+            };
+            for castle_side in [CastleSide::King, CastleSide::Queen] {
+                let Some(rook_file) = castle_rights.rook_file(castle_side) else {
+                    continue;
+                };
+                let is_outermost =
+                    outermost_rook_file(Some(board), color, castle_side) == Some(rook_file);
+                let c = match (notation, castle_side) {
+                    (CastlingNotation::XFen, CastleSide::King) if is_outermost => 'K',
+                    (CastlingNotation::XFen, CastleSide::Queen) if is_outermost => 'Q',
+                    _ => rook_file.as_char(),
+                };
+                s.push(match color {
+                    Color::White => c.to_ascii_uppercase(),
+                    Color::Black => c.to_ascii_lowercase(),
+                });
+            }
+        }
+        if s.is_empty() {
+            return NO_CASTLE_RIGHTS.to_string();
+        }
+        s
     }
-    const fn from_tuple(tuple: (Option<CastleRights>, Option<CastleRights>)) -> Self {
-        let (white, black) = tuple;
-        Self { white, black }
+
+    // without a board K and Q mean the classical h and a file rooks
+    pub fn from_str(s: &str) -> Result<Self, CastleRightsStringParseError> {
+        Self::parse(s, None)
+    }
+
+    // accepts KQkq, Shredder-FEN and X-FEN, the board decides which rook K and Q refer to
+    pub fn from_fen_str(s: &str, board: &Board) -> Result<Self, CastleRightsStringParseError> {
+        Self::parse(s, Some(board))
     }
 
-    pub const fn as_str(&self) -> &'static str {
-        match self.to_tuple() {
-            (None, None) => "-",
-            (None, Some(black_cr)) => black_cr.as_str(Color::Black),
-            (Some(white_cr), None) => white_cr.as_str(Color::White),
-            // TODO: maintaining &'static str is probably not worth it
-            (Some(white_cr), Some(black_cr)) => match (white_cr, black_cr) {
-                (CastleRights::Both, CastleRights::Both) => "KQkq",
-                (CastleRights::KingSideOnly, CastleRights::Both) => "Kkq",
-                (CastleRights::QueenSideOnly, CastleRights::Both) => "Qkq",
-                (CastleRights::Both, CastleRights::KingSideOnly) => "KQk",
-                (CastleRights::Both, CastleRights::QueenSideOnly) => "KQq",
-                (CastleRights::KingSideOnly, CastleRights::KingSideOnly) => "Kk",
-                (CastleRights::QueenSideOnly, CastleRights::QueenSideOnly) => "Qq",
-                (CastleRights::KingSideOnly, CastleRights::QueenSideOnly) => "Kq",
-                (CastleRights::QueenSideOnly, CastleRights::KingSideOnly) => "Qk",
-            },
+    fn parse(s: &str, board: Option<&Board>) -> Result<Self, CastleRightsStringParseError> {
+        let invalid = || CastleRightsStringParseError::InvalidCastleRightsString(s.to_string());
+        let mut color_castle_rights = Self::empty();
+        if s == NO_CASTLE_RIGHTS {
+            return Ok(color_castle_rights);
         }
+        if s.is_empty() {
+            return Err(invalid());
+        }
+        for c in s.chars() {
+            let color = if c.is_ascii_uppercase() {
+                Color::White
+            } else {
+                Color::Black
+            };
+            let (castle_side, rook_file) = match c.to_ascii_lowercase() {
+                'k' => (
+                    CastleSide::King,
+                    outermost_rook_file(board, color, CastleSide::King)
+                        .unwrap_or(CastleRights::standard_rook_file(CastleSide::King)),
+                ),
+                'q' => (
+                    CastleSide::Queen,
+                    outermost_rook_file(board, color, CastleSide::Queen)
+                        .unwrap_or(CastleRights::standard_rook_file(CastleSide::Queen)),
+                ),
+                _ => {
+                    let rook_file = BoardFile::from_char(c).map_err(|_| invalid())?;
+                    (castle_side_for_file(board, color, rook_file, c)?, rook_file)
+                }
+            };
+            let castle_rights = color_castle_rights.for_color_mut(color);
+            if castle_rights.is_some_and(|castle_rights| castle_rights.has(castle_side)) {
+                return Err(invalid());
+            }
+            *castle_rights = CastleRights::with_option(*castle_rights, castle_side, rook_file);
+        }
+        Ok(color_castle_rights)
     }
+}
 
-    pub fn from_str(s: &str) -> Result<Self, CastleRightsStringParseError> {
-        // TODO: being this explicit is probably not worth it
-        Ok(Self::from_tuple(match s {
-            "-" => return Ok(Self::empty()),
-            "KQkq" => (Some(CastleRights::Both), Some(CastleRights::Both)),
-            "Kkq" => (Some(CastleRights::KingSideOnly), Some(CastleRights::Both)),
-            "Qkq" => (Some(CastleRights::QueenSideOnly), Some(CastleRights::Both)),
-            "KQk" => (Some(CastleRights::Both), Some(CastleRights::KingSideOnly)),
-            "KQq" => (Some(CastleRights::Both), Some(CastleRights::QueenSideOnly)),
-            "Kk" => (Some(CastleRights::KingSideOnly), Some(CastleRights::KingSideOnly)),
-            "Qq" => (Some(CastleRights::QueenSideOnly), Some(CastleRights::QueenSideOnly)),
-            "Kq" => (Some(CastleRights::KingSideOnly), Some(CastleRights::QueenSideOnly)),
-            "Qk" => (Some(CastleRights::QueenSideOnly), Some(CastleRights::KingSideOnly)),
-            "KQ" => (Some(CastleRights::Both), None),
-            "K" => (Some(CastleRights::KingSideOnly), None),
-            "Q" => (Some(CastleRights::QueenSideOnly), None),
-            "kq" => (None, Some(CastleRights::Both)),
-            "k" => (None, Some(CastleRights::KingSideOnly)),
-            "q" => (None, Some(CastleRights::QueenSideOnly)),
-            // TODO: InvalidCastleRightsString does not mention "-"
-            _ => return Err(CastleRightsStringParseError::InvalidCastleRightsString(s.to_string()))
-        }))
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::fen::deserialize;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("-")]
+    #[case("KQkq")]
+    #[case("Kkq")]
+    #[case("Qk")]
+    #[case("q")]
+    fn test_classical_round_trip(#[case] s: &str) {
+        let castle_rights = ColorCastleRights::from_str(s).expect("bad castle rights!");
+        assert_eq!(
+            s,
+            castle_rights.to_fen_string(&Board::new(), CastlingNotation::XFen)
+        );
+    }
+
+    #[rstest]
+    #[case("", false)]
+    #[case("KK", false)]
+    #[case("KQkqx", false)]
+    #[case("HAha", true)]
+    #[case("E", false)]
+    fn test_from_str(#[case] s: &str, #[case] is_ok: bool) {
+        assert_eq!(is_ok, ColorCastleRights::from_str(s).is_ok());
+    }
+
+    #[rstest]
+    #[case("KQkq", "HAha")]
+    #[case("HAha", "HAha")]
+    #[case("Kq", "Ha")]
+    fn test_shredder(#[case] s: &str, #[case] expected: &str) {
+        let board = Board::new();
+        let castle_rights = ColorCastleRights::from_fen_str(s, &board).expect("bad castle rights!");
+        assert_eq!(
+            expected,
+            castle_rights.to_fen_string(&board, CastlingNotation::Shredder)
+        );
+    }
+
+    #[rstest]
+    // chess960 start with the rooks on b and g
+    #[case(
+        "nrbqkbrn/pppppppp/8/8/8/8/PPPPPPPP/NRBQKBRN w KQkq - 0 1",
+        "KQkq",
+        "GBgb"
+    )]
+    #[case(
+        "nrbqkbrn/pppppppp/8/8/8/8/PPPPPPPP/NRBQKBRN w GBgb - 0 1",
+        "KQkq",
+        "GBgb"
+    )]
+    // an inner rook needs its file in x-fen
+    #[case("4k3/8/8/8/8/8/8/R2RK2R w KD - 0 1", "KD", "HD")]
+    #[case("1r2k2r/8/8/8/8/8/8/4K3 b k - 0 1", "k", "h")]
+    fn test_chess960_castle_rights(
+        #[case] fen: &str,
+        #[case] x_fen: &str,
+        #[case] shredder_fen: &str,
+    ) {
+        let game_state = deserialize(fen).expect("bad fen string!");
+        assert_eq!(
+            x_fen,
+            game_state
+                .castle_rights
+                .to_fen_string(&game_state.board, CastlingNotation::XFen)
+        );
+        assert_eq!(
+            shredder_fen,
+            game_state
+                .castle_rights
+                .to_fen_string(&game_state.board, CastlingNotation::Shredder)
+        );
     }
 }
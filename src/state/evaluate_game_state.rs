@@ -1,9 +1,18 @@
+use crate::book::polyglot_book::PolyglotBook;
+use crate::book::polyglot_key::polyglot_key;
 use crate::chess_move::chess_move::ChessMove;
-use crate::chess_move::chess_move_handler::try_handle_chess_move_and_apply;
-use crate::chess_move::chess_move_search::unchecked_chess_move_search;
 use crate::color::Color;
+use crate::search::alpha_beta::SearchContext;
+use crate::search::lazy_smp::{lazy_smp_search, lazy_smp_search_with};
+use crate::search::search_options::SearchOptions;
+use crate::search::skill_level::find_skill_limited_move;
+use crate::search::transposition_table::TranspositionTable;
 use crate::state::game_state::GameState;
 use crate::state::game_status::GameStatus;
+use crate::tablebase::tablebase::Tablebase;
+use crate::utils::random::Random;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
 
 pub fn evaluate_game_state(game_state: &GameState, maximizing_color: Color) -> i32 {
     let mut score: i32 = 0;
@@ -16,7 +25,9 @@ pub fn evaluate_game_state(game_state: &GameState, maximizing_color: Color) -> i
     score = score.saturating_add(match game_state.game_status {
         GameStatus::InProgress | GameStatus::New => 0,
         GameStatus::Check(color) => 99 * -color.as_score(maximizing_color),
-        GameStatus::CheckMate(color) => i32::MAX * -color.as_score(maximizing_color),
+        GameStatus::CheckMate(color) | GameStatus::TimeForfeit(color) => {
+            i32::MAX * -color.as_score(maximizing_color)
+        }
         // encourage losing bots to go for stalemate/draw
         GameStatus::Stalemate | GameStatus::Draw => -score + score.signum(),
     });
@@ -30,99 +41,46 @@ pub fn evaluate_game_state(game_state: &GameState, maximizing_color: Color) -> i
     score
 }
 
-fn minimax_with_alpha_beta(
+pub fn find_best_move(game_state: &GameState, depth: u8) -> Result<ChessMove, &'static str> {
+    find_best_move_with_options(game_state, depth, None)
+}
+
+pub fn find_best_move_with_options(
     game_state: &GameState,
     depth: u8,
-    alpha: i32,
-    beta: i32,
-    maximizing_color: Color,
-) -> i32 {
-    if depth == 0 || game_state.game_status.is_game_over() {
-        return evaluate_game_state(game_state, maximizing_color);
-    }
-
-    if maximizing_color == game_state.active_color {
-        let mut max_eval = i32::MIN;
-        for move_ in unchecked_chess_move_search(game_state, None) {
-            let mut new_game_state = game_state.clone();
-            if let Ok(_) = try_handle_chess_move_and_apply(&mut new_game_state, &move_, None) {
-                let eval = minimax_with_alpha_beta(
-                    &new_game_state,
-                    depth - 1,
-                    alpha,
-                    beta,
-                    maximizing_color,
-                );
-                max_eval = max_eval.max(eval);
-
-                if max_eval >= beta {
-                    // println!("prune beta: {max_eval}>={beta}");
-                    break; // Beta cutoff
-                }
-            }
-        }
-        max_eval
-    } else {
-        let mut min_eval = i32::MAX;
-        for move_ in unchecked_chess_move_search(game_state, None) {
-            let mut new_game_state = game_state.clone();
-            if let Ok(_) = try_handle_chess_move_and_apply(&mut new_game_state, &move_, None) {
-                let eval = minimax_with_alpha_beta(
-                    &new_game_state,
-                    depth - 1,
-                    alpha,
-                    beta,
-                    maximizing_color,
-                );
-                min_eval = min_eval.min(eval);
-
-                if min_eval <= alpha {
-                    // println!("prune alpha: {min_eval}>={alpha}");
-                    break; // Alpha cutoff
-                }
-            }
-        }
-        min_eval
-    }
+    options: Option<SearchOptions>,
+) -> Result<ChessMove, &'static str> {
+    let book = options.and_then(|options| options.book);
+    find_best_move_with_book(game_state, depth, book, options)
 }
 
-pub fn find_best_move(game_state: &GameState, depth: u8) -> Result<ChessMove, &'static str> {
-    let mut best_move = None;
-    let mut best_eval = i32::MIN;
-
-    let maximizing_player_color = game_state.active_color;
-    for move_ in unchecked_chess_move_search(game_state, None) {
-        /*if state.history.move_history.len() >= 132 {
-            println!("{:?}", move_);
-        }*/
-        let mut new_game_state = game_state.clone();
-        let move_result = try_handle_chess_move_and_apply(&mut new_game_state, &move_, None);
-        if let Ok(_) = move_result {
-            let eval = minimax_with_alpha_beta(
-                &new_game_state,
-                depth - 1,
-                i32::MIN,
-                i32::MAX,
-                maximizing_player_color,
-            );
+// seeded by the position so a game replays the same book moves with the same seed
+pub fn find_book_move(
+    game_state: &GameState,
+    book: &PolyglotBook,
+    options: SearchOptions,
+) -> Option<ChessMove> {
+    let mut random = Random::new(options.random_seed ^ polyglot_key(game_state));
+    book.choose_move(game_state, options.book_selection, &mut random)
+}
 
-            if eval > best_eval {
-                best_eval = eval;
-                best_move = Some(move_);
-            } else {
-                // force to pick at least one legal chess_move
-                best_move = best_move.or(Some(move_));
-            }
-            /* if state.history.move_history.len() >= 132 {
-                println!("ok - {best_eval}");
-            }*/
-        } /* else if let Err(err) = move_result {
-              if state.history.move_history.len() >= 132 {
-                  println!("err - {best_eval} - {err:?}");
-              }
-          }*/
+// the book is consulted first, searching only starts once the position is out of book
+pub fn find_best_move_with_book(
+    game_state: &GameState,
+    depth: u8,
+    book: Option<&PolyglotBook>,
+    options: Option<SearchOptions>,
+) -> Result<ChessMove, &'static str> {
+    let options = options.unwrap_or_default();
+    if let Some(book_move) = book.and_then(|book| find_book_move(game_state, book, options)) {
+        return Ok(book_move);
     }
-
+    let best_move = match options.skill_level {
+        Some(skill_level) if !skill_level.is_max() => {
+            find_skill_limited_move(game_state, depth, skill_level, options)
+        }
+        _ => lazy_smp_search(game_state, depth, options).best_move,
+    };
     let Some(best_move) = best_move else {
         return Err("No legal moves available");
     };
@@ -130,13 +88,39 @@ pub fn find_best_move(game_state: &GameState, depth: u8) -> Result<ChessMove, &'
     Ok(best_move)
 }
 
+// positions in the tablebase are played perfectly, otherwise the search probes it once
+// captures bring the material down
+pub fn find_best_move_with_tablebase(
+    game_state: &GameState,
+    depth: u8,
+    tablebase: &Arc<Tablebase>,
+    options: Option<SearchOptions>,
+) -> Result<ChessMove, &'static str> {
+    if let Some((best_move, _)) = tablebase.best_move(game_state) {
+        return Ok(best_move);
+    }
+    let options = options.unwrap_or_default();
+    let mut context = SearchContext::new(
+        game_state.active_color,
+        options,
+        Arc::new(TranspositionTable::new(options.hash_size_mb)),
+        Arc::new(AtomicBool::new(false)),
+    );
+    context.tablebase = Some(tablebase.clone());
+    lazy_smp_search_with(game_state, depth, context)
+        .best_move
+        .ok_or("No legal moves available")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::board::board_position::BoardPosition;
     use crate::board::position::*;
+    use crate::book::polyglot_book::{encode_polyglot_move, PolyglotEntry};
     use crate::chess_move::chess_move::ChessMoveType;
-    use crate::notation::fen::deserialize;
+    use crate::notation::fen::{deserialize, FEN_STARTING_POS};
+    use crate::piece::chess_piece::ChessPiece;
     use crate::piece::promotion_piece::PromotionPiece;
     use rstest::rstest;
 
@@ -172,4 +156,66 @@ mod tests {
         }
         Ok(())
     }
+
+    #[test]
+    fn test_find_best_move_with_book() -> Result<(), &'static str> {
+        let game_state = deserialize(FEN_STARTING_POS).expect("bad fen string!");
+        let book_move = ChessMove::create_normal(ChessPiece::WhiteKnight, B1, A3);
+        let book = PolyglotBook::from_entries(vec![PolyglotEntry {
+            key: polyglot_key(&game_state),
+            raw_move: encode_polyglot_move(&game_state, &book_move),
+            weight: 1,
+            learn: 0,
+        }]);
+        assert_eq!(
+            book_move,
+            find_best_move_with_book(&game_state, 2, Some(&book), None)?
+        );
+        // out of book falls back to searching
+        let game_state = deserialize("8/1P2R3/k7/8/1Q6/8/8/7K w - - 0 1").expect("bad fen string!");
+        assert_eq!(
+            find_best_move(&game_state, 2)?,
+            find_best_move_with_book(&game_state, 2, Some(&book), None)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_best_move_with_options_uses_book() -> Result<(), &'static str> {
+        let game_state = deserialize(FEN_STARTING_POS).expect("bad fen string!");
+        let book_move = ChessMove::create_normal(ChessPiece::WhiteKnight, B1, A3);
+        let book = PolyglotBook::from_entries(vec![PolyglotEntry {
+            key: polyglot_key(&game_state),
+            raw_move: encode_polyglot_move(&game_state, &book_move),
+            weight: 1,
+            learn: 0,
+        }]);
+        let options = SearchOptions {
+            book: Some(Box::leak(Box::new(book))),
+            ..SearchOptions::new()
+        };
+        assert_eq!(
+            book_move,
+            find_best_move_with_options(&game_state, 2, Some(options))?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_best_move_with_tablebase() -> Result<(), &'static str> {
+        let mut tablebase = Tablebase::new();
+        tablebase
+            .generate_by_name("KRvK")
+            .map_err(|_| "valid material")?;
+        let tablebase = Arc::new(tablebase);
+        // in the tablebase, the only mate in one
+        let game_state = deserialize("7k/8/6K1/8/8/8/8/1R6 w - - 0 1").expect("bad fen string!");
+        let best_move = find_best_move_with_tablebase(&game_state, 1, &tablebase, None)?;
+        assert_eq!((B1, B8), (best_move.from, best_move.to));
+        // not in the tablebase, but taking the knight lands in a won KRvK ending
+        let game_state = deserialize("7k/8/8/3n4/8/8/8/K2R4 w - - 0 1").expect("bad fen string!");
+        let best_move = find_best_move_with_tablebase(&game_state, 1, &tablebase, None)?;
+        assert_eq!((D1, D5), (best_move.from, best_move.to));
+        Ok(())
+    }
 }
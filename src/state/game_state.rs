@@ -1,5 +1,6 @@
 use crate::board::board::Board;
 use crate::board::board_position::BoardPosition;
+use crate::clock::clock::Clock;
 use crate::color::Color;
 use crate::state::color_castle_rights::ColorCastleRights;
 use crate::state::game_status::GameStatus;
@@ -7,6 +8,7 @@ use crate::state::history::History;
 use crate::state::move_counter::MoveCounter;
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GameState {
     pub board: Board,
     pub history: History,
@@ -15,6 +17,9 @@ pub struct GameState {
     pub castle_rights: ColorCastleRights,
     pub active_color: Color,
     pub game_status: GameStatus,
+    // time sources can't be serialized, so neither are clocks
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub clock: Option<Clock>,
 }
 
 impl GameState {
@@ -27,6 +32,7 @@ impl GameState {
             castle_rights: ColorCastleRights::empty(),
             active_color: Color::White,
             game_status: GameStatus::New,
+            clock: None,
         }
     }
 
@@ -39,6 +45,47 @@ impl GameState {
             castle_rights: ColorCastleRights::new(),
             active_color: Color::White,
             game_status: GameStatus::New,
+            clock: None,
         }
     }
 }
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use crate::chess_move::chess_move_handler::default_chess_move_handler;
+    use crate::notation::fen::serialize;
+    use crate::notation::san::deserialize_san;
+    use crate::state::state_history::StateHistoryContainer;
+
+    #[test]
+    fn test_serde_round_trip() {
+        let mut game_state = GameState::new();
+        for san in ["e4", "e5", "Nf3", "Nc6"] {
+            let chess_move = deserialize_san(&game_state, san).expect("bad san!");
+            default_chess_move_handler(&mut game_state, &chess_move, None).expect("bad move!");
+        }
+        let json = serde_json::to_string(&game_state).expect("failed to serialize!");
+        let round_trip: GameState = serde_json::from_str(&json).expect("failed to deserialize!");
+        assert_eq!(
+            serialize(&game_state).get_str(),
+            serialize(&round_trip).get_str()
+        );
+        assert_eq!(game_state.game_status, round_trip.game_status);
+        assert_eq!(
+            game_state.history.move_history.len(),
+            round_trip.history.move_history.len()
+        );
+        let (
+            Some(StateHistoryContainer::Hash(expected)),
+            Some(StateHistoryContainer::Hash(actual)),
+        ) = (
+            game_state.history.state_history,
+            round_trip.history.state_history,
+        )
+        else {
+            panic!("expected state history to be upgraded after a few moves");
+        };
+        assert_eq!(expected, actual);
+    }
+}
@@ -8,6 +8,7 @@ use crate::piece::piece::Piece;
 use crate::state::game_state::GameState;
 
 #[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GameStatus {
     New,
     InProgress,
@@ -15,6 +16,8 @@ pub enum GameStatus {
     CheckMate(Color),
     Stalemate,
     Draw,
+    // the color that ran out of time
+    TimeForfeit(Color),
 }
 
 impl GameStatus {
@@ -45,6 +48,7 @@ impl GameStatus {
             GameStatus::CheckMate(_) => true,
             GameStatus::Draw => true,
             GameStatus::Stalemate => true,
+            GameStatus::TimeForfeit(_) => true,
             _ => false,
         }
     }
@@ -170,3 +174,36 @@ pub fn is_stalemate(game_state: &GameState) -> bool {
     }
     true
 }
+
+// whether color could still mate by some series of legal moves, a lone knight or bishops on one
+// square color can only do it with the opponent's own pieces hemming its king in
+pub fn has_mating_material(game_state: &GameState, color: Color) -> bool {
+    let mut knights = 0;
+    let mut bishop_square_colors = [false; 2];
+    let mut opponent_pieces = false;
+    let mut opponent_bishop_square_colors = [false; 2];
+    for (pos, maybe_piece) in game_state.board.as_iter() {
+        let Some(chess_piece) = maybe_piece else {
+            continue;
+        };
+        let square_color = (pos.as_pos_index() / 8 + pos.as_pos_index() % 8) % 2;
+        match (chess_piece.as_color() == color, chess_piece.as_piece()) {
+            (_, Piece::King) => {}
+            (true, Piece::Pawn | Piece::Rook | Piece::Queen) => return true,
+            (true, Piece::Knight) => knights += 1,
+            (true, Piece::Bishop) => bishop_square_colors[square_color] = true,
+            (false, Piece::Bishop) => opponent_bishop_square_colors[square_color] = true,
+            (false, _) => opponent_pieces = true,
+        }
+    }
+    match (knights, bishop_square_colors) {
+        (0, [false, false]) => false,
+        (1, [false, false]) => {
+            opponent_pieces || opponent_bishop_square_colors.iter().any(|bishop| *bishop)
+        }
+        // only a piece that can reach the other square color can block the king's escape
+        (0, [true, false]) => opponent_pieces || opponent_bishop_square_colors[1],
+        (0, [false, true]) => opponent_pieces || opponent_bishop_square_colors[0],
+        _ => true,
+    }
+}
@@ -0,0 +1,439 @@
+use crate::chess_move::chess_move::ChessMove;
+use crate::chess_move::chess_move_handler::default_chess_move_handler;
+use crate::chess_move::invalid_chess_move_error::InvalidChessMoveError;
+use crate::notation::san::{
+    deserialize_san, legal_moves_with_status, serialize_san, SanParsingError,
+};
+use crate::state::annotations::Annotations;
+use crate::state::game_state::GameState;
+use crate::state::history::replay_whole_game;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum GameTreeError {
+    #[error("No such node in the game tree: {0:?}")]
+    UnknownNode(NodeId),
+    #[error("The root of a game tree has no move")]
+    Root,
+    #[error("Illegal move: {0:?}")]
+    IllegalMove(ChessMove),
+    #[error("Invalid san: {0}")]
+    InvalidSan(#[from] SanParsingError),
+    #[error("Invalid move: {0}")]
+    InvalidMove(#[from] InvalidChessMoveError),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NodeId(usize);
+
+#[derive(Clone, Debug)]
+pub struct GameNode {
+    // None only for the root
+    chess_move: Option<ChessMove>,
+    san: Option<String>,
+    parent: Option<NodeId>,
+    // the first child continues the line, the rest are variations
+    children: Vec<NodeId>,
+    // moves from the start of the tree
+    ply: usize,
+    pub annotations: Annotations,
+}
+
+impl GameNode {
+    pub const fn chess_move(&self) -> Option<&ChessMove> {
+        self.chess_move.as_ref()
+    }
+    pub fn san(&self) -> Option<&str> {
+        self.san.as_deref()
+    }
+    pub const fn parent(&self) -> Option<NodeId> {
+        self.parent
+    }
+    pub fn children(&self) -> &[NodeId] {
+        &self.children
+    }
+    pub const fn ply(&self) -> usize {
+        self.ply
+    }
+}
+
+// a game with its variations, positions are rebuilt from the start by replaying the moves
+#[derive(Clone, Debug)]
+pub struct GameTree {
+    start: GameState,
+    // deleted nodes leave a gap so ids stay stable
+    nodes: Vec<Option<GameNode>>,
+}
+
+impl GameTree {
+    pub const ROOT: NodeId = NodeId(0);
+
+    pub fn new(start: GameState) -> Self {
+        Self {
+            start,
+            nodes: vec![Some(GameNode {
+                chess_move: None,
+                san: None,
+                parent: None,
+                children: vec![],
+                ply: 0,
+                annotations: Annotations::new(),
+            })],
+        }
+    }
+    // the moves in the history become the mainline from wherever the game started, falling back
+    // to the position itself when the history doesn't lead to it
+    pub fn from_game_state(game_state: &GameState) -> Self {
+        let Some((start, plies)) = replay_whole_game(game_state) else {
+            return Self::new(game_state.clone());
+        };
+        let mut tree = Self::new(start);
+        let mut node = Self::ROOT;
+        for (game_state, chess_move) in plies {
+            let san = serialize_san(&game_state, &chess_move);
+            node = tree.push_node(node, chess_move, san);
+        }
+        tree
+    }
+    pub const fn start(&self) -> &GameState {
+        &self.start
+    }
+    pub fn node(&self, id: NodeId) -> Option<&GameNode> {
+        self.nodes.get(id.0).and_then(Option::as_ref)
+    }
+    pub fn node_mut(&mut self, id: NodeId) -> Option<&mut GameNode> {
+        self.nodes.get_mut(id.0).and_then(Option::as_mut)
+    }
+    fn get(&self, id: NodeId) -> Result<&GameNode, GameTreeError> {
+        self.node(id).ok_or(GameTreeError::UnknownNode(id))
+    }
+    pub fn annotations_mut(&mut self, id: NodeId) -> Option<&mut Annotations> {
+        self.node_mut(id).map(|node| &mut node.annotations)
+    }
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id).and_then(GameNode::parent)
+    }
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        self.node(id).map(GameNode::children).unwrap_or_default()
+    }
+    // the main continuation after a node
+    pub fn next(&self, id: NodeId) -> Option<NodeId> {
+        self.children(id).first().copied()
+    }
+    // the other moves played from the same position
+    pub fn siblings(&self, id: NodeId) -> Vec<NodeId> {
+        self.parent(id)
+            .map(|parent| {
+                self.children(parent)
+                    .iter()
+                    .copied()
+                    .filter(|sibling| *sibling != id)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+    // the nodes that follow by always taking the main continuation
+    pub fn line_from(&self, id: NodeId) -> Vec<NodeId> {
+        let mut line = vec![];
+        let mut node = id;
+        while let Some(next) = self.next(node) {
+            line.push(next);
+            node = next;
+        }
+        line
+    }
+    pub fn mainline(&self) -> Vec<NodeId> {
+        self.line_from(Self::ROOT)
+    }
+    // the nodes leading to id, the root left out
+    pub fn path(&self, id: NodeId) -> Vec<NodeId> {
+        let mut path = vec![];
+        let mut node = Some(id).filter(|id| self.node(*id).is_some());
+        while let Some(id) = node.filter(|id| *id != Self::ROOT) {
+            path.push(id);
+            node = self.parent(id);
+        }
+        path.reverse();
+        path
+    }
+    pub fn is_mainline(&self, id: NodeId) -> bool {
+        self.node(id).is_some()
+            && self
+                .path(id)
+                .iter()
+                .all(|node| self.siblings_before(*node) == 0)
+    }
+    fn siblings_before(&self, id: NodeId) -> usize {
+        self.parent(id)
+            .and_then(|parent| self.children(parent).iter().position(|child| *child == id))
+            .unwrap_or(0)
+    }
+    fn push_node(&mut self, parent: NodeId, chess_move: ChessMove, san: String) -> NodeId {
+        let id = NodeId(self.nodes.len());
+        let ply = self.nodes[parent.0].as_ref().map_or(0, |node| node.ply + 1);
+        self.nodes.push(Some(GameNode {
+            chess_move: Some(chess_move),
+            san: Some(san),
+            parent: Some(parent),
+            children: vec![],
+            ply,
+            annotations: Annotations::new(),
+        }));
+        if let Some(parent) = self.nodes[parent.0].as_mut() {
+            parent.children.push(id);
+        }
+        id
+    }
+    // game_state is the position at parent, a move that is already there is reused,
+    // returns the new node and the position after the move
+    pub(crate) fn insert_move(
+        &mut self,
+        parent: NodeId,
+        game_state: &GameState,
+        chess_move: &ChessMove,
+    ) -> Result<(NodeId, GameState), GameTreeError> {
+        self.get(parent)?;
+        if !legal_moves_with_status(game_state)
+            .iter()
+            .any(|(legal_move, _)| legal_move == chess_move)
+        {
+            return Err(GameTreeError::IllegalMove(chess_move.clone()));
+        }
+        let mut next_game_state = game_state.clone();
+        default_chess_move_handler(&mut next_game_state, chess_move, None)?;
+        let existing = self.children(parent).iter().copied().find(|child| {
+            self.node(*child)
+                .and_then(GameNode::chess_move)
+                .is_some_and(|child_move| child_move == chess_move)
+        });
+        let id = match existing {
+            Some(id) => id,
+            None => {
+                let san = serialize_san(game_state, chess_move);
+                self.push_node(parent, chess_move.clone(), san)
+            }
+        };
+        Ok((id, next_game_state))
+    }
+    pub(crate) fn insert_san(
+        &mut self,
+        parent: NodeId,
+        game_state: &GameState,
+        san: &str,
+    ) -> Result<(NodeId, GameState), GameTreeError> {
+        let chess_move = deserialize_san(game_state, san)?;
+        self.insert_move(parent, game_state, &chess_move)
+    }
+    // adds the move as the main continuation if parent has none, otherwise as a variation
+    pub fn add_move(
+        &mut self,
+        parent: NodeId,
+        chess_move: &ChessMove,
+    ) -> Result<NodeId, GameTreeError> {
+        let game_state = self.game_state(parent)?;
+        Ok(self.insert_move(parent, &game_state, chess_move)?.0)
+    }
+    pub fn add_san(&mut self, parent: NodeId, san: &str) -> Result<NodeId, GameTreeError> {
+        let game_state = self.game_state(parent)?;
+        Ok(self.insert_san(parent, &game_state, san)?.0)
+    }
+    // moves a variation one place up among its siblings, the first one becomes the main continuation
+    pub fn promote_variation(&mut self, id: NodeId) -> Result<(), GameTreeError> {
+        let parent = self.get(id)?.parent.ok_or(GameTreeError::Root)?;
+        let ix = self.siblings_before(id);
+        if let Some(parent) = self.node_mut(parent) {
+            parent.children.swap(ix.saturating_sub(1), ix);
+        }
+        Ok(())
+    }
+    // makes every move leading to id the main continuation
+    pub fn promote_to_mainline(&mut self, id: NodeId) -> Result<(), GameTreeError> {
+        self.get(id)?.parent.ok_or(GameTreeError::Root)?;
+        for node in self.path(id) {
+            let ix = self.siblings_before(node);
+            let parent = self.parent(node).expect("only the root has no parent");
+            if let Some(parent) = self.node_mut(parent) {
+                parent.children[..=ix].rotate_right(1);
+            }
+        }
+        Ok(())
+    }
+    // removes the move at id and everything after it
+    pub fn delete_branch(&mut self, id: NodeId) -> Result<(), GameTreeError> {
+        let parent = self.get(id)?.parent.ok_or(GameTreeError::Root)?;
+        if let Some(parent) = self.node_mut(parent) {
+            parent.children.retain(|child| *child != id);
+        }
+        let mut stack = vec![id];
+        while let Some(id) = stack.pop() {
+            if let Some(node) = self.nodes[id.0].take() {
+                stack.extend(node.children);
+            }
+        }
+        Ok(())
+    }
+    // the position after the move at id
+    pub fn game_state(&self, id: NodeId) -> Result<GameState, GameTreeError> {
+        self.get(id)?;
+        let mut game_state = self.start.clone();
+        for node in self.path(id) {
+            let chess_move = self
+                .get(node)?
+                .chess_move()
+                .expect("only the root has no move");
+            default_chess_move_handler(&mut game_state, chess_move, None)?;
+        }
+        Ok(game_state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::fen::{deserialize, serialize};
+    use crate::notation::san::SanParsingError;
+    use rstest::rstest;
+
+    fn sans(tree: &GameTree, line: &[NodeId]) -> Vec<String> {
+        line.iter()
+            .map(|id| {
+                tree.node(*id)
+                    .and_then(GameNode::san)
+                    .expect("move")
+                    .to_string()
+            })
+            .collect()
+    }
+
+    fn add_line(tree: &mut GameTree, from: NodeId, line: &[&str]) -> NodeId {
+        line.iter().fold(from, |node, san| {
+            tree.add_san(node, san).expect("legal move")
+        })
+    }
+
+    // 1. e4 e5 (1... c5 2. Nf3) (1... e6) 2. Nf3
+    fn sample_tree() -> (GameTree, NodeId, NodeId, NodeId) {
+        let mut tree = GameTree::new(GameState::new());
+        let e4 = add_line(&mut tree, GameTree::ROOT, &["e4"]);
+        add_line(&mut tree, e4, &["e5", "Nf3"]);
+        let c5 = tree.add_san(e4, "c5").expect("legal move");
+        let nf3 = tree.add_san(c5, "Nf3").expect("legal move");
+        let e6 = tree.add_san(e4, "e6").expect("legal move");
+        (tree, c5, nf3, e6)
+    }
+
+    #[test]
+    fn test_navigation() {
+        let (tree, c5, nf3, e6) = sample_tree();
+        let mainline = tree.mainline();
+        assert_eq!(vec!["e4", "e5", "Nf3"], sans(&tree, &mainline));
+        assert_eq!(
+            vec!["e5", "c5", "e6"],
+            sans(&tree, tree.children(mainline[0]))
+        );
+        assert_eq!(Some(nf3), tree.next(c5));
+        assert_eq!(Some(mainline[0]), tree.parent(c5));
+        assert_eq!(vec![mainline[1], c5], tree.siblings(e6));
+        assert_eq!(vec!["e4", "c5", "Nf3"], sans(&tree, &tree.path(nf3)));
+        assert_eq!(3, tree.node(nf3).expect("node").ply());
+        assert!(tree.is_mainline(mainline[2]));
+        assert!(!tree.is_mainline(nf3));
+    }
+
+    #[test]
+    fn test_add_existing_move() {
+        let (mut tree, c5, ..) = sample_tree();
+        let e4 = tree.mainline()[0];
+        assert_eq!(Ok(c5), tree.add_san(e4, "c5"));
+        assert_eq!(3, tree.children(e4).len());
+    }
+
+    #[rstest]
+    #[case("e5", GameTreeError::InvalidSan(SanParsingError::IllegalMove("e5".to_string())))]
+    #[case("Ke2", GameTreeError::InvalidSan(SanParsingError::IllegalMove("Ke2".to_string())))]
+    fn test_add_illegal_move(#[case] san: &str, #[case] expected: GameTreeError) {
+        let mut tree = GameTree::new(GameState::new());
+        assert_eq!(Err(expected), tree.add_san(GameTree::ROOT, san));
+        assert_eq!(
+            Err(GameTreeError::UnknownNode(NodeId(7))),
+            tree.add_san(NodeId(7), "e4")
+        );
+    }
+
+    #[test]
+    fn test_promote_variation() {
+        let (mut tree, c5, nf3, e6) = sample_tree();
+        let e4 = tree.mainline()[0];
+        tree.promote_variation(e6).expect("promoted");
+        assert_eq!(vec!["e5", "e6", "c5"], sans(&tree, tree.children(e4)));
+        tree.promote_variation(c5).expect("promoted");
+        tree.promote_variation(c5).expect("promoted");
+        assert_eq!(vec!["c5", "e5", "e6"], sans(&tree, tree.children(e4)));
+        assert_eq!(vec!["e4", "c5", "Nf3"], sans(&tree, &tree.mainline()));
+        assert_eq!(
+            Err(GameTreeError::Root),
+            tree.promote_variation(GameTree::ROOT)
+        );
+        assert!(tree.is_mainline(nf3));
+    }
+
+    #[test]
+    fn test_promote_to_mainline() {
+        let (mut tree, c5, ..) = sample_tree();
+        let d5 = add_line(&mut tree, GameTree::ROOT, &["d4", "d5"]);
+        add_line(&mut tree, d5, &["c4", "c6"]);
+        let nc3 = tree.add_san(d5, "Nc3").expect("legal move");
+        tree.promote_to_mainline(nc3).expect("promoted");
+        assert_eq!(vec!["d4", "d5", "Nc3"], sans(&tree, &tree.mainline()));
+        assert_eq!(
+            vec!["e4", "c5", "Nf3"],
+            sans(&tree, &tree.path(tree.next(c5).expect("next")))
+        );
+    }
+
+    #[test]
+    fn test_delete_branch() {
+        let (mut tree, c5, nf3, _) = sample_tree();
+        let e4 = tree.mainline()[0];
+        tree.delete_branch(c5).expect("deleted");
+        assert_eq!(vec!["e5", "e6"], sans(&tree, tree.children(e4)));
+        assert!(tree.node(c5).is_none());
+        assert!(tree.node(nf3).is_none());
+        assert_eq!(
+            Err(GameTreeError::UnknownNode(nf3)),
+            tree.game_state(nf3).map(|_| ())
+        );
+        tree.delete_branch(e4).expect("deleted");
+        assert!(tree.mainline().is_empty());
+        assert_eq!(Err(GameTreeError::Root), tree.delete_branch(GameTree::ROOT));
+    }
+
+    #[test]
+    fn test_game_state() {
+        let (mut tree, _, nf3, _) = sample_tree();
+        let game_state = tree.game_state(nf3).expect("legal moves");
+        assert_eq!(
+            "rnbqkbnr/pp1ppppp/8/2p5/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2",
+            serialize(&game_state).get_str()
+        );
+        assert_eq!(3, game_state.history.move_history.len());
+        let round_trip = GameTree::from_game_state(&game_state);
+        assert_eq!(
+            vec!["e4", "c5", "Nf3"],
+            sans(&round_trip, &round_trip.mainline())
+        );
+        let fen = "4k3/8/8/8/8/8/8/4K2R w K - 0 40";
+        let mut from_fen = GameTree::from_game_state(&deserialize(fen).expect("bad fen string!"));
+        let castle = from_fen.add_san(GameTree::ROOT, "O-O").expect("legal move");
+        assert_eq!(
+            "4k3/8/8/8/8/8/8/5RK1 b - - 1 40",
+            serialize(&from_fen.game_state(castle).expect("legal move")).get_str()
+        );
+        // a game from a fen keeps its moves
+        let played = GameTree::from_game_state(&from_fen.game_state(castle).expect("legal move"));
+        assert_eq!(fen, serialize(played.start()).get_str());
+        assert_eq!(vec!["O-O"], sans(&played, &played.mainline()));
+        tree.annotations_mut(nf3).expect("node").nags.push(1);
+        assert_eq!(vec![1], tree.node(nf3).expect("node").annotations.nags);
+    }
+}
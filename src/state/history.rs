@@ -1,12 +1,31 @@
 use crate::board::board::Board;
+use crate::chess_move::chess_move::ChessMove;
+use crate::chess_move::chess_move_handler::default_chess_move_handler;
+use crate::chess_move::invalid_chess_move_error::InvalidChessMoveError;
+use crate::color::Color;
+use crate::notation::fen::deserialize::FenParsingError;
+use crate::state::game_state::GameState;
 use crate::state::move_history_entry::MoveHistoryEntry;
 use crate::state::state_history::StateHistoryContainer;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone)]
+pub enum HistoryReplayError {
+    #[error("Invalid starting FEN: {0}")]
+    InvalidFen(FenParsingError),
+    #[error("Invalid move in history: {0}")]
+    InvalidMove(#[from] InvalidChessMoveError),
+}
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct History {
     pub move_history: Vec<MoveHistoryEntry>,
     // most moves in history 269
     pub state_history: Option<StateHistoryContainer>,
+    // the position the moves were played from, None for the standard starting position
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub start_fen: Option<String>,
 }
 
 impl History {
@@ -14,6 +33,7 @@ impl History {
         Self {
             move_history: Vec::new(),
             state_history: None,
+            start_fen: None,
         }
     }
     pub const fn new() -> Self {
@@ -22,6 +42,66 @@ impl History {
             state_history: Some(StateHistoryContainer::New(
                 Board::new().as_bit_boards_const(),
             )),
+            start_fen: None,
+        }
+    }
+    pub fn start_game_state(&self) -> Result<GameState, FenParsingError> {
+        match &self.start_fen {
+            Some(fen) => crate::notation::fen::deserialize(fen),
+            None => Ok(GameState::new()),
         }
     }
+    // every position from the start paired with the move played from it
+    pub fn replay(&self) -> Result<Vec<(GameState, ChessMove)>, HistoryReplayError> {
+        let mut game_state = self
+            .start_game_state()
+            .map_err(HistoryReplayError::InvalidFen)?;
+        let mut plies = vec![];
+        for entry in self.move_history.iter() {
+            let chess_move = ChessMove {
+                move_type: entry.move_type,
+                piece: entry.piece,
+                from: entry.from,
+                to: entry.to,
+                captured_piece: entry.capture,
+            };
+            let game_state_before = game_state.clone();
+            default_chess_move_handler(&mut game_state, &chess_move, None)?;
+            plies.push((game_state_before, chess_move));
+        }
+        Ok(plies)
+    }
+}
+
+const fn plies(game_state: &GameState) -> usize {
+    (game_state.move_counter.full_move as usize).saturating_sub(1) * 2
+        + matches!(game_state.active_color, Color::Black) as usize
+}
+
+// positions put together by hand (or past the final move) have no history to walk back through
+pub fn history_covers_whole_game(game_state: &GameState) -> bool {
+    game_state.history.start_game_state().is_ok_and(|start| {
+        plies(&start) + game_state.history.move_history.len() == plies(game_state)
+    })
+}
+
+// the start of the game and the moves played from it, None unless replaying them leads to game_state
+pub fn replay_whole_game(
+    game_state: &GameState,
+) -> Option<(GameState, Vec<(GameState, ChessMove)>)> {
+    if !history_covers_whole_game(game_state) {
+        return None;
+    }
+    let start = game_state.history.start_game_state().ok()?;
+    let plies = game_state.history.replay().ok()?;
+    let end = match plies.last() {
+        Some((last, chess_move)) => {
+            let mut end = last.clone();
+            default_chess_move_handler(&mut end, chess_move, None).ok()?;
+            end
+        }
+        None => start.clone(),
+    };
+    let fen = crate::notation::fen::serialize;
+    (fen(&end).get_str() == fen(game_state).get_str()).then_some((start, plies))
 }
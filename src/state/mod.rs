@@ -1,9 +1,13 @@
+pub mod annotations;
 pub mod castle_rights;
+pub mod chess960;
 pub mod color_castle_rights;
 pub mod evaluate_game_state;
 pub mod game_state;
 pub mod game_status;
+pub mod game_tree;
 pub mod history;
 pub mod move_counter;
 pub mod move_history_entry;
 pub mod state_history;
+pub mod validate;
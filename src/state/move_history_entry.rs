@@ -3,6 +3,7 @@ use crate::chess_move::chess_move::{ChessMove, ChessMoveType};
 use crate::piece::chess_piece::ChessPiece;
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MoveHistoryEntry {
     pub move_type: ChessMoveType,
     pub piece: ChessPiece,
@@ -2,9 +2,35 @@ use crate::bit_board::full_color_piece_bit_board::FullColorPieceBitBoard;
 use std::collections::HashMap;
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StateHistoryContainer {
     New(FullColorPieceBitBoard),
-    Hash(HashMap<FullColorPieceBitBoard, u8>),
+    Hash(
+        #[cfg_attr(feature = "serde", serde(with = "map_as_pairs"))]
+        HashMap<FullColorPieceBitBoard, u8>,
+    ),
+}
+
+// bit board keys aren't strings, so formats like json need the map as a list of pairs
+#[cfg(feature = "serde")]
+mod map_as_pairs {
+    use crate::bit_board::full_color_piece_bit_board::FullColorPieceBitBoard;
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::collections::HashMap;
+
+    pub fn serialize<S: Serializer>(
+        map: &HashMap<FullColorPieceBitBoard, u8>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(map.iter())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<HashMap<FullColorPieceBitBoard, u8>, D::Error> {
+        let pairs = Vec::<(FullColorPieceBitBoard, u8)>::deserialize(deserializer)?;
+        Ok(pairs.into_iter().collect())
+    }
 }
 
 impl StateHistoryContainer {
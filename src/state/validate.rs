@@ -0,0 +1,233 @@
+use crate::board::board_file::BoardFile;
+use crate::board::board_position::BoardPosition;
+use crate::board::board_rank::BoardRank;
+use crate::color::Color;
+use crate::direction::castle_side::CastleSide;
+use crate::piece::chess_piece::ChessPiece;
+use crate::piece::piece::Piece;
+use crate::state::game_state::GameState;
+use crate::state::game_status::{is_check, GameStatus};
+use thiserror::Error;
+
+#[derive(Error, Debug, Copy, Clone, PartialEq)]
+pub enum PositionError {
+    #[error("Pawn on back rank: {0}")]
+    PawnOnBackRank(BoardPosition),
+    #[error("Missing {0:?} king")]
+    MissingKing(Color),
+    #[error("Too many {0:?} kings: {1}")]
+    TooManyKings(Color, usize),
+    #[error("{0:?} is in check but it isn't their move")]
+    InactiveSideInCheck(Color),
+    #[error("{0:?} has castle rights without a king on its back rank")]
+    CastleRightsWithoutKing(Color),
+    #[error("{0:?} has {1:?} side castle rights without a rook on its home square")]
+    CastleRightsWithoutRook(Color, CastleSide),
+    #[error("{0:?} has {1:?} side castle rights with the rook on the other side of the king")]
+    CastleRightsRookOnWrongSide(Color, CastleSide),
+    #[error("Invalid en passant target: {0}")]
+    InvalidEnPassant(BoardPosition),
+}
+
+fn validate_pawns(game_state: &GameState, errors: &mut Vec<PositionError>) {
+    for (pos, maybe_piece) in game_state.board.as_iter() {
+        let Some(chess_piece) = maybe_piece else {
+            continue;
+        };
+        if chess_piece.as_piece() == Piece::Pawn
+            && matches!(pos.rank(), BoardRank::One | BoardRank::Eight)
+        {
+            errors.push(PositionError::PawnOnBackRank(pos));
+        }
+    }
+}
+
+fn validate_kings(game_state: &GameState, errors: &mut Vec<PositionError>) {
+    for color in [Color::White, Color::Black] {
+        let king = ChessPiece::from(color, Piece::King);
+        let count = game_state
+            .board
+            .as_iter()
+            .filter(|(_, maybe_piece)| *maybe_piece == Some(king))
+            .count();
+        match count {
+            0 => errors.push(PositionError::MissingKing(color)),
+            1 => {}
+            count => errors.push(PositionError::TooManyKings(color, count)),
+        }
+    }
+}
+
+fn validate_inactive_side_not_in_check(game_state: &GameState, errors: &mut Vec<PositionError>) {
+    let inactive_color = game_state.active_color.as_inverse();
+    let mut inactive_game_state = game_state.clone();
+    inactive_game_state.active_color = inactive_color;
+    // the stored status describes the side to move, not the side being checked here
+    inactive_game_state.game_status = GameStatus::InProgress;
+    if is_check(&inactive_game_state) {
+        errors.push(PositionError::InactiveSideInCheck(inactive_color));
+    }
+}
+
+fn validate_castle_rights(game_state: &GameState, errors: &mut Vec<PositionError>) {
+    for color in [Color::White, Color::Black] {
+        let Some(castle_rights) = game_state.castle_rights.for_color(color) else {
+            continue;
+        };
+        let rank = color.back_rank();
+        // the king can start on any file in chess960
+        let king = ChessPiece::from(color, Piece::King);
+        let king_file = (0..8)
+            .filter_map(|ix| BoardFile::from_zero_based_index(ix).ok())
+            .find(|file| *game_state.board.get(BoardPosition(*file, rank)) == Some(king));
+        if king_file.is_none() {
+            errors.push(PositionError::CastleRightsWithoutKing(color));
+        }
+        for castle_side in [CastleSide::King, CastleSide::Queen] {
+            let Some(rook_file) = castle_rights.rook_file(castle_side) else {
+                continue;
+            };
+            let rook_home = BoardPosition(rook_file, rank);
+            if *game_state.board.get(rook_home) != Some(ChessPiece::from(color, Piece::Rook)) {
+                errors.push(PositionError::CastleRightsWithoutRook(color, castle_side));
+                continue;
+            }
+            let is_on_side = king_file.is_none_or(|king_file| match castle_side {
+                CastleSide::King => rook_file > king_file,
+                CastleSide::Queen => rook_file < king_file,
+            });
+            if !is_on_side {
+                errors.push(PositionError::CastleRightsRookOnWrongSide(
+                    color,
+                    castle_side,
+                ));
+            }
+        }
+    }
+}
+
+// the target has to be the square a pawn of the side that just moved skipped over
+fn validate_en_passant(game_state: &GameState, errors: &mut Vec<PositionError>) {
+    let Some(target) = game_state.en_passant_target_pos else {
+        return;
+    };
+    let moved_color = game_state.active_color.as_inverse();
+    let (target_rank, origin_rank, pawn_rank) = match moved_color {
+        Color::White => (BoardRank::Three, BoardRank::Two, BoardRank::Four),
+        Color::Black => (BoardRank::Six, BoardRank::Seven, BoardRank::Five),
+    };
+    let BoardPosition(file, rank) = target;
+    let is_valid = rank == target_rank
+        && game_state.board.get(target).is_none()
+        && game_state
+            .board
+            .get(BoardPosition(file, origin_rank))
+            .is_none()
+        && *game_state.board.get(BoardPosition(file, pawn_rank))
+            == Some(ChessPiece::from(moved_color, Piece::Pawn));
+    if !is_valid {
+        errors.push(PositionError::InvalidEnPassant(target));
+    }
+}
+
+pub fn validate(game_state: &GameState) -> Result<(), Vec<PositionError>> {
+    let mut errors = vec![];
+    validate_pawns(game_state, &mut errors);
+    validate_kings(game_state, &mut errors);
+    validate_inactive_side_not_in_check(game_state, &mut errors);
+    validate_castle_rights(game_state, &mut errors);
+    validate_en_passant(game_state, &mut errors);
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::position::*;
+    use crate::notation::fen::{deserialize, FEN_STARTING_POS};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(FEN_STARTING_POS)]
+    #[case("rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2")]
+    #[case("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1")]
+    #[case("4k3/8/8/8/8/8/8/4K2R w K - 0 1")]
+    #[case("4k3/8/8/8/8/8/8/3QK3 b - - 0 1")]
+    // chess960 kings off the e-file
+    #[case("bqnbrkrn/pppppppp/8/8/8/8/PPPPPPPP/BQNBRKRN w KQkq - 0 1")]
+    #[case("4k3/8/8/8/8/8/8/RK5R w HA - 0 1")]
+    #[case("1r4kr/8/8/8/8/8/8/4K3 b hb - 0 1")]
+    fn test_validate_legal_position(#[case] fen: &str) {
+        let game_state = deserialize(fen).expect("bad fen string!");
+        assert_eq!(Ok(()), validate(&game_state));
+    }
+
+    #[rstest]
+    #[case("4k3/8/8/8/8/8/8/P3K3 w - - 0 1", vec![PositionError::PawnOnBackRank(A1)])]
+    #[case("p3k3/8/8/8/8/8/8/4K3 w - - 0 1", vec![PositionError::PawnOnBackRank(A8)])]
+    #[case("8/8/8/8/8/8/8/4K3 w - - 0 1", vec![PositionError::MissingKing(Color::Black)])]
+    #[case(
+        "4k3/8/8/8/8/8/8/K3K3 b - - 0 1",
+        vec![PositionError::TooManyKings(Color::White, 2)]
+    )]
+    #[case(
+        "4k3/8/8/8/8/8/8/4Q1K1 w - - 0 1",
+        vec![PositionError::InactiveSideInCheck(Color::Black)]
+    )]
+    #[case(
+        "4k3/8/8/8/8/8/4K3/7R w K - 0 1",
+        vec![PositionError::CastleRightsWithoutKing(Color::White)]
+    )]
+    #[case(
+        "r3k3/8/8/8/8/8/8/4K3 w kq - 0 1",
+        vec![PositionError::CastleRightsWithoutRook(Color::Black, CastleSide::King)]
+    )]
+    #[case("4k3/8/8/8/8/8/8/4K3 w - e6 0 1", vec![PositionError::InvalidEnPassant(E6)])]
+    #[case("4k3/8/8/4p3/8/8/8/4K3 w - e3 0 1", vec![PositionError::InvalidEnPassant(E3)])]
+    fn test_validate_illegal_position(#[case] fen: &str, #[case] expected: Vec<PositionError>) {
+        let game_state = deserialize(fen).expect("bad fen string!");
+        assert_eq!(Err(expected), validate(&game_state));
+    }
+
+    #[rstest]
+    #[case(CastleSide::King, BoardFile::A)]
+    #[case(CastleSide::Queen, BoardFile::H)]
+    fn test_validate_castle_rook_on_wrong_side(
+        #[case] castle_side: CastleSide,
+        #[case] rook_file: BoardFile,
+    ) {
+        let mut game_state =
+            deserialize("4k3/8/8/8/8/8/8/R3K2R w - - 0 1").expect("bad fen string!");
+        game_state.castle_rights.white = Some(
+            crate::state::castle_rights::CastleRights::from_rook_file(castle_side, rook_file),
+        );
+        assert_eq!(
+            Err(vec![PositionError::CastleRightsRookOnWrongSide(
+                Color::White,
+                castle_side
+            )]),
+            validate(&game_state)
+        );
+    }
+
+    #[test]
+    fn test_validate_lists_every_error() {
+        let game_state = deserialize("P7/8/8/8/8/8/8/8 w KQ e3 0 1").expect("bad fen string!");
+        assert_eq!(
+            Err(vec![
+                PositionError::PawnOnBackRank(A8),
+                PositionError::MissingKing(Color::White),
+                PositionError::MissingKing(Color::Black),
+                PositionError::CastleRightsWithoutKing(Color::White),
+                PositionError::CastleRightsWithoutRook(Color::White, CastleSide::King),
+                PositionError::CastleRightsWithoutRook(Color::White, CastleSide::Queen),
+                PositionError::InvalidEnPassant(E3),
+            ]),
+            validate(&game_state)
+        );
+    }
+}
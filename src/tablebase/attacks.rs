@@ -0,0 +1,209 @@
+use crate::color::Color;
+use crate::piece::piece::Piece;
+
+// plain u64 square sets, the generator visits millions of positions so it can't afford the
+// allocation heavy move search used during play
+const KING_DELTAS: [(i8, i8); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+const KNIGHT_DELTAS: [(i8, i8); 8] = [
+    (-2, -1),
+    (-2, 1),
+    (-1, -2),
+    (-1, 2),
+    (1, -2),
+    (1, 2),
+    (2, -1),
+    (2, 1),
+];
+const ROOK_DIRECTIONS: [(i8, i8); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+const BISHOP_DIRECTIONS: [(i8, i8); 4] = [(-1, -1), (-1, 1), (1, -1), (1, 1)];
+
+pub const fn square_bit(square: u8) -> u64 {
+    1 << square
+}
+
+const fn offset_square(square: u8, (file_delta, rank_delta): (i8, i8)) -> Option<u8> {
+    let file = (square % 8) as i8 + file_delta;
+    let rank = (square / 8) as i8 + rank_delta;
+    if file < 0 || file > 7 || rank < 0 || rank > 7 {
+        return None;
+    }
+    Some((rank * 8 + file) as u8)
+}
+
+const fn leaper_attacks(deltas: [(i8, i8); 8]) -> [u64; 64] {
+    let mut attacks = [0; 64];
+    let mut square = 0;
+    while square < 64 {
+        let mut ix = 0;
+        while ix < deltas.len() {
+            if let Some(target) = offset_square(square as u8, deltas[ix]) {
+                attacks[square] |= square_bit(target);
+            }
+            ix += 1;
+        }
+        square += 1;
+    }
+    attacks
+}
+
+const KING_ATTACKS: [u64; 64] = leaper_attacks(KING_DELTAS);
+const KNIGHT_ATTACKS: [u64; 64] = leaper_attacks(KNIGHT_DELTAS);
+
+fn sliding_attacks(square: u8, occupied: u64, directions: &[(i8, i8)]) -> u64 {
+    let mut attacks = 0;
+    for direction in directions {
+        let mut current = square;
+        while let Some(target) = offset_square(current, *direction) {
+            attacks |= square_bit(target);
+            if occupied & square_bit(target) != 0 {
+                break;
+            }
+            current = target;
+        }
+    }
+    attacks
+}
+
+const fn pawn_direction(color: Color) -> i8 {
+    match color {
+        Color::White => 1,
+        Color::Black => -1,
+    }
+}
+
+pub const fn is_promotion_rank(color: Color, square: u8) -> bool {
+    match color {
+        Color::White => square >= 56,
+        Color::Black => square < 8,
+    }
+}
+
+const fn is_pawn_start_rank(color: Color, square: u8) -> bool {
+    match color {
+        Color::White => square / 8 == 1,
+        Color::Black => square / 8 == 6,
+    }
+}
+
+// squares attacked by a piece, for pawns that's only the capture squares
+pub fn piece_attacks(piece: Piece, color: Color, square: u8, occupied: u64) -> u64 {
+    match piece {
+        Piece::King => KING_ATTACKS[square as usize],
+        Piece::Knight => KNIGHT_ATTACKS[square as usize],
+        Piece::Bishop => sliding_attacks(square, occupied, &BISHOP_DIRECTIONS),
+        Piece::Rook => sliding_attacks(square, occupied, &ROOK_DIRECTIONS),
+        Piece::Queen => {
+            sliding_attacks(square, occupied, &BISHOP_DIRECTIONS)
+                | sliding_attacks(square, occupied, &ROOK_DIRECTIONS)
+        }
+        Piece::Pawn => [-1, 1]
+            .into_iter()
+            .filter_map(|file_delta| offset_square(square, (file_delta, pawn_direction(color))))
+            .fold(0, |attacks, target| attacks | square_bit(target)),
+    }
+}
+
+pub fn pawn_pushes(color: Color, square: u8, occupied: u64) -> u64 {
+    let Some(single) = offset_square(square, (0, pawn_direction(color))) else {
+        return 0;
+    };
+    if occupied & square_bit(single) != 0 {
+        return 0;
+    }
+    let mut pushes = square_bit(single);
+    if is_pawn_start_rank(color, square) {
+        if let Some(double) = offset_square(single, (0, pawn_direction(color))) {
+            if occupied & square_bit(double) == 0 {
+                pushes |= square_bit(double);
+            }
+        }
+    }
+    pushes
+}
+
+// the squares a pawn standing on square could have been pushed from
+pub fn pawn_unpushes(color: Color, square: u8, occupied: u64) -> u64 {
+    let backwards = -pawn_direction(color);
+    let Some(single) = offset_square(square, (0, backwards)) else {
+        return 0;
+    };
+    if occupied & square_bit(single) != 0 || is_promotion_rank(color.as_inverse(), single) {
+        return 0;
+    }
+    let mut unpushes = square_bit(single);
+    if let Some(double) = offset_square(single, (0, backwards)) {
+        if is_pawn_start_rank(color, double) && occupied & square_bit(double) == 0 {
+            unpushes |= square_bit(double);
+        }
+    }
+    unpushes
+}
+
+pub fn iter_squares(mut bits: u64) -> impl Iterator<Item = u8> {
+    std::iter::from_fn(move || {
+        if bits == 0 {
+            return None;
+        }
+        let square = bits.trailing_zeros() as u8;
+        bits &= bits - 1;
+        Some(square)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(Piece::King, 0, 0, 3)]
+    #[case(Piece::King, 27, 0, 8)]
+    #[case(Piece::Knight, 0, 0, 2)]
+    #[case(Piece::Knight, 27, 0, 8)]
+    #[case(Piece::Rook, 0, 0, 14)]
+    #[case(Piece::Rook, 0, square_bit(8) | square_bit(1), 2)]
+    #[case(Piece::Bishop, 27, 0, 13)]
+    #[case(Piece::Queen, 27, 0, 27)]
+    #[case(Piece::Pawn, 8, 0, 1)]
+    #[case(Piece::Pawn, 12, 0, 2)]
+    fn test_piece_attacks(
+        #[case] piece: Piece,
+        #[case] square: u8,
+        #[case] occupied: u64,
+        #[case] expected: u32,
+    ) {
+        assert_eq!(
+            expected,
+            piece_attacks(piece, Color::White, square, occupied).count_ones()
+        );
+    }
+
+    #[test]
+    fn test_pawn_pushes() {
+        // e2 can go to e3 and e4, e3 only came from e2
+        assert_eq!(
+            square_bit(20) | square_bit(28),
+            pawn_pushes(Color::White, 12, 0)
+        );
+        assert_eq!(0, pawn_pushes(Color::White, 12, square_bit(20)));
+        assert_eq!(square_bit(12), pawn_unpushes(Color::White, 20, 0));
+        assert_eq!(
+            square_bit(20) | square_bit(12),
+            pawn_unpushes(Color::White, 28, 0)
+        );
+        assert_eq!(
+            square_bit(44) | square_bit(52),
+            pawn_unpushes(Color::Black, 36, 0)
+        );
+        assert_eq!(0, pawn_unpushes(Color::White, 12, 0));
+    }
+}
@@ -0,0 +1,173 @@
+use crate::color::Color;
+use crate::piece::chess_piece::ChessPiece;
+use crate::piece::piece::Piece;
+use crate::state::game_state::GameState;
+use crate::tablebase::tablebase::TablebaseError;
+use std::fmt::{Display, Formatter};
+
+pub const MAX_TABLEBASE_PIECES: usize = 4;
+
+const PROMOTION_PIECES: [Piece; 4] = [Piece::Queen, Piece::Rook, Piece::Bishop, Piece::Knight];
+
+// every piece on the board including both kings, sorted so that the same material always
+// maps to the same table, e.g. KQvK
+#[derive(Clone, Debug, PartialEq)]
+pub struct Material {
+    pieces: Vec<ChessPiece>,
+}
+
+fn sorted(mut pieces: Vec<ChessPiece>) -> Vec<ChessPiece> {
+    pieces.sort_by_key(|piece| *piece as u8);
+    pieces
+}
+
+impl Material {
+    pub fn from_pieces(pieces: Vec<ChessPiece>) -> Result<Self, TablebaseError> {
+        let material = Self {
+            pieces: sorted(pieces),
+        };
+        let king_count = |color: Color| {
+            material
+                .pieces
+                .iter()
+                .filter(|piece| **piece == ChessPiece::from(color, Piece::King))
+                .count()
+        };
+        if king_count(Color::White) != 1 || king_count(Color::Black) != 1 {
+            return Err(TablebaseError::InvalidMaterial(material.to_string()));
+        }
+        if material.pieces.len() > MAX_TABLEBASE_PIECES {
+            return Err(TablebaseError::TooManyPieces(material.pieces.len()));
+        }
+        Ok(material)
+    }
+    pub fn from_name(name: &str) -> Result<Self, TablebaseError> {
+        let invalid = || TablebaseError::InvalidMaterial(name.to_string());
+        let (white, black) = name.split_once('v').ok_or_else(invalid)?;
+        let mut pieces = vec![];
+        for (color, side) in [(Color::White, white), (Color::Black, black)] {
+            for c in side.chars() {
+                let piece = Piece::from_char(c).map_err(|_| invalid())?;
+                pieces.push(ChessPiece::from(color, piece));
+            }
+        }
+        Self::from_pieces(pieces)
+    }
+    pub fn from_game_state(game_state: &GameState) -> Result<Self, TablebaseError> {
+        Self::from_pieces(
+            game_state
+                .board
+                .as_iter()
+                .filter_map(|(_, maybe_piece)| maybe_piece)
+                .collect(),
+        )
+    }
+    pub fn pieces(&self) -> &[ChessPiece] {
+        &self.pieces
+    }
+    pub fn len(&self) -> usize {
+        self.pieces.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.pieces.is_empty()
+    }
+    pub fn name(&self) -> String {
+        self.to_string()
+    }
+    // the materials reachable by a capture or a promotion, these have to be solved first
+    pub fn sub_materials(&self) -> Vec<Material> {
+        let mut sub_materials: Vec<Material> = vec![];
+        for (ix, piece) in self.pieces.iter().enumerate() {
+            let mut candidates = vec![];
+            if piece.as_piece() != Piece::King {
+                let mut pieces = self.pieces.clone();
+                pieces.remove(ix);
+                candidates.push(pieces);
+            }
+            if piece.as_piece() == Piece::Pawn {
+                for promotion_piece in PROMOTION_PIECES {
+                    let mut pieces = self.pieces.clone();
+                    pieces[ix] = ChessPiece::from(piece.as_color(), promotion_piece);
+                    candidates.push(pieces);
+                }
+            }
+            for pieces in candidates {
+                let material = Self {
+                    pieces: sorted(pieces),
+                };
+                if !sub_materials.contains(&material) {
+                    sub_materials.push(material);
+                }
+            }
+        }
+        sub_materials
+    }
+}
+
+impl Display for Material {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for color in [Color::White, Color::Black] {
+            if color == Color::Black {
+                write!(f, "v")?;
+            }
+            for piece in self.pieces.iter().filter(|piece| piece.as_color() == color) {
+                write!(f, "{}", piece.as_piece().as_char())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::fen::deserialize;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("KQvK")]
+    #[case("KvKR")]
+    #[case("KBNvK")]
+    #[case("KPvKP")]
+    fn test_material_name_round_trip(#[case] name: &'static str) {
+        assert_eq!(
+            name,
+            Material::from_name(name).expect("valid material").name()
+        );
+    }
+
+    #[rstest]
+    #[case("KQK")]
+    #[case("KQvQ")]
+    #[case("KQRvKR")]
+    #[case("KXvK")]
+    fn test_invalid_material(#[case] name: &'static str) {
+        assert!(Material::from_name(name).is_err());
+    }
+
+    #[test]
+    fn test_sub_materials() {
+        let names = |material: &str| {
+            Material::from_name(material)
+                .expect("valid material")
+                .sub_materials()
+                .iter()
+                .map(Material::name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(vec!["KvK"], names("KQvK"));
+        assert_eq!(vec!["KvK", "KQvK", "KRvK", "KBvK", "KNvK"], names("KPvK"));
+        assert_eq!(vec!["KvKR", "KQvK"], names("KQvKR"));
+    }
+
+    #[test]
+    fn test_material_from_game_state() {
+        let game_state = deserialize("8/8/8/4k3/8/8/1q6/4K2R w - - 0 1").expect("bad fen string!");
+        assert_eq!(
+            "KRvKQ",
+            Material::from_game_state(&game_state)
+                .expect("valid material")
+                .name()
+        );
+    }
+}
@@ -0,0 +1,4 @@
+pub mod attacks;
+pub mod material;
+pub mod tablebase;
+pub mod tablebase_generator;
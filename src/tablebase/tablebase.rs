@@ -0,0 +1,411 @@
+use crate::chess_move::chess_move::ChessMove;
+use crate::chess_move::chess_move_handler::try_handle_chess_move;
+use crate::chess_move::chess_move_search::unchecked_chess_move_search;
+use crate::state::game_state::GameState;
+use crate::tablebase::material::Material;
+use crate::tablebase::tablebase_generator::{
+    generate_table, is_valid_index, position_index, table_size,
+};
+use std::collections::HashMap;
+use std::path::Path;
+use thiserror::Error;
+
+pub const TABLEBASE_FILE_EXTENSION: &str = "rctb";
+const TABLEBASE_MAGIC: &[u8; 4] = b"RCTB";
+const TABLEBASE_VERSION: u8 = 1;
+pub(crate) const INVALID_BYTE: u8 = 0;
+pub(crate) const DRAW_BYTE: u8 = 1;
+const PLIES_OFFSET: u8 = 2;
+
+#[derive(Error, Debug)]
+pub enum TablebaseError {
+    #[error("Failed to read tablebase: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid tablebase material: {0}")]
+    InvalidMaterial(String),
+    #[error("Too many pieces for a tablebase: {0}")]
+    TooManyPieces(usize),
+    #[error("Corrupt tablebase file: {0}")]
+    Corrupt(&'static str),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Wdl {
+    Loss,
+    Draw,
+    Win,
+}
+
+// from the point of view of the side to move, wins and losses count plies until mate
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TablebaseValue {
+    Win(u8),
+    Draw,
+    Loss(u8),
+}
+
+impl TablebaseValue {
+    // the side to move wins after an odd number of plies and gets mated after an even number
+    pub const fn from_plies(plies: u8) -> Self {
+        if plies % 2 == 1 {
+            Self::Win(plies)
+        } else {
+            Self::Loss(plies)
+        }
+    }
+    pub const fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            INVALID_BYTE => None,
+            DRAW_BYTE => Some(Self::Draw),
+            _ => Some(Self::from_plies(byte - PLIES_OFFSET)),
+        }
+    }
+    pub const fn as_byte(&self) -> u8 {
+        match self {
+            Self::Win(plies) | Self::Loss(plies) => *plies + PLIES_OFFSET,
+            Self::Draw => DRAW_BYTE,
+        }
+    }
+    pub const fn wdl(&self) -> Wdl {
+        match self {
+            Self::Win(_) => Wdl::Win,
+            Self::Draw => Wdl::Draw,
+            Self::Loss(_) => Wdl::Loss,
+        }
+    }
+    pub const fn moves_to_mate(&self) -> Option<u8> {
+        match self {
+            Self::Win(plies) => Some(*plies / 2 + 1),
+            Self::Loss(plies) => Some(*plies / 2),
+            Self::Draw => None,
+        }
+    }
+}
+
+fn write_var_u32(bytes: &mut Vec<u8>, mut value: u32) {
+    while value >= 0x80 {
+        bytes.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+fn read_var_u32(bytes: &mut impl Iterator<Item = u8>) -> Option<u32> {
+    let mut value = 0u32;
+    for shift in (0..35).step_by(7) {
+        let byte = bytes.next()?;
+        value |= ((byte & 0x7F) as u32) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+#[derive(Clone, Debug)]
+pub struct TablebaseTable {
+    material: Material,
+    values: Vec<u8>,
+}
+
+impl TablebaseTable {
+    pub(crate) fn new(material: Material, values: Vec<u8>) -> Self {
+        Self { material, values }
+    }
+    pub fn material(&self) -> &Material {
+        &self.material
+    }
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+    pub fn value_at(&self, index: usize) -> Option<TablebaseValue> {
+        TablebaseValue::from_byte(*self.values.get(index)?)
+    }
+    pub fn probe(&self, game_state: &GameState) -> Option<TablebaseValue> {
+        let pieces = game_state
+            .board
+            .as_iter()
+            .filter_map(|(pos, maybe_piece)| {
+                maybe_piece.map(|piece| (piece, pos.as_pos_index() as u8))
+            })
+            .collect::<Vec<_>>();
+        if Material::from_game_state(game_state).ok()? != self.material {
+            return None;
+        }
+        self.value_at(position_index(&pieces, game_state.active_color))
+    }
+    pub fn longest_mate(&self) -> u8 {
+        self.values
+            .iter()
+            .filter_map(|byte| TablebaseValue::from_byte(*byte))
+            .filter_map(|value| match value {
+                TablebaseValue::Win(_) => value.moves_to_mate(),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0)
+    }
+    // runs of equal values are stored as value + varint length. Illegal positions can join any
+    // run since they are recomputed when loading
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let name = self.material.name();
+        let mut bytes = TABLEBASE_MAGIC.to_vec();
+        bytes.push(TABLEBASE_VERSION);
+        bytes.push(name.len() as u8);
+        bytes.extend_from_slice(name.as_bytes());
+        let mut values = self.values.iter().copied().peekable();
+        while let Some(mut value) = values.next() {
+            let mut run = 1u32;
+            while let Some(next) = values
+                .next_if(|next| *next == value || *next == INVALID_BYTE || value == INVALID_BYTE)
+            {
+                if value == INVALID_BYTE {
+                    value = next;
+                }
+                run += 1;
+            }
+            bytes.push(value);
+            write_var_u32(&mut bytes, run);
+        }
+        bytes
+    }
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TablebaseError> {
+        let Some(rest) = bytes.strip_prefix(TABLEBASE_MAGIC) else {
+            return Err(TablebaseError::Corrupt("missing magic"));
+        };
+        let [version, name_len, rest @ ..] = rest else {
+            return Err(TablebaseError::Corrupt("missing header"));
+        };
+        if *version != TABLEBASE_VERSION {
+            return Err(TablebaseError::Corrupt("unsupported version"));
+        }
+        let (name, rest) = rest
+            .split_at_checked(*name_len as usize)
+            .ok_or(TablebaseError::Corrupt("missing material"))?;
+        let name =
+            std::str::from_utf8(name).map_err(|_| TablebaseError::Corrupt("bad material"))?;
+        let material = Material::from_name(name)?;
+        let size = table_size(material.len());
+        let mut values = Vec::with_capacity(size);
+        let mut bytes = rest.iter().copied();
+        while let Some(value) = bytes.next() {
+            let run = read_var_u32(&mut bytes).ok_or(TablebaseError::Corrupt("truncated run"))?;
+            values.resize(values.len() + run as usize, value);
+        }
+        if values.len() != size {
+            return Err(TablebaseError::Corrupt("wrong number of positions"));
+        }
+        for (index, value) in values.iter_mut().enumerate() {
+            if !is_valid_index(&material, index) {
+                *value = INVALID_BYTE;
+            }
+        }
+        Ok(Self::new(material, values))
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Tablebase {
+    tables: HashMap<String, TablebaseTable>,
+}
+
+impl Tablebase {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn len(&self) -> usize {
+        self.tables.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.tables.is_empty()
+    }
+    pub fn table(&self, name: &str) -> Option<&TablebaseTable> {
+        self.tables.get(name)
+    }
+    pub fn insert(&mut self, table: TablebaseTable) {
+        self.tables.insert(table.material.name(), table);
+    }
+    // also generates every smaller table the material can reach through captures and promotions
+    pub fn generate(&mut self, material: &Material) -> &TablebaseTable {
+        let name = material.name();
+        if !self.tables.contains_key(&name) {
+            for sub_material in material.sub_materials() {
+                self.generate(&sub_material);
+            }
+            let table = generate_table(material, &self.tables);
+            self.tables.insert(name.clone(), table);
+        }
+        &self.tables[&name]
+    }
+    pub fn generate_by_name(&mut self, name: &str) -> Result<&TablebaseTable, TablebaseError> {
+        let material = Material::from_name(name)?;
+        Ok(self.generate(&material))
+    }
+    pub fn open(path: impl AsRef<Path>) -> Result<TablebaseTable, TablebaseError> {
+        TablebaseTable::from_bytes(&std::fs::read(path)?)
+    }
+    pub fn load_dir(dir: impl AsRef<Path>) -> Result<Self, TablebaseError> {
+        let mut tablebase = Self::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path
+                .extension()
+                .is_some_and(|ext| ext == TABLEBASE_FILE_EXTENSION)
+            {
+                tablebase.insert(Self::open(&path)?);
+            }
+        }
+        Ok(tablebase)
+    }
+    pub fn save_dir(&self, dir: impl AsRef<Path>) -> Result<(), TablebaseError> {
+        std::fs::create_dir_all(dir.as_ref())?;
+        for (name, table) in self.tables.iter() {
+            let path = dir
+                .as_ref()
+                .join(format!("{name}.{TABLEBASE_FILE_EXTENSION}"));
+            std::fs::write(path, table.to_bytes())?;
+        }
+        Ok(())
+    }
+    // the tables know nothing about castling or en passant
+    pub fn probe(&self, game_state: &GameState) -> Option<TablebaseValue> {
+        if game_state.castle_rights.white.is_some()
+            || game_state.castle_rights.black.is_some()
+            || game_state.en_passant_target_pos.is_some()
+        {
+            return None;
+        }
+        let material = Material::from_game_state(game_state).ok()?;
+        self.tables.get(&material.name())?.probe(game_state)
+    }
+    // wins as fast as possible, loses as slowly as possible
+    pub fn best_move(&self, game_state: &GameState) -> Option<(ChessMove, TablebaseValue)> {
+        self.probe(game_state)?;
+        unchecked_chess_move_search(game_state, None)
+            .into_iter()
+            .filter_map(|chess_move| {
+                let new_game_state = try_handle_chess_move(game_state, &chess_move, None).ok()?;
+                let value = match self.probe(&new_game_state)? {
+                    TablebaseValue::Loss(plies) => TablebaseValue::Win(plies + 1),
+                    TablebaseValue::Draw => TablebaseValue::Draw,
+                    TablebaseValue::Win(plies) => TablebaseValue::Loss(plies + 1),
+                };
+                Some((chess_move, value))
+            })
+            .max_by_key(|(_, value)| match value {
+                TablebaseValue::Win(plies) => (2, -(*plies as i32)),
+                TablebaseValue::Draw => (1, 0),
+                TablebaseValue::Loss(plies) => (0, *plies as i32),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::position::*;
+    use crate::notation::fen::deserialize;
+    use rstest::rstest;
+    use std::sync::OnceLock;
+
+    static TABLEBASE: OnceLock<Tablebase> = OnceLock::new();
+
+    fn generated() -> &'static Tablebase {
+        TABLEBASE.get_or_init(|| {
+            let mut tablebase = Tablebase::new();
+            for name in ["KQvK", "KRvK", "KvKQ", "KPvK"] {
+                tablebase.generate_by_name(name).expect("valid material");
+            }
+            tablebase
+        })
+    }
+
+    #[rstest]
+    #[case("KQvK", 10)]
+    #[case("KRvK", 16)]
+    #[case("KvKQ", 10)]
+    #[case("KBvK", 0)]
+    #[case("KNvK", 0)]
+    fn test_longest_mate(#[case] name: &'static str, #[case] expected: u8) {
+        let tablebase = generated();
+        assert_eq!(
+            expected,
+            tablebase.table(name).expect("generated").longest_mate()
+        );
+    }
+
+    #[test]
+    #[ignore = "generating a four piece table takes minutes in debug builds"]
+    fn test_longest_mate_kbnk() {
+        let mut tablebase = Tablebase::new();
+        let table = tablebase.generate_by_name("KBNvK").expect("valid material");
+        assert_eq!(33, table.longest_mate());
+        // Bg7 mates in the corner of the bishop's color
+        let game_state = deserialize("7k/8/5NKB/8/8/8/8/8 w - - 0 1").expect("bad fen string!");
+        assert_eq!(Some(TablebaseValue::Win(1)), table.probe(&game_state));
+    }
+
+    #[rstest]
+    #[case("7k/8/6K1/8/8/8/8/1Q6 w - - 0 1", Some(TablebaseValue::Win(1)))]
+    #[case("7k/8/6K1/8/8/8/8/1Q6 b - - 0 1", Some(TablebaseValue::Loss(2)))]
+    // the side not to move is in check
+    #[case("7k/8/6K1/8/8/8/8/Q7 w - - 0 1", None)]
+    #[case("Q6k/8/6K1/8/8/8/8/8 b - - 0 1", Some(TablebaseValue::Loss(0)))]
+    // black takes the queen
+    #[case("6k1/6Q1/8/8/8/8/8/K7 b - - 0 1", Some(TablebaseValue::Draw))]
+    #[case("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1", Some(TablebaseValue::Draw))]
+    #[case("7k/8/6K1/8/8/8/8/1Q6 w K - 0 1", None)]
+    #[case("7k/8/6K1/8/8/8/8/1R6 w - - 0 1", Some(TablebaseValue::Win(1)))]
+    #[case("7k/8/6K1/8/8/8/8/QR6 w - - 0 1", None)]
+    fn test_probe(#[case] fen: &'static str, #[case] expected: Option<TablebaseValue>) {
+        let tablebase = generated();
+        let game_state = deserialize(fen).expect("bad fen string!");
+        assert_eq!(expected, tablebase.probe(&game_state));
+    }
+
+    #[rstest]
+    // king on the sixth in front of the pawn wins no matter who moves
+    #[case("4k3/8/4K3/4P3/8/8/8/8 w - - 0 1", Wdl::Win)]
+    #[case("4k3/8/4K3/4P3/8/8/8/8 b - - 0 1", Wdl::Loss)]
+    #[case("4k3/4P3/4K3/8/8/8/8/8 b - - 0 1", Wdl::Draw)]
+    #[case("8/8/8/8/8/8/4P3/k3K3 b - - 0 1", Wdl::Loss)]
+    // the rook pawn can't be won when the defending king reaches the corner
+    #[case("7k/8/8/6KP/8/8/8/8 w - - 0 1", Wdl::Draw)]
+    fn test_probe_kpk(#[case] fen: &'static str, #[case] expected: Wdl) {
+        let tablebase = generated();
+        let game_state = deserialize(fen).expect("bad fen string!");
+        assert_eq!(
+            Some(expected),
+            tablebase.probe(&game_state).map(|value| value.wdl())
+        );
+    }
+
+    #[test]
+    fn test_best_move_mates() {
+        let tablebase = generated();
+        let game_state = deserialize("7k/8/6K1/8/8/8/8/1Q6 w - - 0 1").expect("bad fen string!");
+        let (best_move, value) = tablebase.best_move(&game_state).expect("in tablebase");
+        assert_eq!(TablebaseValue::Win(1), value);
+        assert_eq!(B8, best_move.to);
+    }
+
+    #[test]
+    fn test_save_and_load() -> Result<(), TablebaseError> {
+        let tablebase = generated();
+        let dir = std::env::temp_dir().join(format!("rust-chess-tablebase-{}", std::process::id()));
+        tablebase.save_dir(&dir)?;
+        let loaded = Tablebase::load_dir(&dir)?;
+        std::fs::remove_dir_all(&dir)?;
+        assert_eq!(tablebase.len(), loaded.len());
+        let table = tablebase.table("KRvK").expect("generated");
+        let loaded_table = loaded.table("KRvK").expect("loaded");
+        assert_eq!(table.values, loaded_table.values);
+        // illegal and drawn positions make up most of the table
+        assert!(table.to_bytes().len() < table.len() / 4);
+        assert!(TablebaseTable::from_bytes(&table.to_bytes()[..20]).is_err());
+        Ok(())
+    }
+}
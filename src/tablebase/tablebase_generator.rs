@@ -0,0 +1,450 @@
+use crate::color::Color;
+use crate::piece::chess_piece::ChessPiece;
+use crate::piece::piece::Piece;
+use crate::tablebase::attacks::{
+    is_promotion_rank, iter_squares, pawn_pushes, pawn_unpushes, piece_attacks, square_bit,
+};
+use crate::tablebase::material::{Material, MAX_TABLEBASE_PIECES};
+use crate::tablebase::tablebase::{TablebaseTable, TablebaseValue, DRAW_BYTE, INVALID_BYTE};
+use std::collections::HashMap;
+
+const PROMOTION_PIECES: [Piece; 4] = [Piece::Queen, Piece::Rook, Piece::Bishop, Piece::Knight];
+const UNRESOLVED: u8 = u8::MAX;
+const EXIT_DRAW: u8 = 1;
+const EXIT_WIN: u8 = 2;
+const NO_SLOT: usize = usize::MAX;
+
+type Squares = [u8; MAX_TABLEBASE_PIECES];
+
+pub const fn table_size(piece_count: usize) -> usize {
+    2 * (1 << (6 * piece_count))
+}
+
+const fn side_index(color: Color) -> usize {
+    match color {
+        Color::White => 0,
+        Color::Black => 1,
+    }
+}
+
+// pawnless positions look the same mirrored or rotated in 8 ways, pawns only allow the
+// left-right mirror
+const fn symmetry_count(has_pawns: bool) -> u8 {
+    if has_pawns {
+        2
+    } else {
+        8
+    }
+}
+
+const fn transform_square(square: u8, symmetry: u8) -> u8 {
+    let (mut file, mut rank) = (square % 8, square / 8);
+    if symmetry & 1 != 0 {
+        file = 7 - file;
+    }
+    if symmetry & 2 != 0 {
+        rank = 7 - rank;
+    }
+    if symmetry & 4 != 0 {
+        (file, rank) = (rank, file);
+    }
+    rank * 8 + file
+}
+
+// the side to move is the highest part followed by the squares in the order of the material's
+// pieces, so the white king's square splits the table into large blocks
+fn encode_index(squares: &[u8], side: Color, symmetry: u8) -> usize {
+    squares.iter().fold(side_index(side), |index, square| {
+        index * 64 + transform_square(*square, symmetry) as usize
+    })
+}
+
+// every symmetric image of a position shares the index of the smallest one
+fn canonical_index(squares: &[u8], side: Color, has_pawns: bool) -> usize {
+    (0..symmetry_count(has_pawns))
+        .map(|symmetry| encode_index(squares, side, symmetry))
+        .min()
+        .expect("at least one symmetry")
+}
+
+fn decode_index(mut index: usize, piece_count: usize) -> (Squares, Color) {
+    let side = if index < table_size(piece_count) / 2 {
+        Color::White
+    } else {
+        Color::Black
+    };
+    let mut squares = [0; MAX_TABLEBASE_PIECES];
+    for square in squares.iter_mut().take(piece_count).rev() {
+        *square = (index % 64) as u8;
+        index /= 64;
+    }
+    (squares, side)
+}
+
+fn has_pawns(pieces: &[ChessPiece]) -> bool {
+    pieces.iter().any(|piece| piece.as_piece() == Piece::Pawn)
+}
+
+// pieces can come in any order, identical pieces may end up in either slot
+pub fn position_index(pieces: &[(ChessPiece, u8)], side: Color) -> usize {
+    let mut pieces = pieces.to_vec();
+    pieces.sort_by_key(|(piece, _)| *piece as u8);
+    let squares = pieces.iter().map(|(_, square)| *square).collect::<Vec<_>>();
+    let has_pawns = pieces
+        .iter()
+        .any(|(piece, _)| piece.as_piece() == Piece::Pawn);
+    canonical_index(&squares, side, has_pawns)
+}
+
+fn occupancy(squares: &[u8], skip: usize) -> u64 {
+    squares
+        .iter()
+        .enumerate()
+        .filter(|(slot, _)| *slot != skip)
+        .fold(0, |occupied, (_, square)| occupied | square_bit(*square))
+}
+
+fn is_attacked(pieces: &[ChessPiece], squares: &[u8], target: u8, by: Color, skip: usize) -> bool {
+    let occupied = occupancy(squares, skip);
+    pieces
+        .iter()
+        .zip(squares.iter())
+        .enumerate()
+        .any(|(slot, (piece, square))| {
+            slot != skip
+                && piece.as_color() == by
+                && piece_attacks(piece.as_piece(), by, *square, occupied) & square_bit(target) != 0
+        })
+}
+
+fn king_slot(pieces: &[ChessPiece], color: Color) -> usize {
+    let king = ChessPiece::from(color, Piece::King);
+    pieces
+        .iter()
+        .position(|piece| *piece == king)
+        .expect("material always has both kings")
+}
+
+fn is_in_check(pieces: &[ChessPiece], squares: &[u8], color: Color) -> bool {
+    let king_square = squares[king_slot(pieces, color)];
+    is_attacked(pieces, squares, king_square, color.as_inverse(), NO_SLOT)
+}
+
+fn is_valid(pieces: &[ChessPiece], squares: &[u8], side: Color) -> bool {
+    if occupancy(squares, NO_SLOT).count_ones() as usize != squares.len() {
+        return false;
+    }
+    let pawn_on_back_rank = pieces.iter().zip(squares.iter()).any(|(piece, square)| {
+        piece.as_piece() == Piece::Pawn
+            && (is_promotion_rank(Color::White, *square)
+                || is_promotion_rank(Color::Black, *square))
+    });
+    !pawn_on_back_rank && !is_in_check(pieces, squares, side.as_inverse())
+}
+
+// only the canonical image of each position is stored, the rest of the table is unused
+pub fn is_valid_index(material: &Material, index: usize) -> bool {
+    let pieces = material.pieces();
+    let (squares, side) = decode_index(index, pieces.len());
+    let squares = &squares[..pieces.len()];
+    is_valid(pieces, squares, side) && canonical_index(squares, side, has_pawns(pieces)) == index
+}
+
+// a capture and/or promotion leaves the table, this maps our slots onto the smaller table's slots
+struct ExitTable<'a> {
+    table: &'a TablebaseTable,
+    slots: Vec<usize>,
+    has_pawns: bool,
+}
+
+impl ExitTable<'_> {
+    fn value(&self, squares: &[u8], side: Color) -> Option<TablebaseValue> {
+        let mut sub_squares = [0; MAX_TABLEBASE_PIECES];
+        for (sub_square, slot) in sub_squares.iter_mut().zip(self.slots.iter()) {
+            *sub_square = squares[*slot];
+        }
+        let index = canonical_index(&sub_squares[..self.slots.len()], side, self.has_pawns);
+        self.table.value_at(index)
+    }
+}
+
+type ExitKey = (usize, Option<(usize, u8)>);
+
+fn exit_tables<'a>(
+    pieces: &[ChessPiece],
+    sub_tables: &'a HashMap<String, TablebaseTable>,
+) -> HashMap<ExitKey, ExitTable<'a>> {
+    let mut keys: Vec<(usize, Option<(usize, Piece)>)> = vec![];
+    let captures = (0..pieces.len())
+        .filter(|slot| pieces[*slot].as_piece() != Piece::King)
+        .chain([NO_SLOT]);
+    for captured in captures {
+        if captured != NO_SLOT {
+            keys.push((captured, None));
+        }
+        for (slot, piece) in pieces.iter().enumerate() {
+            if piece.as_piece() == Piece::Pawn && slot != captured {
+                for promotion_piece in PROMOTION_PIECES {
+                    keys.push((captured, Some((slot, promotion_piece))));
+                }
+            }
+        }
+    }
+    keys.into_iter()
+        .filter_map(|(captured, promotion)| {
+            let mut remaining = pieces
+                .iter()
+                .enumerate()
+                .filter(|(slot, _)| *slot != captured)
+                .map(|(slot, piece)| match promotion {
+                    Some((promoted, promotion_piece)) if promoted == slot => {
+                        (ChessPiece::from(piece.as_color(), promotion_piece), slot)
+                    }
+                    _ => (*piece, slot),
+                })
+                .collect::<Vec<_>>();
+            remaining.sort_by_key(|(piece, _)| *piece as u8);
+            let material =
+                Material::from_pieces(remaining.iter().map(|(piece, _)| *piece).collect()).ok()?;
+            let exit_table = ExitTable {
+                table: sub_tables.get(&material.name())?,
+                slots: remaining.iter().map(|(_, slot)| *slot).collect(),
+                has_pawns: has_pawns(material.pieces()),
+            };
+            let promotion = promotion.map(|(slot, piece)| (slot, piece as u8));
+            Some(((captured, promotion), exit_table))
+        })
+        .collect()
+}
+
+enum MoveOutcome {
+    InTable(Squares),
+    // the squares after the move with the captured and promoted slots
+    Exit(Squares, usize, Option<(usize, u8)>),
+}
+
+fn for_each_legal_move(
+    pieces: &[ChessPiece],
+    squares: &[u8],
+    side: Color,
+    mut f: impl FnMut(MoveOutcome),
+) {
+    let occupied = occupancy(squares, NO_SLOT);
+    let own = pieces
+        .iter()
+        .zip(squares.iter())
+        .filter(|(piece, _)| piece.as_color() == side)
+        .fold(0u64, |own, (_, square)| own | square_bit(*square));
+    let enemy = occupied & !own;
+    let king_slot = king_slot(pieces, side);
+    for (slot, piece) in pieces.iter().enumerate() {
+        if piece.as_color() != side {
+            continue;
+        }
+        let from = squares[slot];
+        let targets = match piece.as_piece() {
+            Piece::Pawn => {
+                pawn_pushes(side, from, occupied)
+                    | piece_attacks(Piece::Pawn, side, from, occupied) & enemy
+            }
+            moving_piece => piece_attacks(moving_piece, side, from, occupied) & !own,
+        };
+        for to in iter_squares(targets) {
+            let captured = squares
+                .iter()
+                .position(|square| *square == to)
+                .unwrap_or(NO_SLOT);
+            let mut new_squares = [0; MAX_TABLEBASE_PIECES];
+            new_squares[..squares.len()].copy_from_slice(squares);
+            new_squares[slot] = to;
+            let new_squares_slice = &new_squares[..squares.len()];
+            let king_square = new_squares_slice[king_slot];
+            if is_attacked(
+                pieces,
+                new_squares_slice,
+                king_square,
+                side.as_inverse(),
+                captured,
+            ) {
+                continue;
+            }
+            let is_promotion = piece.as_piece() == Piece::Pawn && is_promotion_rank(side, to);
+            if !is_promotion {
+                match captured {
+                    NO_SLOT => f(MoveOutcome::InTable(new_squares)),
+                    _ => f(MoveOutcome::Exit(new_squares, captured, None)),
+                }
+                continue;
+            }
+            for promotion_piece in PROMOTION_PIECES {
+                f(MoveOutcome::Exit(
+                    new_squares,
+                    captured,
+                    Some((slot, promotion_piece as u8)),
+                ));
+            }
+        }
+    }
+}
+
+// positions where the side that just moved could have come from with a quiet move
+fn for_each_predecessor(
+    pieces: &[ChessPiece],
+    squares: &[u8],
+    side: Color,
+    mut f: impl FnMut(usize),
+) {
+    let mover = side.as_inverse();
+    let occupied = occupancy(squares, NO_SLOT);
+    for (slot, piece) in pieces.iter().enumerate() {
+        if piece.as_color() != mover {
+            continue;
+        }
+        let to = squares[slot];
+        let origins = match piece.as_piece() {
+            Piece::Pawn => pawn_unpushes(mover, to, occupied),
+            moving_piece => piece_attacks(moving_piece, mover, to, occupied) & !occupied,
+        };
+        for from in iter_squares(origins) {
+            let mut previous_squares = [0; MAX_TABLEBASE_PIECES];
+            previous_squares[..squares.len()].copy_from_slice(squares);
+            previous_squares[slot] = from;
+            let previous_squares = &previous_squares[..squares.len()];
+            if !is_in_check(pieces, previous_squares, side) {
+                f(canonical_index(previous_squares, mover, has_pawns(pieces)));
+            }
+        }
+    }
+}
+
+fn push_bucket(buckets: &mut Vec<Vec<u32>>, level: usize, index: usize) {
+    if buckets.len() <= level {
+        buckets.resize_with(level + 1, Vec::new);
+    }
+    buckets[level].push(index as u32);
+}
+
+// retrograde analysis: mates are found first, then positions are resolved in order of
+// increasing distance to mate by walking quiet moves backwards. Captures and promotions
+// are looked up in the already solved smaller tables.
+// en passant and castling are not part of the tables.
+pub fn generate_table(
+    material: &Material,
+    sub_tables: &HashMap<String, TablebaseTable>,
+) -> TablebaseTable {
+    let pieces = material.pieces();
+    let piece_count = pieces.len();
+    let has_pawns = has_pawns(pieces);
+    let size = table_size(piece_count);
+    let exit_tables = exit_tables(pieces, sub_tables);
+    let mut values = vec![INVALID_BYTE; size];
+    // distinct positions reachable with a quiet move that aren't known to be lost for the opponent
+    let mut remaining = vec![0u8; size];
+    let mut longest_loss = vec![0u8; size];
+    let mut exits = vec![0u8; size];
+    let mut buckets: Vec<Vec<u32>> = vec![];
+    let mut children = vec![];
+
+    for index in 0..size {
+        if !is_valid_index(material, index) {
+            continue;
+        }
+        let (squares, side) = decode_index(index, piece_count);
+        let squares = &squares[..piece_count];
+        values[index] = UNRESOLVED;
+        let mut move_count = 0;
+        let mut exit_win: Option<u8> = None;
+        children.clear();
+        for_each_legal_move(pieces, squares, side, |outcome| {
+            move_count += 1;
+            let (new_squares, captured, promotion) = match outcome {
+                MoveOutcome::InTable(new_squares) => {
+                    let new_squares = &new_squares[..piece_count];
+                    children.push(canonical_index(new_squares, side.as_inverse(), has_pawns));
+                    return;
+                }
+                MoveOutcome::Exit(new_squares, captured, promotion) => {
+                    (new_squares, captured, promotion)
+                }
+            };
+            let value = exit_tables
+                .get(&(captured, promotion))
+                .and_then(|exit_table| exit_table.value(&new_squares, side.as_inverse()))
+                .expect("sub tables are generated first");
+            match value {
+                TablebaseValue::Loss(plies) => {
+                    exit_win = Some(exit_win.map_or(plies + 1, |win| win.min(plies + 1)));
+                }
+                TablebaseValue::Draw => exits[index] |= EXIT_DRAW,
+                TablebaseValue::Win(plies) => longest_loss[index] = longest_loss[index].max(plies),
+            }
+        });
+        // symmetric moves lead to the same child, each child is only counted once
+        children.sort_unstable();
+        children.dedup();
+        remaining[index] = children.len() as u8;
+        if move_count == 0 {
+            if is_in_check(pieces, squares, side) {
+                push_bucket(&mut buckets, 0, index);
+            } else {
+                values[index] = DRAW_BYTE;
+            }
+        } else if let Some(exit_win) = exit_win {
+            exits[index] |= EXIT_WIN;
+            push_bucket(&mut buckets, exit_win as usize, index);
+        } else if remaining[index] == 0 {
+            if exits[index] & EXIT_DRAW != 0 {
+                values[index] = DRAW_BYTE;
+            } else {
+                push_bucket(&mut buckets, longest_loss[index] as usize + 1, index);
+            }
+        }
+    }
+
+    // wins are an odd number of plies from mate and losses an even number
+    let mut level = 0;
+    let mut predecessors = vec![];
+    while level < buckets.len() {
+        let bucket = std::mem::take(&mut buckets[level]);
+        for index in bucket {
+            let index = index as usize;
+            if values[index] != UNRESOLVED {
+                continue;
+            }
+            values[index] = TablebaseValue::from_plies(level as u8).as_byte();
+            let (squares, side) = decode_index(index, piece_count);
+            predecessors.clear();
+            for_each_predecessor(pieces, &squares[..piece_count], side, |predecessor| {
+                predecessors.push(predecessor)
+            });
+            predecessors.sort_unstable();
+            predecessors.dedup();
+            for predecessor in predecessors.iter().copied() {
+                if values[predecessor] != UNRESOLVED {
+                    continue;
+                }
+                if level % 2 == 0 {
+                    push_bucket(&mut buckets, level + 1, predecessor);
+                    continue;
+                }
+                remaining[predecessor] -= 1;
+                longest_loss[predecessor] = longest_loss[predecessor].max(level as u8);
+                if remaining[predecessor] == 0 && exits[predecessor] == 0 {
+                    push_bucket(
+                        &mut buckets,
+                        longest_loss[predecessor] as usize + 1,
+                        predecessor,
+                    );
+                }
+            }
+        }
+        level += 1;
+    }
+
+    for value in values.iter_mut() {
+        if *value == UNRESOLVED {
+            *value = DRAW_BYTE;
+        }
+    }
+    TablebaseTable::new(material.clone(), values)
+}
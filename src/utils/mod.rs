@@ -1,3 +1,4 @@
 pub mod custom_struct_iterator;
 pub mod hash;
+pub mod random;
 pub mod slice;
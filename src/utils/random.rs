@@ -0,0 +1,48 @@
+pub const fn split_mix_64(state: u64) -> (u64, u64) {
+    let state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    (state, z ^ (z >> 31))
+}
+
+// small seeded generator so anything random can be replayed from its seed
+#[derive(Clone, Debug)]
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub const fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+    pub fn next_u64(&mut self) -> u64 {
+        let (state, value) = split_mix_64(self.state);
+        self.state = state;
+        value
+    }
+    pub fn next_below(&mut self, bound: u64) -> u64 {
+        if bound == 0 {
+            return 0;
+        }
+        self.next_u64() % bound
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_random_is_reproducible() {
+        let mut a = Random::new(42);
+        let mut b = Random::new(42);
+        let mut c = Random::new(43);
+        let values_a = (0..8).map(|_| a.next_u64()).collect::<Vec<_>>();
+        let values_b = (0..8).map(|_| b.next_u64()).collect::<Vec<_>>();
+        let values_c = (0..8).map(|_| c.next_u64()).collect::<Vec<_>>();
+        assert_eq!(values_a, values_b);
+        assert_ne!(values_a, values_c);
+        assert!((0..100).all(|_| a.next_below(6) < 6));
+    }
+}
@@ -0,0 +1,163 @@
+use crate::chess_move::chess_move::ChessMove;
+use crate::chess_move::chess_move_handler::default_chess_move_handler;
+use crate::notation::coordinate::{deserialize_coordinate, serialize_coordinate};
+use crate::notation::fen::{deserialize_strict, serialize};
+use crate::notation::san::{deserialize_san, legal_moves_with_status, serialize_san};
+use crate::search::lazy_smp::single_threaded_search;
+use crate::search::search_limits::SearchLimits;
+use crate::search::search_options::SearchOptions;
+use crate::state::game_state::GameState;
+use crate::state::game_status::GameStatus;
+use wasm_bindgen::prelude::*;
+
+pub const DEFAULT_BOT_DEPTH: u8 = 3;
+
+#[wasm_bindgen]
+pub struct WasmGame {
+    game_state: GameState,
+}
+
+impl WasmGame {
+    fn play(&mut self, chess_move: &ChessMove) -> Result<(), JsError> {
+        default_chess_move_handler(&mut self.game_state, chess_move, None)
+            .map(|_| ())
+            .map_err(|err| JsError::new(&err.to_string()))
+    }
+}
+
+impl Default for WasmGame {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[wasm_bindgen]
+impl WasmGame {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self {
+            game_state: GameState::new(),
+        }
+    }
+    #[wasm_bindgen(js_name = fromFen)]
+    pub fn from_fen(fen: &str) -> Result<WasmGame, JsError> {
+        let mut game = Self::new();
+        game.load_fen(fen)?;
+        Ok(game)
+    }
+    #[wasm_bindgen(js_name = loadFen)]
+    pub fn load_fen(&mut self, fen: &str) -> Result<(), JsError> {
+        self.game_state = deserialize_strict(fen).map_err(|err| JsError::new(&err.to_string()))?;
+        Ok(())
+    }
+    pub fn fen(&self) -> String {
+        serialize(&self.game_state).get_str().to_string()
+    }
+    #[wasm_bindgen(js_name = legalMoves)]
+    pub fn legal_moves(&self) -> Vec<String> {
+        legal_moves_with_status(&self.game_state)
+            .iter()
+            .map(|(chess_move, _)| serialize_coordinate(chess_move))
+            .collect()
+    }
+    // accepts uci or san and returns the san of the move played
+    #[wasm_bindgen(js_name = makeMove)]
+    pub fn make_move(&mut self, input: &str) -> Result<String, JsError> {
+        let chess_move = deserialize_coordinate(&self.game_state, input)
+            .or_else(|_| deserialize_san(&self.game_state, input))
+            .map_err(|err| JsError::new(&err.to_string()))?;
+        let san = serialize_san(&self.game_state, &chess_move);
+        self.play(&chess_move)?;
+        Ok(san)
+    }
+    // one of new, in_progress, check, checkmate, stalemate, draw or time_forfeit
+    pub fn status(&self) -> String {
+        match self.game_state.game_status {
+            GameStatus::New => "new",
+            GameStatus::InProgress => "in_progress",
+            GameStatus::Check(_) => "check",
+            GameStatus::CheckMate(_) => "checkmate",
+            GameStatus::Stalemate => "stalemate",
+            GameStatus::TimeForfeit(_) => "time_forfeit",
+            GameStatus::Draw => "draw",
+        }
+        .to_string()
+    }
+    #[wasm_bindgen(js_name = activeColor)]
+    pub fn active_color(&self) -> String {
+        format!("{:?}", self.game_state.active_color).to_lowercase()
+    }
+    #[wasm_bindgen(js_name = isGameOver)]
+    pub fn is_game_over(&self) -> bool {
+        self.game_state.game_status.is_game_over()
+    }
+    // plays the bot's move and returns it as uci, depth defaults when neither limit is given
+    #[wasm_bindgen(js_name = botMove)]
+    pub fn bot_move(&mut self, depth: Option<u8>, nodes: Option<u32>) -> Result<String, JsError> {
+        let limits = SearchLimits {
+            depth: match (depth, nodes) {
+                (None, None) => Some(DEFAULT_BOT_DEPTH),
+                _ => depth,
+            },
+            nodes: nodes.map(u64::from),
+            ..SearchLimits::new()
+        };
+        let result = single_threaded_search(&self.game_state, limits, SearchOptions::selective());
+        let chess_move = result
+            .best_move
+            .ok_or_else(|| JsError::new("No legal moves available"))?;
+        self.play(&chess_move)?;
+        Ok(serialize_coordinate(&chess_move))
+    }
+}
+
+// JsError can only be built on wasm, so the error paths are only tested there
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::fen::FEN_STARTING_POS;
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+
+    #[test]
+    fn test_new_game() {
+        let game = WasmGame::new();
+        assert_eq!(FEN_STARTING_POS, game.fen());
+        assert_eq!(20, game.legal_moves().len());
+        assert!(game.legal_moves().contains(&"e2e4".to_string()));
+        assert_eq!("new", game.status());
+        assert_eq!("white", game.active_color());
+    }
+
+    #[test]
+    fn test_make_move() {
+        let mut game = WasmGame::new();
+        assert_eq!("e4", game.make_move("e2e4").ok().unwrap());
+        assert_eq!("Nf6", game.make_move("Nf6").ok().unwrap());
+        assert_eq!("white", game.active_color());
+        assert_eq!("in_progress", game.status());
+    }
+
+    #[test]
+    fn test_bot_move() {
+        let mut game = WasmGame::from_fen("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1")
+            .ok()
+            .unwrap();
+        assert_eq!("a1a8", game.bot_move(Some(2), None).ok().unwrap());
+        assert_eq!("checkmate", game.status());
+        assert!(game.is_game_over());
+        let mut game = WasmGame::new();
+        assert!(game.bot_move(None, Some(500)).is_ok());
+        assert_eq!("black", game.active_color());
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[test]
+    fn test_errors() {
+        let mut game = WasmGame::new();
+        assert!(game.make_move("e2e5").is_err());
+        assert!(game.load_fen("not a fen").is_err());
+        assert!(WasmGame::from_fen("4k3/8/8/8/8/8/8/8 w - - 0 1").is_err());
+        assert_eq!(FEN_STARTING_POS, game.fen());
+    }
+}